@@ -2,9 +2,20 @@ use actix_web::{web, HttpResponse, Responder};
 use sea_orm::DatabaseConnection;
 use std::sync::Arc;
 
-use crate::api::responses::{Category, PaginatedResponse, Post, PostMeta, PostType, RootResponse};
+use crate::api::auth::{ApiClient, PostPassword};
+use crate::api::responses::{
+    AcfContext, Author, Breadcrumb, Category, CategoryTreeNode, Comment, FeaturedMedia, Media,
+    Menu, MenuItem, MenuWithItems, NetworkSite, PageTreeNode, PaginatedResponse, Post, PostMeta,
+    PostType, Product, Resolved, Revision, RevisionDiff, RootResponse, SiteSettings, Taxonomy,
+    Term, Translation,
+};
+use crate::config::Config;
 use crate::db::queries;
 use crate::error::ApiError;
+use crate::models::post::{self, MetaCompare, MetaCondition, MetaFilter, ProductFilter};
+use crate::utils::formatting::{self, Formatter};
+use crate::utils::shortcodes::{self, GalleryImage, Registry, RenderContext, ShortcodeMode};
+use crate::utils::{blocks, seo};
 
 pub async fn root() -> impl Responder {
     let response = RootResponse {
@@ -16,9 +27,78 @@ pub async fn root() -> impl Responder {
     HttpResponse::Ok().json(response)
 }
 
+pub async fn get_site(
+    db: web::Data<Arc<DatabaseConnection>>,
+    config: web::Data<Config>,
+) -> Result<HttpResponse, ApiError> {
+    let options = queries::get_site_options(&db, &config.site.option_allowlist).await?;
+
+    let response = SiteSettings { options };
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+pub async fn get_network_sites(
+    query: web::Query<GetNetworkSitesQuery>,
+    db: web::Data<Arc<DatabaseConnection>>,
+    config: web::Data<Config>,
+) -> Result<HttpResponse, ApiError> {
+    if !config.site.multisite {
+        return Err(ApiError::NotFound("Multisite is not enabled".to_string()));
+    }
+
+    if let Some(page) = query.page {
+        if page == 0 {
+            return Err(ApiError::BadRequest(
+                "Page number must be greater than 0".to_string(),
+            ));
+        }
+    }
+
+    if let Some(page_size) = query.page_size {
+        if page_size == 0 {
+            return Err(ApiError::BadRequest(
+                "Page size must be greater than 0".to_string(),
+            ));
+        }
+        if page_size > 100 {
+            return Err(ApiError::BadRequest("Maximum page size is 100".to_string()));
+        }
+    }
+
+    let page = query.page.unwrap_or(1);
+    let page_size = query.page_size.unwrap_or(20).min(100);
+
+    let (sites, total) = queries::get_network_sites(&db, page, page_size).await?;
+
+    let site_responses: Vec<NetworkSite> = sites.into_iter().map(NetworkSite::from).collect();
+
+    let response = PaginatedResponse::new(site_responses, total, page, page_size);
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+pub async fn get_network_site(
+    path: web::Path<u64>,
+    db: web::Data<Arc<DatabaseConnection>>,
+    config: web::Data<Config>,
+) -> Result<HttpResponse, ApiError> {
+    if !config.site.multisite {
+        return Err(ApiError::NotFound("Multisite is not enabled".to_string()));
+    }
+
+    let blog_id = path.into_inner();
+
+    let site = queries::get_network_site(&db, blog_id).await?;
+
+    Ok(HttpResponse::Ok().json(NetworkSite::from(site)))
+}
+
 pub async fn get_posts(
     query: web::Query<GetPostsQuery>,
+    client: Option<ApiClient>,
     db: web::Data<Arc<DatabaseConnection>>,
+    config: web::Data<Config>,
 ) -> Result<HttpResponse, ApiError> {
     if let Some(page) = query.page {
         if page == 0 {
@@ -47,119 +127,1419 @@ pub async fn get_posts(
         }
     }
 
-    let post_status = if let Some(status) = &query.post_status {
-        let valid_statuses = [
-            "publish",
-            "draft",
-            "private",
-            "pending",
-            "future",
-            "trash",
-            "auto-draft",
-        ];
-        if !valid_statuses.contains(&status.as_str()) {
+    let post_status = Some(validate_status(
+        query.post_status.as_deref(),
+        client.as_ref(),
+    )?);
+
+    if let Some(sticky) = &query.sticky {
+        let valid_modes = ["only", "exclude", "first"];
+        if !valid_modes.contains(&sticky.as_str()) {
+            return Err(ApiError::BadRequest(format!(
+                "Invalid sticky mode: {}. Valid modes are: {}",
+                sticky,
+                valid_modes.join(", ")
+            )));
+        }
+    }
+
+    let post_type = query.post_type.clone();
+    let shortcode_mode = parse_shortcode_mode(query.shortcodes.as_deref())?;
+    let embed = parse_embed(query.embed.as_deref())?;
+    let meta = parse_meta_filter(
+        query.meta_key.as_deref(),
+        query.meta_value.as_deref(),
+        query.meta_compare.as_deref(),
+        query.meta_type.as_deref(),
+        query.meta_relation.as_deref(),
+    )?;
+
+    let page = query.page.unwrap_or(1);
+    let page_size = query.page_size.unwrap_or(10).min(100);
+    let search = query.search.clone();
+    let author_id = query.author_id;
+
+    let lang = queries::get_language_filter(&db, query.lang.as_deref()).await?;
+
+    let (posts, total) = queries::get_posts(
+        &db,
+        post_type,
+        post_status,
+        page,
+        page_size,
+        search,
+        author_id,
+        query.sticky.clone(),
+        meta.as_ref(),
+        lang.as_ref(),
+    )
+    .await?;
+
+    let post_responses =
+        build_post_responses(&db, &config, posts, shortcode_mode, embed, None).await?;
+
+    let response = PaginatedResponse::new(post_responses, total, page, page_size);
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+// Builds the `Post` responses for a page of posts, resolving everything that
+// is embedded in them with batched queries rather than one query per post.
+async fn build_post_responses(
+    db: &DatabaseConnection,
+    config: &Config,
+    posts: Vec<post::Model>,
+    shortcode_mode: ShortcodeMode,
+    embed: Embed,
+    password: Option<&PostPassword>,
+) -> Result<Vec<Post>, ApiError> {
+    let permalinks = queries::get_permalinks(db, &config.site.post_type_bases).await?;
+    let mut links = queries::get_post_links(db, &permalinks, &posts).await?;
+
+    let post_ids: Vec<u64> = posts.iter().map(|post| post.id).collect();
+    let mut featured_media = queries::get_featured_media(db, post_ids.clone()).await?;
+
+    let acf_values = queries::get_acf_values(db, post_ids.clone()).await?;
+
+    let mut post_terms = if embed.terms {
+        Some(queries::get_post_terms(db, post_ids).await?)
+    } else {
+        None
+    };
+
+    let uploads_url = if featured_media.is_empty()
+        && acf_values.as_ref().is_none_or(|acf| acf.media.is_empty())
+    {
+        String::new()
+    } else {
+        queries::get_uploads_url(db).await?
+    };
+
+    let (mut acf, acf_context) = match acf_values {
+        Some(acf_values) => {
+            // Links are only needed for posts the fields relate to.
+            let links = if acf_values.posts.is_empty() {
+                Default::default()
+            } else {
+                queries::get_post_links(db, &permalinks, &acf_values.posts).await?
+            };
+            let context = AcfContext::new(acf_values.media, acf_values.posts, links, &uploads_url);
+            (Some(acf_values.values), Some(context))
+        }
+        None => (None, None),
+    };
+
+    // Only posts can be made sticky in WordPress, but the option may still
+    // list IDs of posts that were later converted.
+    let sticky_ids = if posts.iter().any(|post| post.post_type == "post") {
+        queries::get_sticky_post_ids(db).await?
+    } else {
+        Vec::new()
+    };
+
+    let seo_settings = queries::get_seo_settings(db).await?;
+    let mut seo_meta = queries::get_seo_meta(
+        db,
+        &seo_settings,
+        posts.iter().map(|post| post.id).collect(),
+    )
+    .await?;
+
+    let mut responses: Vec<Post> = posts
+        .into_iter()
+        .map(|post| {
+            let featured = featured_media.remove(&post.id).map(|(attachment, meta)| {
+                FeaturedMedia::from(Media::from_attachment(attachment, meta, &uploads_url))
+            });
+
+            let permalink = links.remove(&post.id).unwrap_or_default();
+
+            let terms = post_terms
+                .as_mut()
+                .map(|terms| Term::by_taxonomy(terms.remove(&post.id).unwrap_or_default()));
+
+            let sticky = post.post_type == "post" && sticky_ids.contains(&post.id);
+            let unlocked = password.is_some_and(|password| password.unlocks(&post));
+
+            let acf = acf
+                .as_mut()
+                .zip(acf_context.as_ref())
+                .map(|(acf, context)| context.object(acf.remove(&post.id).unwrap_or_default()));
+
+            let mut response = Post::from(post);
+            response.permalink = permalink;
+            response.sticky = sticky;
+            response.featured_media = featured;
+            response.terms = terms;
+            response.acf = acf;
+            if response.protected && !unlocked {
+                response.redact();
+            }
+            response
+        })
+        .collect();
+
+    render_post_content(db, &mut responses, shortcode_mode).await?;
+
+    // The description falls back to the excerpt as rendered, so this runs
+    // once it is.
+    for response in responses.iter_mut() {
+        let excerpt = formatting::wp_strip_all_tags(&response.excerpt.rendered);
+        let meta = seo_meta.remove(&response.id).unwrap_or_default();
+
+        response.seo = Some(
+            seo_settings.resolve(&seo::Source {
+                object: seo::Object::Post {
+                    post_type: &response.post_type,
+                },
+                title: &response.post_title,
+                excerpt: &excerpt,
+                link: &response.permalink,
+                image: response
+                    .featured_media
+                    .as_ref()
+                    .map(|media| media.url.as_str()),
+                meta: &meta,
+            }),
+        );
+    }
+
+    Ok(responses)
+}
+
+// Fills in the content and excerpt as the WordPress front-end renders them
+// and then strips or renders shortcodes as requested. Galleries across the
+// whole page are resolved up front so rendering needs no further queries.
+async fn render_post_content(
+    db: &DatabaseConnection,
+    posts: &mut [Post],
+    shortcode_mode: ShortcodeMode,
+) -> Result<(), ApiError> {
+    let registry = Registry::core();
+    let formatter = Formatter::new(&registry.names());
+
+    // Block content is rendered by `do_blocks()`, which also switches off
+    // `wpautop` for it. Reusable blocks are loaded for the whole page.
+    let mut documents: Vec<Vec<blocks::Block>> = posts
+        .iter()
+        .map(|post| {
+            if blocks::has_blocks(&post.content.raw) {
+                blocks::parse(&post.content.raw)
+            } else {
+                Vec::new()
+            }
+        })
+        .collect();
+    queries::resolve_reusable_blocks(db, &mut documents).await?;
+
+    // Excerpts are only generated for posts without one, in the words or
+    // characters the site's locale counts.
+    let word_count_type = if posts.iter().any(|post| post.excerpt.raw.trim().is_empty()) {
+        queries::get_word_count_type(db).await?
+    } else {
+        formatting::WordCountType::Words
+    };
+
+    for (post, parsed_blocks) in posts.iter_mut().zip(&documents) {
+        let (html, autop) = if blocks::has_blocks(&post.content.raw) {
+            (blocks::render(parsed_blocks), false)
+        } else {
+            (post.content.raw.clone(), true)
+        };
+
+        post.content.rendered = formatter.the_content(&html, autop);
+
+        let excerpt = if post.excerpt.raw.trim().is_empty() {
+            // Like `wp_trim_excerpt()`, build the excerpt from the content
+            // without its shortcodes.
+            let content = shortcodes::render(
+                &shortcodes::parse(&html),
+                ShortcodeMode::Strip,
+                &registry,
+                &RenderContext::default(),
+            );
+            formatting::wp_trim_words(
+                &content,
+                formatting::EXCERPT_LENGTH,
+                formatting::EXCERPT_MORE,
+                word_count_type,
+            )
+        } else {
+            post.excerpt.raw.clone()
+        };
+
+        post.excerpt.rendered = formatter.the_excerpt(&excerpt);
+    }
+
+    if shortcode_mode == ShortcodeMode::Raw {
+        return Ok(());
+    }
+
+    let mut context = RenderContext::default();
+
+    if shortcode_mode == ShortcodeMode::Render {
+        let mut attachment_ids = Vec::new();
+        let mut parent_ids = Vec::new();
+        for post in posts.iter() {
+            let nodes = shortcodes::parse(&post.post_content);
+            attachment_ids.extend(shortcodes::gallery_attachment_ids(&nodes));
+            parent_ids.extend(shortcodes::gallery_parent_ids(&nodes, post.id));
+        }
+
+        if !attachment_ids.is_empty() || !parent_ids.is_empty() {
+            let (attachments, children) =
+                queries::get_gallery_attachments(db, attachment_ids, parent_ids).await?;
+            let uploads_url = queries::get_uploads_url(db).await?;
+
+            context.images = attachments
+                .into_iter()
+                .map(|(id, (attachment, meta))| {
+                    let media = Media::from_attachment(attachment, meta, &uploads_url);
+                    (id, GalleryImage::from(media))
+                })
+                .collect();
+            context.children = children;
+        }
+    }
+
+    for post in posts.iter_mut() {
+        context.post_id = post.id;
+
+        let apply = |text: &str| {
+            shortcodes::render(
+                &shortcodes::parse(text),
+                shortcode_mode,
+                &registry,
+                &context,
+            )
+        };
+
+        post.post_content = apply(&post.post_content);
+        post.post_excerpt = apply(&post.post_excerpt);
+        post.content.rendered = apply(&post.content.rendered);
+        post.excerpt.rendered = apply(&post.excerpt.rendered);
+    }
+
+    Ok(())
+}
+
+// Lists default to published posts. Only those are public; drafts,
+// scheduled posts and the like are for authenticated clients.
+fn validate_status(status: Option<&str>, client: Option<&ApiClient>) -> Result<String, ApiError> {
+    let Some(status) = status else {
+        return Ok("publish".to_string());
+    };
+
+    let valid_statuses = [
+        "publish",
+        "draft",
+        "private",
+        "pending",
+        "future",
+        "trash",
+        "auto-draft",
+    ];
+    if !valid_statuses.contains(&status) {
+        return Err(ApiError::BadRequest(format!(
+            "Invalid post status: {}. Valid statuses are: {}",
+            status,
+            valid_statuses.join(", ")
+        )));
+    }
+
+    if status != "publish" && client.is_none() {
+        return Err(ApiError::Unauthorized(format!(
+            "An API key is required to list posts with status {}",
+            status
+        )));
+    }
+
+    Ok(status.to_string())
+}
+
+fn parse_shortcode_mode(value: Option<&str>) -> Result<ShortcodeMode, ApiError> {
+    match value {
+        None => Ok(ShortcodeMode::Raw),
+        Some(value) => ShortcodeMode::parse(value).ok_or_else(|| {
+            ApiError::BadRequest(format!(
+                "Invalid shortcodes mode: {}. Valid modes are: raw, strip, render",
+                value
+            ))
+        }),
+    }
+}
+
+/// Related data embedded in post responses on request.
+#[derive(Clone, Copy, Default)]
+struct Embed {
+    terms: bool,
+}
+
+fn parse_embed(value: Option<&str>) -> Result<Embed, ApiError> {
+    let mut embed = Embed::default();
+
+    for name in value
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+    {
+        match name {
+            "terms" => embed.terms = true,
+            _ => {
+                return Err(ApiError::BadRequest(format!(
+                    "Invalid embed: {}. Valid values are: terms",
+                    name
+                )));
+            }
+        }
+    }
+
+    Ok(embed)
+}
+
+// Builds the post meta filter from the `meta_*` parameters. Several
+// conditions are given as comma-separated lists that pair up by position; a
+// single compare or type applies to every key, and a single key takes its
+// value whole, so it may contain commas.
+fn parse_meta_filter(
+    key: Option<&str>,
+    value: Option<&str>,
+    compare: Option<&str>,
+    meta_type: Option<&str>,
+    relation: Option<&str>,
+) -> Result<Option<MetaFilter>, ApiError> {
+    let Some(key) = key else {
+        if value.is_some() || compare.is_some() || meta_type.is_some() || relation.is_some() {
+            return Err(ApiError::BadRequest(
+                "meta_value, meta_compare, meta_type and meta_relation require meta_key"
+                    .to_string(),
+            ));
+        }
+        return Ok(None);
+    };
+
+    let keys: Vec<&str> = key.split(',').map(str::trim).collect();
+
+    if keys.iter().any(|key| key.is_empty()) {
+        return Err(ApiError::BadRequest("Meta key cannot be empty".to_string()));
+    }
+
+    if keys.len() > 10 {
+        return Err(ApiError::BadRequest(
+            "At most 10 meta conditions are allowed".to_string(),
+        ));
+    }
+
+    let list = |name: &str, value: Option<&str>, whole: bool| {
+        let values: Vec<String> = match value {
+            None => Vec::new(),
+            Some(value) if whole => vec![value.to_string()],
+            Some(value) => value.split(',').map(str::to_string).collect(),
+        };
+
+        match values.len() {
+            0 => Ok(Vec::new()),
+            1 => Ok(vec![values[0].clone(); keys.len()]),
+            count if count == keys.len() => Ok(values),
+            count => Err(ApiError::BadRequest(format!(
+                "Expected 1 or {} values for {}, got {}",
+                keys.len(),
+                name,
+                count
+            ))),
+        }
+    };
+
+    let values = list("meta_value", value, keys.len() == 1)?;
+    let compares = list("meta_compare", compare, false)?;
+    let types = list("meta_type", meta_type, false)?;
+
+    let any = match relation.map(|relation| relation.trim().to_ascii_lowercase()) {
+        None => false,
+        Some(relation) if relation == "and" => false,
+        Some(relation) if relation == "or" => true,
+        Some(relation) => {
+            return Err(ApiError::BadRequest(format!(
+                "Invalid meta relation: {}. Valid relations are: and, or",
+                relation
+            )));
+        }
+    };
+
+    let mut conditions = Vec::new();
+
+    for (index, key) in keys.iter().enumerate() {
+        let value = values.get(index).map(|value| value.trim().to_string());
+
+        // Without a compare, a key with a value is matched on it and a key
+        // alone on whether posts have it, as in `meta_query`.
+        let compare = match compares.get(index) {
+            Some(compare) => MetaCompare::parse(compare).ok_or_else(|| {
+                ApiError::BadRequest(format!(
+                    "Invalid meta compare: {}. Valid comparisons are: =, !=, >, >=, <, <=, LIKE, NOT LIKE, EXISTS, NOT EXISTS",
+                    compare.trim()
+                ))
+            })?,
+            None if value.is_some() => MetaCompare::Equal,
+            None => MetaCompare::Exists,
+        };
+
+        let numeric = match types.get(index).map(|meta_type| meta_type.trim()) {
+            None | Some("string") => false,
+            Some("numeric") => true,
+            Some(meta_type) => {
+                return Err(ApiError::BadRequest(format!(
+                    "Invalid meta type: {}. Valid types are: string, numeric",
+                    meta_type
+                )));
+            }
+        };
+
+        let value = if compare.takes_value() {
+            let value = value.ok_or_else(|| {
+                ApiError::BadRequest(format!("Meta key {} requires a meta_value", key))
+            })?;
+
+            if numeric && value.parse::<f64>().is_err() {
+                return Err(ApiError::BadRequest(format!(
+                    "Meta value for {} must be a number for numeric comparisons",
+                    key
+                )));
+            }
+            value
+        } else {
+            String::new()
+        };
+
+        conditions.push(MetaCondition {
+            key: key.to_string(),
+            compare,
+            value,
+            numeric,
+        });
+    }
+
+    Ok(Some(MetaFilter { conditions, any }))
+}
+
+pub async fn get_post(
+    path: web::Path<u64>,
+    query: web::Query<GetPostQuery>,
+    password: PostPassword,
+    db: web::Data<Arc<DatabaseConnection>>,
+    config: web::Data<Config>,
+) -> Result<HttpResponse, ApiError> {
+    let post_id = path.into_inner();
+
+    if post_id == 0 {
+        return Err(ApiError::BadRequest(
+            "Post ID must be a positive integer".to_string(),
+        ));
+    }
+
+    let with_blocks = match query.format.as_deref() {
+        None | Some("raw") => false,
+        Some("blocks") => true,
+        Some(format) => {
             return Err(ApiError::BadRequest(format!(
-                "Invalid post status: {}. Valid statuses are: {}",
-                status,
-                valid_statuses.join(", ")
+                "Invalid format: {}. Valid formats are: raw, blocks",
+                format
             )));
         }
-        Some(status.to_string())
-    } else {
-        Some("publish".to_string())
-    };
+    };
+
+    let shortcode_mode = parse_shortcode_mode(query.shortcodes.as_deref())?;
+
+    let post = queries::get_post_by_id(&db, post_id, true).await?;
+    password.check(&post)?;
+
+    let translations = queries::get_post_translations(&db, &post).await?;
+
+    let parsed_blocks = if with_blocks && password.unlocks(&post) {
+        let mut parsed_blocks = blocks::parse(&post.post_content);
+        queries::resolve_reusable_blocks(&db, std::slice::from_mut(&mut parsed_blocks)).await?;
+        Some(parsed_blocks)
+    } else {
+        None
+    };
+
+    // Hierarchical posts get their breadcrumb trail and page template.
+    let hierarchy = if config.site.is_hierarchical(&post.post_type) {
+        let ancestors = queries::get_post_ancestors(&db, &post).await?;
+        let mut breadcrumbs: Vec<Breadcrumb> = ancestors.iter().map(Breadcrumb::from).collect();
+        breadcrumbs.push(Breadcrumb::from(&post));
+
+        let page_template = queries::get_page_template(&db, post.id)
+            .await?
+            .unwrap_or_else(|| "default".to_string());
+
+        Some((breadcrumbs, page_template))
+    } else {
+        None
+    };
+
+    let mut post_response = build_post_responses(
+        &db,
+        &config,
+        vec![post],
+        shortcode_mode,
+        Embed::default(),
+        Some(&password),
+    )
+    .await?
+    .pop()
+    .ok_or_else(|| ApiError::NotFound(format!("Post with ID {} not found", post_id)))?;
+    post_response.blocks = parsed_blocks;
+    post_response.translations = translations.map(Translation::by_language);
+
+    if let Some((breadcrumbs, page_template)) = hierarchy {
+        post_response.breadcrumbs = Some(breadcrumbs);
+        post_response.page_template = Some(page_template);
+    }
+
+    Ok(HttpResponse::Ok().json(post_response))
+}
+
+pub async fn get_post_children(
+    path: web::Path<u64>,
+    query: web::Query<GetPostChildrenQuery>,
+    db: web::Data<Arc<DatabaseConnection>>,
+    config: web::Data<Config>,
+) -> Result<HttpResponse, ApiError> {
+    let post_id = path.into_inner();
+
+    if post_id == 0 {
+        return Err(ApiError::BadRequest(
+            "Post ID must be a positive integer".to_string(),
+        ));
+    }
+
+    if let Some(page) = query.page {
+        if page == 0 {
+            return Err(ApiError::BadRequest(
+                "Page number must be greater than 0".to_string(),
+            ));
+        }
+    }
+
+    if let Some(page_size) = query.page_size {
+        if page_size == 0 {
+            return Err(ApiError::BadRequest(
+                "Page size must be greater than 0".to_string(),
+            ));
+        }
+        if page_size > 100 {
+            return Err(ApiError::BadRequest("Maximum page size is 100".to_string()));
+        }
+    }
+
+    let shortcode_mode = parse_shortcode_mode(query.shortcodes.as_deref())?;
+    let embed = parse_embed(query.embed.as_deref())?;
+
+    let page = query.page.unwrap_or(1);
+    let page_size = query.page_size.unwrap_or(10).min(100);
+
+    let lang = queries::get_language_filter(&db, query.lang.as_deref()).await?;
+
+    let (children, total) =
+        queries::get_post_children(&db, post_id, page, page_size, lang.as_ref()).await?;
+
+    let post_responses =
+        build_post_responses(&db, &config, children, shortcode_mode, embed, None).await?;
+
+    let response = PaginatedResponse::new(post_responses, total, page, page_size);
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+pub async fn get_post_ancestors(
+    path: web::Path<u64>,
+    query: web::Query<GetPostAncestorsQuery>,
+    db: web::Data<Arc<DatabaseConnection>>,
+    config: web::Data<Config>,
+) -> Result<HttpResponse, ApiError> {
+    let post_id = path.into_inner();
+
+    if post_id == 0 {
+        return Err(ApiError::BadRequest(
+            "Post ID must be a positive integer".to_string(),
+        ));
+    }
+
+    let shortcode_mode = parse_shortcode_mode(query.shortcodes.as_deref())?;
+
+    let post = queries::get_post_by_id(&db, post_id, true).await?;
+    let ancestors = queries::get_post_ancestors(&db, &post).await?;
+
+    let post_responses = build_post_responses(
+        &db,
+        &config,
+        ancestors,
+        shortcode_mode,
+        Embed::default(),
+        None,
+    )
+    .await?;
+
+    Ok(HttpResponse::Ok().json(post_responses))
+}
+
+pub async fn get_page_tree(
+    query: web::Query<GetPageTreeQuery>,
+    db: web::Data<Arc<DatabaseConnection>>,
+    config: web::Data<Config>,
+) -> Result<HttpResponse, ApiError> {
+    let post_type = query.post_type.as_deref().unwrap_or("page");
+
+    if post_type.is_empty() {
+        return Err(ApiError::BadRequest(
+            "Post type cannot be empty".to_string(),
+        ));
+    }
+
+    if !config.site.is_hierarchical(post_type) {
+        return Err(ApiError::BadRequest(format!(
+            "Post type '{}' is not hierarchical",
+            post_type
+        )));
+    }
+
+    let pages = queries::get_page_tree(&db, post_type).await?;
+
+    Ok(HttpResponse::Ok().json(PageTreeNode::tree(pages)))
+}
+
+pub async fn get_post_revisions(
+    _client: ApiClient,
+    path: web::Path<u64>,
+    query: web::Query<GetRevisionsQuery>,
+    db: web::Data<Arc<DatabaseConnection>>,
+) -> Result<HttpResponse, ApiError> {
+    let post_id = path.into_inner();
+
+    if post_id == 0 {
+        return Err(ApiError::BadRequest(
+            "Post ID must be a positive integer".to_string(),
+        ));
+    }
+
+    if let Some(page) = query.page {
+        if page == 0 {
+            return Err(ApiError::BadRequest(
+                "Page number must be greater than 0".to_string(),
+            ));
+        }
+    }
+
+    if let Some(page_size) = query.page_size {
+        if page_size == 0 {
+            return Err(ApiError::BadRequest(
+                "Page size must be greater than 0".to_string(),
+            ));
+        }
+        if page_size > 100 {
+            return Err(ApiError::BadRequest("Maximum page size is 100".to_string()));
+        }
+    }
+
+    let page = query.page.unwrap_or(1);
+    let page_size = query.page_size.unwrap_or(10).min(100);
+
+    let (revisions, total) = queries::get_revisions(&db, post_id, page, page_size).await?;
+
+    let revision_responses: Vec<Revision> = revisions.into_iter().map(Revision::from).collect();
+
+    let response = PaginatedResponse::new(revision_responses, total, page, page_size);
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+pub async fn get_revision_diff(
+    _client: ApiClient,
+    path: web::Path<(u64, u64)>,
+    query: web::Query<GetRevisionDiffQuery>,
+    db: web::Data<Arc<DatabaseConnection>>,
+) -> Result<HttpResponse, ApiError> {
+    let (post_id, revision_id) = path.into_inner();
+
+    if post_id == 0 || revision_id == 0 {
+        return Err(ApiError::BadRequest(
+            "Post and revision IDs must be positive integers".to_string(),
+        ));
+    }
+
+    if query.against == Some(0) {
+        return Err(ApiError::BadRequest(
+            "Revision to compare against must be a positive integer".to_string(),
+        ));
+    }
+
+    let revision = queries::get_revision(&db, post_id, revision_id).await?;
+    let base = queries::get_revision_base(&db, &revision, query.against).await?;
+
+    let response = RevisionDiff::new(base.as_ref(), &revision);
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+pub async fn get_post_terms(
+    path: web::Path<u64>,
+    db: web::Data<Arc<DatabaseConnection>>,
+) -> Result<HttpResponse, ApiError> {
+    let post_id = path.into_inner();
+
+    let post = queries::get_post_by_id(&db, post_id, true).await?;
+
+    let mut terms = queries::get_post_terms(&db, vec![post.id]).await?;
+
+    let response = Term::by_taxonomy(terms.remove(&post.id).unwrap_or_default());
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+pub async fn get_post_meta(
+    path: web::Path<u64>,
+    query: web::Query<GetPostMetaQuery>,
+    password: PostPassword,
+    db: web::Data<Arc<DatabaseConnection>>,
+) -> Result<HttpResponse, ApiError> {
+    let post_id = path.into_inner();
+
+    if post_id == 0 {
+        return Err(ApiError::BadRequest(
+            "Post ID must be a positive integer".to_string(),
+        ));
+    }
+
+    // Meta holds the same content the redacted post hides, such as ACF
+    // field values, so it stays behind the password as well.
+    let post = queries::get_post_by_id(&db, post_id, true).await?;
+    password.check(&post)?;
+    if !password.unlocks(&post) {
+        return Err(ApiError::Forbidden(
+            "Metadata of a password-protected post requires its password".to_string(),
+        ));
+    }
+
+    let meta = queries::get_post_meta(&db, post.id).await?;
+
+    let response = PostMeta::new(meta, query.raw.unwrap_or(false));
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+pub async fn get_post_comments(
+    path: web::Path<u64>,
+    query: web::Query<GetCommentsQuery>,
+    password: PostPassword,
+    db: web::Data<Arc<DatabaseConnection>>,
+) -> Result<HttpResponse, ApiError> {
+    let post_id = path.into_inner();
+
+    if post_id == 0 {
+        return Err(ApiError::BadRequest(
+            "Post ID must be a positive integer".to_string(),
+        ));
+    }
+
+    if let Some(page) = query.page {
+        if page == 0 {
+            return Err(ApiError::BadRequest(
+                "Page number must be greater than 0".to_string(),
+            ));
+        }
+    }
+
+    if let Some(page_size) = query.page_size {
+        if page_size == 0 {
+            return Err(ApiError::BadRequest(
+                "Page size must be greater than 0".to_string(),
+            ));
+        }
+        if page_size > 100 {
+            return Err(ApiError::BadRequest("Maximum page size is 100".to_string()));
+        }
+    }
+
+    let page = query.page.unwrap_or(1);
+    let page_size = query.page_size.unwrap_or(20).min(100);
+
+    // WordPress keeps the comments of a protected post behind its password.
+    let post = queries::get_post_by_id(&db, post_id, true).await?;
+    password.check(&post)?;
+    if !password.unlocks(&post) {
+        return Err(ApiError::Forbidden(
+            "Comments of a password-protected post require its password".to_string(),
+        ));
+    }
+
+    let (threads, total) = queries::get_post_comments(&db, post_id, page, page_size).await?;
+
+    let comment_responses: Vec<Comment> = threads.into_iter().map(Comment::from).collect();
+
+    let response = PaginatedResponse::new(comment_responses, total, page, page_size);
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+pub async fn resolve_path(
+    query: web::Query<ResolvePathQuery>,
+    password: PostPassword,
+    db: web::Data<Arc<DatabaseConnection>>,
+    config: web::Data<Config>,
+) -> Result<HttpResponse, ApiError> {
+    if query.path.trim().is_empty() {
+        return Err(ApiError::BadRequest("Path cannot be empty".to_string()));
+    }
+
+    let shortcode_mode = parse_shortcode_mode(query.shortcodes.as_deref())?;
+
+    let permalinks = queries::get_permalinks(&db, &config.site.post_type_bases).await?;
+
+    let response = match queries::resolve_path(&db, &permalinks, query.path.trim()).await? {
+        queries::Resource::Post(post) => {
+            password.check(&post)?;
+            let translations = queries::get_post_translations(&db, &post).await?;
+            let mut post_response = build_post_responses(
+                &db,
+                &config,
+                vec![*post],
+                shortcode_mode,
+                Embed::default(),
+                Some(&password),
+            )
+            .await?
+            .pop()
+            .ok_or_else(|| ApiError::NotFound(format!("Nothing found at path {}", query.path)))?;
+            post_response.translations = translations.map(Translation::by_language);
+            Resolved::Post(Box::new(post_response))
+        }
+        queries::Resource::Term(taxonomy, term) => {
+            let term = (taxonomy, term);
+            let seo = queries::get_term_seo(&db, &permalinks, std::slice::from_ref(&term))
+                .await?
+                .remove(&term.0.term_taxonomy_id);
+
+            let mut term_response = Term::from(term);
+            term_response.seo = seo;
+            Resolved::Term(term_response)
+        }
+        queries::Resource::Author(profile) => Resolved::Author(Author::from(profile)),
+    };
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+pub async fn get_post_types(
+    db: web::Data<Arc<DatabaseConnection>>,
+) -> Result<HttpResponse, ApiError> {
+    let post_types = queries::get_post_types(&db).await?;
+
+    let response: Vec<PostType> = post_types
+        .into_iter()
+        .map(|(name, count, published_count)| PostType {
+            name,
+            count,
+            published_count,
+        })
+        .collect();
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+pub async fn get_posts_by_type(
+    path: web::Path<String>,
+    query: web::Query<GetPostsTypeQuery>,
+    client: Option<ApiClient>,
+    db: web::Data<Arc<DatabaseConnection>>,
+    config: web::Data<Config>,
+) -> Result<HttpResponse, ApiError> {
+    let post_type = path.into_inner();
+
+    if post_type.is_empty() {
+        return Err(ApiError::BadRequest(
+            "Post type cannot be empty".to_string(),
+        ));
+    }
+
+    if let Some(page) = query.page {
+        if page == 0 {
+            return Err(ApiError::BadRequest(
+                "Page number must be greater than 0".to_string(),
+            ));
+        }
+    }
+
+    if let Some(page_size) = query.page_size {
+        if page_size == 0 {
+            return Err(ApiError::BadRequest(
+                "Page size must be greater than 0".to_string(),
+            ));
+        }
+        if page_size > 100 {
+            return Err(ApiError::BadRequest("Maximum page size is 100".to_string()));
+        }
+    }
+
+    if let Some(search_term) = &query.search {
+        if search_term.chars().count() < 3 {
+            return Err(ApiError::BadRequest(
+                "Search term must be at least 3 characters long".to_string(),
+            ));
+        }
+    }
+
+    let post_status = validate_status(query.post_status.as_deref(), client.as_ref())?;
+
+    let shortcode_mode = parse_shortcode_mode(query.shortcodes.as_deref())?;
+    let embed = parse_embed(query.embed.as_deref())?;
+    let meta = parse_meta_filter(
+        query.meta_key.as_deref(),
+        query.meta_value.as_deref(),
+        query.meta_compare.as_deref(),
+        query.meta_type.as_deref(),
+        query.meta_relation.as_deref(),
+    )?;
+
+    let page = query.page.unwrap_or(1);
+    let page_size = query.page_size.unwrap_or(10).min(100);
+    let search = query.search.clone();
+
+    let lang = queries::get_language_filter(&db, query.lang.as_deref()).await?;
+
+    let (posts, total) = queries::get_posts_by_type(
+        &db,
+        &post_type,
+        Some(&post_status),
+        page,
+        page_size,
+        search,
+        meta.as_ref(),
+        lang.as_ref(),
+    )
+    .await?;
+
+    let post_responses =
+        build_post_responses(&db, &config, posts, shortcode_mode, embed, None).await?;
+
+    let response = PaginatedResponse::new(post_responses, total, page, page_size);
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+pub async fn get_categories(
+    query: web::Query<GetCategoriesQuery>,
+    db: web::Data<Arc<DatabaseConnection>>,
+    config: web::Data<Config>,
+) -> Result<HttpResponse, ApiError> {
+    if let Some(page) = query.page {
+        if page == 0 {
+            return Err(ApiError::BadRequest(
+                "Page number must be greater than 0".to_string(),
+            ));
+        }
+    }
+
+    if let Some(page_size) = query.page_size {
+        if page_size == 0 {
+            return Err(ApiError::BadRequest(
+                "Page size must be greater than 0".to_string(),
+            ));
+        }
+        if page_size > 100 {
+            return Err(ApiError::BadRequest("Maximum page size is 100".to_string()));
+        }
+    }
+
+    let page = query.page.unwrap_or(1);
+    let page_size = query.page_size.unwrap_or(20).min(100);
+
+    let lang = queries::get_language_filter(&db, query.lang.as_deref()).await?;
+
+    let (categories, total) = queries::get_categories(&db, page, page_size, lang.as_ref()).await?;
+
+    let permalinks = queries::get_permalinks(&db, &config.site.post_type_bases).await?;
+    let mut seo = queries::get_term_seo(&db, &permalinks, &categories).await?;
+
+    let category_responses: Vec<Category> = categories
+        .into_iter()
+        .map(|category| {
+            let seo = seo.remove(&category.0.term_taxonomy_id);
+            let mut response = Category::from(category);
+            response.seo = seo;
+            response
+        })
+        .collect();
+
+    let response = PaginatedResponse::new(category_responses, total, page, page_size);
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+pub async fn get_category_tree(
+    query: web::Query<GetCategoryTreeQuery>,
+    db: web::Data<Arc<DatabaseConnection>>,
+) -> Result<HttpResponse, ApiError> {
+    let lang = queries::get_language_filter(&db, query.lang.as_deref()).await?;
+
+    let (categories, posts) = queries::get_category_tree(&db, lang.as_ref()).await?;
+
+    let response = CategoryTreeNode::tree(categories, &posts);
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+pub async fn get_category_by_slug(
+    path: web::Path<String>,
+    db: web::Data<Arc<DatabaseConnection>>,
+    config: web::Data<Config>,
+) -> Result<HttpResponse, ApiError> {
+    let slug = path.into_inner();
+
+    let category = queries::get_category_by_slug(&db, &slug).await?;
+
+    let permalinks = queries::get_permalinks(&db, &config.site.post_type_bases).await?;
+    let seo = queries::get_term_seo(&db, &permalinks, std::slice::from_ref(&category))
+        .await?
+        .remove(&category.0.term_taxonomy_id);
+
+    let mut response = Category::from(category);
+    response.seo = seo;
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+pub async fn get_posts_by_category(
+    path: web::Path<i32>,
+    query: web::Query<GetPostsCategoryQuery>,
+    db: web::Data<Arc<DatabaseConnection>>,
+    config: web::Data<Config>,
+) -> Result<HttpResponse, ApiError> {
+    let category_id = path.into_inner();
+
+    if category_id <= 0 {
+        return Err(ApiError::BadRequest(
+            "Category ID must be a positive integer".to_string(),
+        ));
+    }
+
+    category_posts(&db, &config, category_id, &query).await
+}
+
+pub async fn get_posts_by_category_slug(
+    path: web::Path<String>,
+    query: web::Query<GetPostsCategoryQuery>,
+    db: web::Data<Arc<DatabaseConnection>>,
+    config: web::Data<Config>,
+) -> Result<HttpResponse, ApiError> {
+    let slug = path.into_inner();
+
+    let (_, term) = queries::get_category_by_slug(&db, &slug).await?;
+
+    let category_id = i32::try_from(term.term_id).map_err(|_| {
+        ApiError::BadRequest(format!("Category ID {} is out of range", term.term_id))
+    })?;
+
+    category_posts(&db, &config, category_id, &query).await
+}
+
+// Shared by the category posts endpoints once the category is known.
+async fn category_posts(
+    db: &DatabaseConnection,
+    config: &Config,
+    category_id: i32,
+    query: &GetPostsCategoryQuery,
+) -> Result<HttpResponse, ApiError> {
+    if let Some(page) = query.page {
+        if page == 0 {
+            return Err(ApiError::BadRequest(
+                "Page number must be greater than 0".to_string(),
+            ));
+        }
+    }
+
+    if let Some(page_size) = query.page_size {
+        if page_size == 0 {
+            return Err(ApiError::BadRequest(
+                "Page size must be greater than 0".to_string(),
+            ));
+        }
+        if page_size > 100 {
+            return Err(ApiError::BadRequest("Maximum page size is 100".to_string()));
+        }
+    }
+
+    if let Some(search_term) = &query.search {
+        if search_term.chars().count() < 3 {
+            return Err(ApiError::BadRequest(
+                "Search term must be at least 3 characters long".to_string(),
+            ));
+        }
+    }
+
+    let shortcode_mode = parse_shortcode_mode(query.shortcodes.as_deref())?;
+    let embed = parse_embed(query.embed.as_deref())?;
+
+    let page = query.page.unwrap_or(1);
+    let page_size = query.page_size.unwrap_or(10).min(100);
+    let search = query.search.clone();
+
+    let include_children = query.include_children.unwrap_or(false);
+
+    let lang = queries::get_language_filter(db, query.lang.as_deref()).await?;
+
+    let (posts, total) = queries::get_posts_by_category(
+        db,
+        category_id,
+        include_children,
+        page,
+        page_size,
+        search,
+        lang.as_ref(),
+    )
+    .await?;
+
+    let post_responses =
+        build_post_responses(db, config, posts, shortcode_mode, embed, None).await?;
+
+    let response = PaginatedResponse::new(post_responses, total, page, page_size);
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+pub async fn get_taxonomies(
+    db: web::Data<Arc<DatabaseConnection>>,
+) -> Result<HttpResponse, ApiError> {
+    let taxonomies = queries::get_taxonomies(&db).await?;
+
+    let response: Vec<Taxonomy> = taxonomies
+        .into_iter()
+        .map(|(name, term_count, count)| Taxonomy {
+            name,
+            term_count,
+            count,
+        })
+        .collect();
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+pub async fn get_terms(
+    path: web::Path<String>,
+    query: web::Query<GetTermsQuery>,
+    db: web::Data<Arc<DatabaseConnection>>,
+    config: web::Data<Config>,
+) -> Result<HttpResponse, ApiError> {
+    let taxonomy = path.into_inner();
+
+    if taxonomy.is_empty() || taxonomy.len() > 32 {
+        return Err(ApiError::BadRequest(
+            "Taxonomy must be between 1 and 32 characters long".to_string(),
+        ));
+    }
+
+    if let Some(page) = query.page {
+        if page == 0 {
+            return Err(ApiError::BadRequest(
+                "Page number must be greater than 0".to_string(),
+            ));
+        }
+    }
+
+    if let Some(page_size) = query.page_size {
+        if page_size == 0 {
+            return Err(ApiError::BadRequest(
+                "Page size must be greater than 0".to_string(),
+            ));
+        }
+        if page_size > 100 {
+            return Err(ApiError::BadRequest("Maximum page size is 100".to_string()));
+        }
+    }
+
+    if let Some(search_term) = &query.search {
+        if search_term.chars().count() < 3 {
+            return Err(ApiError::BadRequest(
+                "Search term must be at least 3 characters long".to_string(),
+            ));
+        }
+    }
+
+    let page = query.page.unwrap_or(1);
+    let page_size = query.page_size.unwrap_or(20).min(100);
+    let search = query.search.clone();
+
+    let lang = queries::get_language_filter(&db, query.lang.as_deref()).await?;
+
+    let (terms, total) =
+        queries::get_terms(&db, &taxonomy, page, page_size, search, lang.as_ref()).await?;
+
+    let permalinks = queries::get_permalinks(&db, &config.site.post_type_bases).await?;
+    let mut seo = queries::get_term_seo(&db, &permalinks, &terms).await?;
+
+    let term_responses: Vec<Term> = terms
+        .into_iter()
+        .map(|term| {
+            let seo = seo.remove(&term.0.term_taxonomy_id);
+            let mut response = Term::from(term);
+            response.seo = seo;
+            response
+        })
+        .collect();
+
+    let response = PaginatedResponse::new(term_responses, total, page, page_size);
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+pub async fn get_posts_by_term(
+    path: web::Path<(String, u64)>,
+    query: web::Query<GetPostsCategoryQuery>,
+    db: web::Data<Arc<DatabaseConnection>>,
+    config: web::Data<Config>,
+) -> Result<HttpResponse, ApiError> {
+    let (taxonomy, term_id) = path.into_inner();
+
+    if taxonomy.is_empty() || taxonomy.len() > 32 {
+        return Err(ApiError::BadRequest(
+            "Taxonomy must be between 1 and 32 characters long".to_string(),
+        ));
+    }
+
+    if term_id == 0 {
+        return Err(ApiError::BadRequest(
+            "Term ID must be a positive integer".to_string(),
+        ));
+    }
+
+    if let Some(page) = query.page {
+        if page == 0 {
+            return Err(ApiError::BadRequest(
+                "Page number must be greater than 0".to_string(),
+            ));
+        }
+    }
+
+    if let Some(page_size) = query.page_size {
+        if page_size == 0 {
+            return Err(ApiError::BadRequest(
+                "Page size must be greater than 0".to_string(),
+            ));
+        }
+        if page_size > 100 {
+            return Err(ApiError::BadRequest("Maximum page size is 100".to_string()));
+        }
+    }
+
+    if let Some(search_term) = &query.search {
+        if search_term.chars().count() < 3 {
+            return Err(ApiError::BadRequest(
+                "Search term must be at least 3 characters long".to_string(),
+            ));
+        }
+    }
+
+    let shortcode_mode = parse_shortcode_mode(query.shortcodes.as_deref())?;
+    let embed = parse_embed(query.embed.as_deref())?;
+
+    let page = query.page.unwrap_or(1);
+    let page_size = query.page_size.unwrap_or(10).min(100);
+    let search = query.search.clone();
+
+    let lang = queries::get_language_filter(&db, query.lang.as_deref()).await?;
+
+    let (posts, total) = queries::get_posts_by_term(
+        &db,
+        &taxonomy,
+        term_id,
+        page,
+        page_size,
+        search,
+        lang.as_ref(),
+    )
+    .await?;
+
+    let post_responses =
+        build_post_responses(&db, &config, posts, shortcode_mode, embed, None).await?;
+
+    let response = PaginatedResponse::new(post_responses, total, page, page_size);
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+pub async fn get_authors(
+    query: web::Query<GetAuthorsQuery>,
+    db: web::Data<Arc<DatabaseConnection>>,
+) -> Result<HttpResponse, ApiError> {
+    if let Some(page) = query.page {
+        if page == 0 {
+            return Err(ApiError::BadRequest(
+                "Page number must be greater than 0".to_string(),
+            ));
+        }
+    }
+
+    if let Some(page_size) = query.page_size {
+        if page_size == 0 {
+            return Err(ApiError::BadRequest(
+                "Page size must be greater than 0".to_string(),
+            ));
+        }
+        if page_size > 100 {
+            return Err(ApiError::BadRequest("Maximum page size is 100".to_string()));
+        }
+    }
+
+    if let Some(search_term) = &query.search {
+        if search_term.chars().count() < 3 {
+            return Err(ApiError::BadRequest(
+                "Search term must be at least 3 characters long".to_string(),
+            ));
+        }
+    }
 
-    let post_type = query.post_type.clone();
     let page = query.page.unwrap_or(1);
-    let page_size = query.page_size.unwrap_or(10).min(100);
+    let page_size = query.page_size.unwrap_or(20).min(100);
     let search = query.search.clone();
-    let author_id = query.author_id;
 
-    let (posts, total) = queries::get_posts(
-        &db,
-        post_type,
-        post_status,
-        page,
-        page_size,
-        search,
-        author_id,
-    )
-    .await?;
+    let (authors, total) = queries::get_authors(&db, page, page_size, search).await?;
 
-    let post_responses: Vec<Post> = posts.into_iter().map(Post::from).collect();
+    let author_responses: Vec<Author> = authors.into_iter().map(Author::from).collect();
 
-    let response = PaginatedResponse::new(post_responses, total, page, page_size);
+    let response = PaginatedResponse::new(author_responses, total, page, page_size);
 
     Ok(HttpResponse::Ok().json(response))
 }
 
-pub async fn get_post(
+pub async fn get_author(
     path: web::Path<u64>,
     db: web::Data<Arc<DatabaseConnection>>,
 ) -> Result<HttpResponse, ApiError> {
-    let post_id = path.into_inner();
+    let author_id = path.into_inner();
 
-    if post_id == 0 {
+    if author_id == 0 {
         return Err(ApiError::BadRequest(
-            "Post ID must be a positive integer".to_string(),
+            "Author ID must be a positive integer".to_string(),
         ));
     }
 
-    let post = queries::get_post_by_id(&db, post_id, true).await?;
-
-    let post_response = Post::from(post);
+    let author = queries::get_author_by_id(&db, author_id).await?;
 
-    Ok(HttpResponse::Ok().json(post_response))
+    Ok(HttpResponse::Ok().json(Author::from(author)))
 }
 
-pub async fn get_post_meta(
+pub async fn get_posts_by_author(
     path: web::Path<u64>,
+    query: web::Query<GetPostsAuthorQuery>,
     db: web::Data<Arc<DatabaseConnection>>,
+    config: web::Data<Config>,
 ) -> Result<HttpResponse, ApiError> {
-    let post_id = path.into_inner();
-
-    if post_id == 0 {
-        return Err(ApiError::BadRequest(
-            "Post ID must be a positive integer".to_string(),
-        ));
-    }
-
-    let meta = queries::get_post_meta(&db, post_id).await?;
-
-    if meta.is_empty() {}
-
-    let response = PostMeta { meta };
-
-    Ok(HttpResponse::Ok().json(response))
-}
-
-pub async fn get_post_types(
-    db: web::Data<Arc<DatabaseConnection>>,
-) -> Result<HttpResponse, ApiError> {
-    let post_types = queries::get_post_types(&db).await?;
-
-    let response: Vec<PostType> = post_types
-        .into_iter()
-        .map(|(name, count, published_count)| PostType {
-            name,
-            count,
-            published_count,
-        })
-        .collect();
-
-    Ok(HttpResponse::Ok().json(response))
-}
-
-pub async fn get_posts_by_type(
-    path: web::Path<String>,
-    query: web::Query<GetPostsTypeQuery>,
-    db: web::Data<Arc<DatabaseConnection>>,
-) -> Result<HttpResponse, ApiError> {
-    let post_type = path.into_inner();
+    let author_id = path.into_inner();
 
-    if post_type.is_empty() {
+    if author_id == 0 {
         return Err(ApiError::BadRequest(
-            "Post type cannot be empty".to_string(),
+            "Author ID must be a positive integer".to_string(),
         ));
     }
 
@@ -182,52 +1562,50 @@ pub async fn get_posts_by_type(
         }
     }
 
-    if let Some(search_term) = &query.search {
-        if search_term.chars().count() < 3 {
+    if let Some(post_type) = &query.post_type {
+        if post_type.is_empty() {
             return Err(ApiError::BadRequest(
-                "Search term must be at least 3 characters long".to_string(),
+                "Post type cannot be empty".to_string(),
             ));
         }
     }
 
-    let post_status = if let Some(status) = &query.post_status {
-        let valid_statuses = [
-            "publish",
-            "draft",
-            "private",
-            "pending",
-            "future",
-            "trash",
-            "auto-draft",
-        ];
-        if !valid_statuses.contains(&status.as_str()) {
-            return Err(ApiError::BadRequest(format!(
-                "Invalid post status: {}. Valid statuses are: {}",
-                status,
-                valid_statuses.join(", ")
-            )));
-        }
-        Some(status.as_str())
-    } else {
-        None
-    };
+    let author = queries::get_author_by_id(&db, author_id).await?;
+
+    let post_type = query.post_type.clone();
+    let shortcode_mode = parse_shortcode_mode(query.shortcodes.as_deref())?;
+    let embed = parse_embed(query.embed.as_deref())?;
 
     let page = query.page.unwrap_or(1);
     let page_size = query.page_size.unwrap_or(10).min(100);
     let search = query.search.clone();
 
-    let (posts, total) =
-        queries::get_posts_by_type(&db, &post_type, post_status, page, page_size, search).await?;
+    let lang = queries::get_language_filter(&db, query.lang.as_deref()).await?;
+
+    let (posts, total) = queries::get_posts(
+        &db,
+        post_type,
+        Some("publish".to_string()),
+        page,
+        page_size,
+        search,
+        Some(author.user.id),
+        None,
+        None,
+        lang.as_ref(),
+    )
+    .await?;
 
-    let post_responses: Vec<Post> = posts.into_iter().map(Post::from).collect();
+    let post_responses =
+        build_post_responses(&db, &config, posts, shortcode_mode, embed, None).await?;
 
     let response = PaginatedResponse::new(post_responses, total, page, page_size);
 
     Ok(HttpResponse::Ok().json(response))
 }
 
-pub async fn get_categories(
-    query: web::Query<GetCategoriesQuery>,
+pub async fn get_media(
+    query: web::Query<GetMediaQuery>,
     db: web::Data<Arc<DatabaseConnection>>,
 ) -> Result<HttpResponse, ApiError> {
     if let Some(page) = query.page {
@@ -249,31 +1627,68 @@ pub async fn get_categories(
         }
     }
 
+    if let Some(search_term) = &query.search {
+        if search_term.chars().count() < 3 {
+            return Err(ApiError::BadRequest(
+                "Search term must be at least 3 characters long".to_string(),
+            ));
+        }
+    }
+
+    if let Some(mime_type) = &query.mime_type {
+        if mime_type.is_empty() {
+            return Err(ApiError::BadRequest(
+                "MIME type cannot be empty".to_string(),
+            ));
+        }
+    }
+
     let page = query.page.unwrap_or(1);
-    let page_size = query.page_size.unwrap_or(20).min(100);
+    let page_size = query.page_size.unwrap_or(10).min(100);
+    let search = query.search.clone();
+    let mime_type = query.mime_type.clone();
 
-    let (categories, total) = queries::get_categories(&db, page, page_size).await?;
+    let lang = queries::get_language_filter(&db, query.lang.as_deref()).await?;
 
-    let category_responses: Vec<Category> = categories.into_iter().map(Category::from).collect();
+    let (attachments, total) =
+        queries::get_media(&db, page, page_size, search, mime_type, lang.as_ref()).await?;
+    let uploads_url = queries::get_uploads_url(&db).await?;
 
-    let response = PaginatedResponse::new(category_responses, total, page, page_size);
+    let media_responses: Vec<Media> = attachments
+        .into_iter()
+        .map(|(attachment, meta)| Media::from_attachment(attachment, meta, &uploads_url))
+        .collect();
+
+    let response = PaginatedResponse::new(media_responses, total, page, page_size);
 
     Ok(HttpResponse::Ok().json(response))
 }
 
-pub async fn get_posts_by_category(
-    path: web::Path<i32>,
-    query: web::Query<GetPostsCategoryQuery>,
+pub async fn get_media_item(
+    path: web::Path<u64>,
     db: web::Data<Arc<DatabaseConnection>>,
 ) -> Result<HttpResponse, ApiError> {
-    let category_id = path.into_inner();
+    let media_id = path.into_inner();
 
-    if category_id <= 0 {
+    if media_id == 0 {
         return Err(ApiError::BadRequest(
-            "Category ID must be a positive integer".to_string(),
+            "Media ID must be a positive integer".to_string(),
         ));
     }
 
+    let (attachment, meta) = queries::get_media_by_id(&db, media_id).await?;
+    let uploads_url = queries::get_uploads_url(&db).await?;
+
+    let response = Media::from_attachment(attachment, meta, &uploads_url);
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+pub async fn get_products(
+    query: web::Query<GetProductsQuery>,
+    db: web::Data<Arc<DatabaseConnection>>,
+    config: web::Data<Config>,
+) -> Result<HttpResponse, ApiError> {
     if let Some(page) = query.page {
         if page == 0 {
             return Err(ApiError::BadRequest(
@@ -301,20 +1716,151 @@ pub async fn get_posts_by_category(
         }
     }
 
+    for price in [query.min_price, query.max_price].into_iter().flatten() {
+        if !price.is_finite() || price < 0.0 {
+            return Err(ApiError::BadRequest(
+                "Prices must be non-negative numbers".to_string(),
+            ));
+        }
+    }
+
+    let mut attributes = Vec::new();
+    if let Some(attribute_filter) = &query.attributes {
+        for pair in attribute_filter.split(',').filter(|pair| !pair.is_empty()) {
+            match pair.split_once(':') {
+                Some((taxonomy, slug)) if !taxonomy.is_empty() && !slug.is_empty() => {
+                    attributes.push((taxonomy.to_string(), slug.to_string()));
+                }
+                _ => {
+                    return Err(ApiError::BadRequest(format!(
+                        "Invalid attribute filter: {}. Expected taxonomy:slug, e.g. pa_color:red",
+                        pair
+                    )));
+                }
+            }
+        }
+    }
+
+    let filter = ProductFilter {
+        min_price: query.min_price,
+        max_price: query.max_price,
+        in_stock: query.in_stock,
+        attributes,
+    };
+
     let page = query.page.unwrap_or(1);
     let page_size = query.page_size.unwrap_or(10).min(100);
     let search = query.search.clone();
 
-    let (posts, total) =
-        queries::get_posts_by_category(&db, category_id, page, page_size, search).await?;
+    let lang = queries::get_language_filter(&db, query.lang.as_deref()).await?;
+
+    let (records, total) = queries::get_products(
+        &db,
+        &config.shop.product_post_type,
+        filter,
+        page,
+        page_size,
+        search,
+        lang.as_ref(),
+    )
+    .await?;
 
-    let post_responses: Vec<Post> = posts.into_iter().map(Post::from).collect();
+    let image_ids = records
+        .iter()
+        .flat_map(|record| record.image_ids())
+        .collect();
+    let attachments = queries::get_attachments(&db, image_ids).await?;
+    let uploads_url = queries::get_uploads_url(&db).await?;
 
-    let response = PaginatedResponse::new(post_responses, total, page, page_size);
+    // Like post lists, product lists always return protected products
+    // redacted.
+    let product_responses: Vec<Product> = records
+        .into_iter()
+        .map(|record| {
+            let mut product = Product::from_record(record, &attachments, &uploads_url);
+            if product.protected {
+                product.redact();
+            }
+            product
+        })
+        .collect();
+
+    let response = PaginatedResponse::new(product_responses, total, page, page_size);
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+pub async fn get_product(
+    path: web::Path<u64>,
+    password: PostPassword,
+    db: web::Data<Arc<DatabaseConnection>>,
+    config: web::Data<Config>,
+) -> Result<HttpResponse, ApiError> {
+    let product_id = path.into_inner();
+
+    if product_id == 0 {
+        return Err(ApiError::BadRequest(
+            "Product ID must be a positive integer".to_string(),
+        ));
+    }
+
+    let record =
+        queries::get_product_by_id(&db, &config.shop.product_post_type, product_id).await?;
+    password.check(&record.product)?;
+    let unlocked = password.unlocks(&record.product);
+
+    let attachments = queries::get_attachments(&db, record.image_ids()).await?;
+    let uploads_url = queries::get_uploads_url(&db).await?;
+
+    let mut response = Product::from_record(record, &attachments, &uploads_url);
+    if !unlocked {
+        response.redact();
+    }
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+pub async fn get_menus(db: web::Data<Arc<DatabaseConnection>>) -> Result<HttpResponse, ApiError> {
+    let menus = queries::get_menus(&db).await?;
+    let locations = queries::get_menu_locations(&db).await?;
+
+    let response: Vec<Menu> = menus
+        .into_iter()
+        .map(|(taxonomy, term)| Menu::new(taxonomy, term, &locations))
+        .collect();
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+pub async fn get_menu(
+    path: web::Path<String>,
+    db: web::Data<Arc<DatabaseConnection>>,
+) -> Result<HttpResponse, ApiError> {
+    let slug = path.into_inner();
+
+    if slug.is_empty() {
+        return Err(ApiError::BadRequest(
+            "Menu slug cannot be empty".to_string(),
+        ));
+    }
+
+    let (taxonomy, term, items) = queries::get_menu_by_slug(&db, &slug).await?;
+    let locations = queries::get_menu_locations(&db).await?;
+
+    let response = MenuWithItems {
+        menu: Menu::new(taxonomy, term, &locations),
+        items: MenuItem::tree(items),
+    };
 
     Ok(HttpResponse::Ok().json(response))
 }
 
+#[derive(serde::Deserialize)]
+pub struct GetNetworkSitesQuery {
+    pub page: Option<u64>,
+    pub page_size: Option<u64>,
+}
+
 #[derive(serde::Deserialize)]
 pub struct GetPostsQuery {
     pub post_type: Option<String>,
@@ -323,6 +1869,62 @@ pub struct GetPostsQuery {
     pub page_size: Option<u64>,
     pub search: Option<String>,
     pub author_id: Option<u64>,
+    pub sticky: Option<String>,
+    pub meta_key: Option<String>,
+    pub meta_value: Option<String>,
+    pub meta_compare: Option<String>,
+    pub meta_type: Option<String>,
+    pub meta_relation: Option<String>,
+    pub lang: Option<String>,
+    pub embed: Option<String>,
+    pub shortcodes: Option<String>,
+}
+
+#[derive(serde::Deserialize)]
+pub struct GetPostQuery {
+    pub format: Option<String>,
+    pub shortcodes: Option<String>,
+}
+
+#[derive(serde::Deserialize)]
+pub struct GetPostChildrenQuery {
+    pub page: Option<u64>,
+    pub page_size: Option<u64>,
+    pub lang: Option<String>,
+    pub embed: Option<String>,
+    pub shortcodes: Option<String>,
+}
+
+#[derive(serde::Deserialize)]
+pub struct GetPostAncestorsQuery {
+    pub shortcodes: Option<String>,
+}
+
+#[derive(serde::Deserialize)]
+pub struct GetPageTreeQuery {
+    pub post_type: Option<String>,
+}
+
+#[derive(serde::Deserialize)]
+pub struct GetRevisionsQuery {
+    pub page: Option<u64>,
+    pub page_size: Option<u64>,
+}
+
+#[derive(serde::Deserialize)]
+pub struct GetRevisionDiffQuery {
+    pub against: Option<u64>,
+}
+
+#[derive(serde::Deserialize)]
+pub struct ResolvePathQuery {
+    pub path: String,
+    pub shortcodes: Option<String>,
+}
+
+#[derive(serde::Deserialize)]
+pub struct GetPostMetaQuery {
+    pub raw: Option<bool>,
 }
 
 #[derive(serde::Deserialize)]
@@ -331,12 +1933,26 @@ pub struct GetPostsTypeQuery {
     pub page: Option<u64>,
     pub page_size: Option<u64>,
     pub search: Option<String>,
+    pub meta_key: Option<String>,
+    pub meta_value: Option<String>,
+    pub meta_compare: Option<String>,
+    pub meta_type: Option<String>,
+    pub meta_relation: Option<String>,
+    pub lang: Option<String>,
+    pub embed: Option<String>,
+    pub shortcodes: Option<String>,
 }
 
 #[derive(serde::Deserialize)]
 pub struct GetCategoriesQuery {
     pub page: Option<u64>,
     pub page_size: Option<u64>,
+    pub lang: Option<String>,
+}
+
+#[derive(serde::Deserialize)]
+pub struct GetCategoryTreeQuery {
+    pub lang: Option<String>,
 }
 
 #[derive(serde::Deserialize)]
@@ -344,4 +1960,61 @@ pub struct GetPostsCategoryQuery {
     pub page: Option<u64>,
     pub page_size: Option<u64>,
     pub search: Option<String>,
+    pub include_children: Option<bool>,
+    pub lang: Option<String>,
+    pub embed: Option<String>,
+    pub shortcodes: Option<String>,
+}
+
+#[derive(serde::Deserialize)]
+pub struct GetTermsQuery {
+    pub page: Option<u64>,
+    pub page_size: Option<u64>,
+    pub search: Option<String>,
+    pub lang: Option<String>,
+}
+
+#[derive(serde::Deserialize)]
+pub struct GetAuthorsQuery {
+    pub page: Option<u64>,
+    pub page_size: Option<u64>,
+    pub search: Option<String>,
+}
+
+#[derive(serde::Deserialize)]
+pub struct GetPostsAuthorQuery {
+    pub post_type: Option<String>,
+    pub page: Option<u64>,
+    pub page_size: Option<u64>,
+    pub search: Option<String>,
+    pub lang: Option<String>,
+    pub embed: Option<String>,
+    pub shortcodes: Option<String>,
+}
+
+#[derive(serde::Deserialize)]
+pub struct GetCommentsQuery {
+    pub page: Option<u64>,
+    pub page_size: Option<u64>,
+}
+
+#[derive(serde::Deserialize)]
+pub struct GetMediaQuery {
+    pub page: Option<u64>,
+    pub page_size: Option<u64>,
+    pub search: Option<String>,
+    pub mime_type: Option<String>,
+    pub lang: Option<String>,
+}
+
+#[derive(serde::Deserialize)]
+pub struct GetProductsQuery {
+    pub page: Option<u64>,
+    pub page_size: Option<u64>,
+    pub search: Option<String>,
+    pub min_price: Option<f64>,
+    pub max_price: Option<f64>,
+    pub in_stock: Option<bool>,
+    pub attributes: Option<String>,
+    pub lang: Option<String>,
 }
@@ -1,3 +1,5 @@
+pub mod auth;
 pub mod handlers;
+pub mod multisite;
 pub mod responses;
 pub mod routes;
@@ -0,0 +1,65 @@
+use actix_web::body::MessageBody;
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::http::Uri;
+use actix_web::middleware::Next;
+use actix_web::{web, Error};
+use sea_orm::DatabaseConnection;
+use std::sync::Arc;
+
+use crate::config::Config;
+use crate::db::{queries, tables};
+use crate::error::ApiError;
+
+const SITES_PREFIX: &str = "/api/v1/sites/";
+
+/// Serves `/api/v1/sites/{blog_id}/...` from the tables of that site of the
+/// network. The request is rewritten to the matching `/api/v1/...` route
+/// before routing, so every endpoint is available per site without being
+/// registered twice.
+pub async fn route_to_site(
+    mut req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, Error> {
+    let Some((blog_id, rest)) = site_route(req.path()) else {
+        return next.call(req).await;
+    };
+
+    let multisite = req
+        .app_data::<web::Data<Config>>()
+        .is_some_and(|config| config.site.multisite);
+    if !multisite {
+        return Err(ApiError::NotFound("Multisite is not enabled".to_string()).into());
+    }
+
+    let blog_id: u64 = blog_id
+        .parse()
+        .map_err(|_| ApiError::BadRequest("Site ID must be a positive integer".to_string()))?;
+
+    let db = req
+        .app_data::<web::Data<Arc<DatabaseConnection>>>()
+        .cloned()
+        .ok_or_else(|| {
+            ApiError::InternalServerError("Database connection is not available".to_string())
+        })?;
+    queries::get_network_site(&db, blog_id).await?;
+
+    let uri = match req.uri().query() {
+        Some(query) => format!("/api/v1/{}?{}", rest, query),
+        None => format!("/api/v1/{}", rest),
+    };
+    let uri: Uri = uri
+        .parse()
+        .map_err(|_| ApiError::BadRequest("Invalid request path".to_string()))?;
+
+    req.head_mut().uri = uri.clone();
+    req.match_info_mut().get_mut().update(&uri);
+
+    tables::for_site(blog_id, next.call(req)).await
+}
+
+// Splits `/api/v1/sites/{blog_id}/{rest}`; the site endpoints themselves
+// (`/sites` and `/sites/{blog_id}`) are routed as they are.
+fn site_route(path: &str) -> Option<(String, String)> {
+    let (blog_id, rest) = path.strip_prefix(SITES_PREFIX)?.split_once('/')?;
+    (!rest.is_empty()).then(|| (blog_id.to_string(), rest.to_string()))
+}
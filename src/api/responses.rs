@@ -1,8 +1,10 @@
 use chrono::{DateTime, Utc};
 use serde::Serialize;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 
-use crate::models::{post, term, term_taxonomy};
+use crate::db::queries::{MenuItemRecord, PostWithMeta, ProductRecord, SiteWithOptions};
+use crate::models::{comment, post, term, term_relationship, term_taxonomy, user};
+use crate::utils::{acf, blocks, diff, php, seo, shortcodes};
 
 #[derive(Serialize)]
 pub struct RootResponse {
@@ -26,11 +28,36 @@ pub struct Post {
     pub post_modified: DateTime<Utc>,
     pub guid: String,
     pub post_name: String,
+    pub permalink: String,
+    pub post_parent: u64,
+    pub menu_order: i32,
     pub comment_count: i64,
+    pub sticky: bool,
+    pub protected: bool,
+    pub content: RenderedContent,
+    pub excerpt: RenderedContent,
+    pub featured_media: Option<FeaturedMedia>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub blocks: Option<Vec<blocks::Block>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub breadcrumbs: Option<Vec<Breadcrumb>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page_template: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub terms: Option<BTreeMap<String, Vec<Term>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub acf: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub translations: Option<BTreeMap<String, Translation>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seo: Option<seo::Seo>,
 }
 
 impl From<post::Model> for Post {
     fn from(model: post::Model) -> Self {
+        let content = RenderedContent::new(&model.post_content);
+        let excerpt = RenderedContent::new(&model.post_excerpt);
+
         Post {
             id: model.id,
             post_title: model.post_title,
@@ -39,21 +66,306 @@ impl From<post::Model> for Post {
             post_status: model.post_status,
             post_type: model.post_type,
             post_author: model.post_author,
-            post_date: DateTime::<Utc>::from_naive_utc_and_offset(
-                model.post_date.unwrap(),
-                Utc,
-            ),
+            post_date: DateTime::<Utc>::from_naive_utc_and_offset(model.post_date.unwrap(), Utc),
             post_modified: DateTime::<Utc>::from_naive_utc_and_offset(
                 model.post_modified.unwrap(),
                 Utc,
             ),
             guid: model.guid,
             post_name: model.post_name,
+            permalink: String::new(),
+            post_parent: model.post_parent,
+            menu_order: model.menu_order,
             comment_count: model.comment_count,
+            sticky: false,
+            protected: !model.post_password.is_empty(),
+            content,
+            excerpt,
+            featured_media: None,
+            blocks: None,
+            breadcrumbs: None,
+            page_template: None,
+            terms: None,
+            acf: None,
+            translations: None,
+            seo: None,
+        }
+    }
+}
+
+impl Post {
+    /// Empties the content, excerpt and custom fields of a password-protected
+    /// post that was requested without its password.
+    pub fn redact(&mut self) {
+        self.post_content.clear();
+        self.post_excerpt.clear();
+        self.content = RenderedContent::new("");
+        self.excerpt = RenderedContent::new("");
+        self.acf = self
+            .acf
+            .as_ref()
+            .map(|_| serde_json::Value::Object(Default::default()));
+    }
+}
+
+/// A post referenced by an ACF relationship, post object or page link field.
+#[derive(Serialize)]
+pub struct PostStub {
+    pub id: u64,
+    pub post_type: String,
+    pub title: String,
+    pub slug: String,
+    pub permalink: String,
+}
+
+/// Turns decoded ACF values into JSON, embedding the attachments and posts
+/// they refer to. References to attachments or posts that are missing or
+/// not published come out as `null`, or are left out of lists.
+pub struct AcfContext {
+    media: HashMap<u64, serde_json::Value>,
+    posts: HashMap<u64, serde_json::Value>,
+}
+
+impl AcfContext {
+    pub fn new(
+        media: HashMap<u64, PostWithMeta>,
+        posts: Vec<post::Model>,
+        mut links: HashMap<u64, String>,
+        uploads_url: &str,
+    ) -> Self {
+        let media = media
+            .into_iter()
+            .map(|(id, (attachment, meta))| {
+                let media = Media::from_attachment(attachment, meta, uploads_url);
+                (id, serde_json::to_value(media).unwrap_or_default())
+            })
+            .collect();
+
+        let posts = posts
+            .into_iter()
+            .map(|post| {
+                let stub = PostStub {
+                    id: post.id,
+                    permalink: links.remove(&post.id).unwrap_or_default(),
+                    post_type: post.post_type,
+                    title: post.post_title,
+                    slug: post.post_name,
+                };
+                (post.id, serde_json::to_value(stub).unwrap_or_default())
+            })
+            .collect();
+
+        AcfContext { media, posts }
+    }
+
+    pub fn object(&self, values: Vec<(String, acf::Value)>) -> serde_json::Value {
+        serde_json::Value::Object(
+            values
+                .into_iter()
+                .map(|(name, value)| (name, self.value(value)))
+                .collect(),
+        )
+    }
+
+    fn value(&self, value: acf::Value) -> serde_json::Value {
+        match value {
+            acf::Value::Null => serde_json::Value::Null,
+            acf::Value::Bool(value) => serde_json::Value::Bool(value),
+            acf::Value::Number(value) => serde_json::Value::Number(value),
+            acf::Value::String(value) => serde_json::Value::String(value),
+            acf::Value::List(values) => serde_json::Value::Array(
+                values
+                    .into_iter()
+                    .filter(|value| match value {
+                        acf::Value::Media(id) => self.media.contains_key(id),
+                        acf::Value::Post(id) => self.posts.contains_key(id),
+                        _ => true,
+                    })
+                    .map(|value| self.value(value))
+                    .collect(),
+            ),
+            acf::Value::Object(values) => self.object(values),
+            acf::Value::Media(id) => self.media.get(&id).cloned().unwrap_or_default(),
+            acf::Value::Post(id) => self.posts.get(&id).cloned().unwrap_or_default(),
+        }
+    }
+}
+
+/// A translation of a post, keyed by its language code in the post's
+/// `translations`.
+#[derive(Serialize)]
+pub struct Translation {
+    pub id: u64,
+    pub slug: String,
+}
+
+impl Translation {
+    pub fn by_language(posts: Vec<(String, post::Model)>) -> BTreeMap<String, Translation> {
+        posts
+            .into_iter()
+            .map(|(lang, post)| {
+                let translation = Translation {
+                    id: post.id,
+                    slug: post.post_name,
+                };
+                (lang, translation)
+            })
+            .collect()
+    }
+}
+
+/// Stored content next to what the WordPress front-end outputs for it.
+#[derive(Serialize)]
+pub struct RenderedContent {
+    pub raw: String,
+    pub rendered: String,
+}
+
+impl RenderedContent {
+    fn new(raw: &str) -> Self {
+        RenderedContent {
+            raw: raw.to_string(),
+            rendered: String::new(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct Breadcrumb {
+    pub id: u64,
+    pub title: String,
+    pub slug: String,
+}
+
+impl From<&post::Model> for Breadcrumb {
+    fn from(model: &post::Model) -> Self {
+        Breadcrumb {
+            id: model.id,
+            title: model.post_title.clone(),
+            slug: model.post_name.clone(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct PageTreeNode {
+    pub id: u64,
+    pub title: String,
+    pub slug: String,
+    pub menu_order: i32,
+    pub children: Vec<PageTreeNode>,
+}
+
+impl PageTreeNode {
+    // Pages whose parent is not published are promoted to the top level so
+    // that their subtree stays reachable.
+    pub fn tree(pages: Vec<post::PageSummary>) -> Vec<PageTreeNode> {
+        let page_ids: HashSet<u64> = pages.iter().map(|page| page.id).collect();
+
+        let mut roots = Vec::new();
+        let mut children: HashMap<u64, Vec<PageTreeNode>> = HashMap::new();
+
+        for page in pages {
+            let parent = page.post_parent;
+            let node = PageTreeNode::from(page);
+            if parent != 0 && parent != node.id && page_ids.contains(&parent) {
+                children.entry(parent).or_default().push(node);
+            } else {
+                roots.push(node);
+            }
+        }
+
+        roots
+            .into_iter()
+            .map(|node| node.attach_children(&mut children))
+            .collect()
+    }
+
+    fn attach_children(mut self, children: &mut HashMap<u64, Vec<PageTreeNode>>) -> Self {
+        self.children = children
+            .remove(&self.id)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|child| child.attach_children(children))
+            .collect();
+        self
+    }
+}
+
+impl From<post::PageSummary> for PageTreeNode {
+    fn from(page: post::PageSummary) -> Self {
+        PageTreeNode {
+            id: page.id,
+            title: page.post_title,
+            slug: page.post_name,
+            menu_order: page.menu_order,
+            children: Vec::new(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct Revision {
+    pub id: u64,
+    pub parent: u64,
+    pub author: u64,
+    #[serde(with = "chrono::serde::ts_seconds_option")]
+    pub date: Option<DateTime<Utc>>,
+    pub title: String,
+    pub excerpt: String,
+    pub content: String,
+}
+
+impl From<post::Model> for Revision {
+    fn from(model: post::Model) -> Self {
+        Revision {
+            id: model.id,
+            parent: model.post_parent,
+            author: model.post_author,
+            date: model
+                .post_date
+                .map(|date| DateTime::<Utc>::from_naive_utc_and_offset(date, Utc)),
+            title: model.post_title,
+            excerpt: model.post_excerpt,
+            content: model.post_content,
         }
     }
 }
 
+#[derive(Serialize)]
+pub struct RevisionDiff {
+    pub from: Option<u64>,
+    pub to: u64,
+    pub title: Vec<diff::Change>,
+    pub excerpt: Vec<diff::Change>,
+    pub content: Vec<diff::Change>,
+}
+
+impl RevisionDiff {
+    pub fn new(from: Option<&post::Model>, to: &post::Model) -> Self {
+        let field = |value: fn(&post::Model) -> &str| {
+            diff::diff_words(from.map(value).unwrap_or_default(), value(to))
+        };
+
+        RevisionDiff {
+            from: from.map(|from| from.id),
+            to: to.id,
+            title: field(|model| &model.post_title),
+            excerpt: field(|model| &model.post_excerpt),
+            content: field(|model| &model.post_content),
+        }
+    }
+}
+
+/// What `/resolve` found at a path, tagged with its kind.
+#[derive(Serialize)]
+#[serde(tag = "type", content = "item", rename_all = "lowercase")]
+pub enum Resolved {
+    Post(Box<Post>),
+    Term(Term),
+    Author(Author),
+}
+
 #[derive(Serialize)]
 pub struct PaginatedResponse<T> {
     pub items: Vec<T>,
@@ -67,11 +379,7 @@ pub struct PaginatedResponse<T> {
 
 impl<T> PaginatedResponse<T> {
     pub fn new(items: Vec<T>, total: u64, page: u64, size: u64) -> Self {
-        let pages = if size > 0 {
-            (total + size - 1) / size
-        } else {
-            0
-        };
+        let pages = if size > 0 { total.div_ceil(size) } else { 0 };
 
         PaginatedResponse {
             items,
@@ -94,7 +402,69 @@ pub struct PostType {
 
 #[derive(Serialize)]
 pub struct PostMeta {
-    pub meta: HashMap<String, String>,
+    pub meta: HashMap<String, serde_json::Value>,
+}
+
+impl PostMeta {
+    pub fn new(meta: HashMap<String, String>, raw: bool) -> Self {
+        let meta = meta
+            .into_iter()
+            .map(|(key, value)| {
+                let value = if raw {
+                    serde_json::Value::String(value)
+                } else {
+                    php::maybe_unserialize(&value)
+                };
+                (key, value)
+            })
+            .collect();
+
+        PostMeta { meta }
+    }
+}
+
+#[derive(Serialize)]
+pub struct SiteSettings {
+    pub options: HashMap<String, String>,
+}
+
+/// A site of a multisite network, from `wp_blogs` and its own options.
+#[derive(Serialize)]
+pub struct NetworkSite {
+    pub id: u64,
+    pub name: String,
+    pub description: String,
+    pub url: String,
+    pub domain: String,
+    pub path: String,
+    pub public: bool,
+    #[serde(with = "chrono::serde::ts_seconds_option")]
+    pub registered: Option<DateTime<Utc>>,
+    #[serde(with = "chrono::serde::ts_seconds_option")]
+    pub last_updated: Option<DateTime<Utc>>,
+}
+
+impl From<SiteWithOptions> for NetworkSite {
+    fn from(data: SiteWithOptions) -> Self {
+        let (blog, mut options) = data;
+        let mut option = |name: &str| options.remove(name).unwrap_or_default();
+
+        NetworkSite {
+            id: blog.blog_id,
+            name: option("blogname"),
+            description: option("blogdescription"),
+            url: option("home"),
+            domain: blog.domain,
+            path: blog.path,
+            public: blog.public == 1,
+            registered: blog
+                .registered
+                .map(|date| DateTime::<Utc>::from_naive_utc_and_offset(date, Utc)),
+            last_updated: blog
+                .last_updated
+                .map(|date| DateTime::<Utc>::from_naive_utc_and_offset(date, Utc)),
+        }
+    }
 }
 
 #[derive(Serialize)]
@@ -105,6 +475,8 @@ pub struct Category {
     pub description: String,
     pub parent: u64,
     pub count: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seo: Option<seo::Seo>,
 }
 
 impl From<(term_taxonomy::Model, term::Model)> for Category {
@@ -118,6 +490,794 @@ impl From<(term_taxonomy::Model, term::Model)> for Category {
             description: taxonomy.description,
             parent: taxonomy.parent,
             count: taxonomy.count,
+            seo: None,
+        }
+    }
+}
+#[derive(Serialize)]
+pub struct CategoryTreeNode {
+    pub term_id: u64,
+    pub name: String,
+    pub slug: String,
+    pub description: String,
+    pub count: i32,
+    /// Distinct published posts in the category and all its descendants, as
+    /// with `pad_counts` in `get_terms()`.
+    pub total_count: usize,
+    pub children: Vec<CategoryTreeNode>,
+}
+
+impl CategoryTreeNode {
+    // Categories whose parent no longer exists are shown at the top level.
+    pub fn tree(
+        categories: Vec<(term_taxonomy::Model, term::Model)>,
+        posts: &HashMap<u64, Vec<u64>>,
+    ) -> Vec<CategoryTreeNode> {
+        let term_ids: HashSet<u64> = categories.iter().map(|(_, term)| term.term_id).collect();
+
+        let mut roots = Vec::new();
+        let mut children: HashMap<u64, Vec<CategoryTreeNode>> = HashMap::new();
+
+        for (taxonomy, term) in categories {
+            let parent = taxonomy.parent;
+            let node = CategoryTreeNode {
+                term_id: term.term_id,
+                name: term.name,
+                slug: term.slug,
+                description: taxonomy.description,
+                count: taxonomy.count,
+                total_count: 0,
+                children: Vec::new(),
+            };
+            if parent != 0 && parent != node.term_id && term_ids.contains(&parent) {
+                children.entry(parent).or_default().push(node);
+            } else {
+                roots.push(node);
+            }
+        }
+
+        roots
+            .into_iter()
+            .map(|node| {
+                let mut node = node.attach_children(&mut children);
+                node.aggregate(posts);
+                node
+            })
+            .collect()
+    }
+
+    fn attach_children(mut self, children: &mut HashMap<u64, Vec<CategoryTreeNode>>) -> Self {
+        self.children = children
+            .remove(&self.term_id)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|child| child.attach_children(children))
+            .collect();
+        self
+    }
+
+    // Returns the posts of the subtree so a post filed under several of its
+    // categories is counted once.
+    fn aggregate(&mut self, posts: &HashMap<u64, Vec<u64>>) -> HashSet<u64> {
+        let mut subtree: HashSet<u64> = posts
+            .get(&self.term_id)
+            .into_iter()
+            .flatten()
+            .copied()
+            .collect();
+
+        for child in &mut self.children {
+            subtree.extend(child.aggregate(posts));
+        }
+
+        self.total_count = subtree.len();
+        subtree
+    }
+}
+
+#[derive(Serialize)]
+pub struct Taxonomy {
+    pub name: String,
+    pub term_count: i64,
+    pub count: i64,
+}
+
+#[derive(Serialize)]
+pub struct Term {
+    pub term_id: u64,
+    pub term_taxonomy_id: u64,
+    pub taxonomy: String,
+    pub name: String,
+    pub slug: String,
+    pub description: String,
+    pub parent: u64,
+    pub count: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seo: Option<seo::Seo>,
+}
+
+impl From<(term_taxonomy::Model, term::Model)> for Term {
+    fn from(data: (term_taxonomy::Model, term::Model)) -> Self {
+        let (taxonomy, term) = data;
+
+        Term {
+            term_id: term.term_id,
+            term_taxonomy_id: taxonomy.term_taxonomy_id,
+            taxonomy: taxonomy.taxonomy,
+            name: term.name,
+            slug: term.slug,
+            description: taxonomy.description,
+            parent: taxonomy.parent,
+            count: taxonomy.count,
+            seo: None,
+        }
+    }
+}
+
+impl Term {
+    /// Groups the terms of a post by taxonomy name.
+    pub fn by_taxonomy(terms: Vec<term_relationship::ObjectTerm>) -> BTreeMap<String, Vec<Term>> {
+        let mut grouped: BTreeMap<String, Vec<Term>> = BTreeMap::new();
+        for term in terms {
+            grouped
+                .entry(term.taxonomy.clone())
+                .or_default()
+                .push(Term::from(term));
+        }
+        grouped
+    }
+}
+
+impl From<term_relationship::ObjectTerm> for Term {
+    fn from(term: term_relationship::ObjectTerm) -> Self {
+        Term {
+            term_id: term.term_id,
+            term_taxonomy_id: term.term_taxonomy_id,
+            taxonomy: term.taxonomy,
+            name: term.name,
+            slug: term.slug,
+            description: term.description,
+            parent: term.parent,
+            count: term.count,
+            seo: None,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct Author {
+    pub id: u64,
+    pub display_name: String,
+    pub user_nicename: String,
+    pub description: String,
+    pub gravatar_hash: String,
+    pub post_count: i64,
+}
+
+impl From<user::AuthorProfile> for Author {
+    fn from(profile: user::AuthorProfile) -> Self {
+        Author {
+            id: profile.user.id,
+            display_name: profile.user.display_name,
+            user_nicename: profile.user.user_nicename,
+            description: profile.description,
+            gravatar_hash: profile.gravatar_hash,
+            post_count: profile.post_count,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct Comment {
+    pub id: u64,
+    pub post_id: u64,
+    pub parent: u64,
+    pub author_name: String,
+    pub author_url: String,
+    pub user_id: u64,
+    #[serde(with = "chrono::serde::ts_seconds_option")]
+    pub date: Option<DateTime<Utc>>,
+    pub content: String,
+    pub comment_type: String,
+    pub rating: Option<u8>,
+    pub replies: Vec<Comment>,
+}
+
+impl From<comment::CommentThread> for Comment {
+    fn from(thread: comment::CommentThread) -> Self {
+        let model = thread.comment;
+
+        Comment {
+            id: model.comment_id,
+            post_id: model.comment_post_id,
+            parent: model.comment_parent,
+            author_name: model.comment_author,
+            author_url: model.comment_author_url,
+            user_id: model.user_id,
+            date: model
+                .comment_date_gmt
+                .or(model.comment_date)
+                .map(|date| DateTime::<Utc>::from_naive_utc_and_offset(date, Utc)),
+            content: model.comment_content,
+            comment_type: model.comment_type,
+            rating: thread.rating.and_then(|rating| rating.trim().parse().ok()),
+            replies: thread.replies.into_iter().map(Comment::from).collect(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct MediaSize {
+    pub url: String,
+    pub width: Option<u64>,
+    pub height: Option<u64>,
+    pub mime_type: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct Media {
+    pub id: u64,
+    pub title: String,
+    pub caption: String,
+    pub description: String,
+    pub alt: String,
+    pub mime_type: String,
+    pub url: String,
+    pub width: Option<u64>,
+    pub height: Option<u64>,
+    pub filesize: Option<u64>,
+    pub sizes: HashMap<String, MediaSize>,
+    pub parent: u64,
+    #[serde(with = "chrono::serde::ts_seconds_option")]
+    pub date: Option<DateTime<Utc>>,
+}
+
+impl Media {
+    pub fn from_attachment(
+        model: post::Model,
+        meta: HashMap<String, String>,
+        uploads_url: &str,
+    ) -> Self {
+        let attached_file = meta.get("_wp_attached_file").cloned().unwrap_or_default();
+        let metadata = meta
+            .get("_wp_attachment_metadata")
+            .and_then(|value| php::unserialize(value))
+            .unwrap_or_default();
+
+        let url = if attached_file.is_empty() {
+            model.guid.clone()
+        } else {
+            Self::upload_url(uploads_url, &attached_file)
+        };
+
+        // Generated sizes live next to the original file and only store
+        // their own file name.
+        let directory = match attached_file.rfind('/') {
+            Some(index) => &attached_file[..=index],
+            None => "",
+        };
+
+        let sizes = metadata
+            .get("sizes")
+            .and_then(|sizes| sizes.as_object())
+            .map(|sizes| {
+                sizes
+                    .iter()
+                    .filter_map(|(name, size)| {
+                        let file = size.get("file")?.as_str()?;
+                        Some((
+                            name.clone(),
+                            MediaSize {
+                                url: Self::upload_url(
+                                    uploads_url,
+                                    &format!("{}{}", directory, file),
+                                ),
+                                width: Self::number(size.get("width")),
+                                height: Self::number(size.get("height")),
+                                mime_type: size
+                                    .get("mime-type")
+                                    .and_then(|mime_type| mime_type.as_str())
+                                    .map(str::to_string),
+                            },
+                        ))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Media {
+            id: model.id,
+            title: model.post_title,
+            caption: model.post_excerpt,
+            description: model.post_content,
+            alt: meta
+                .get("_wp_attachment_image_alt")
+                .cloned()
+                .unwrap_or_default(),
+            mime_type: model.post_mime_type,
+            url,
+            width: Self::number(metadata.get("width")),
+            height: Self::number(metadata.get("height")),
+            filesize: Self::number(metadata.get("filesize")),
+            sizes,
+            parent: model.post_parent,
+            date: model
+                .post_date_gmt
+                .or(model.post_date)
+                .map(|date| DateTime::<Utc>::from_naive_utc_and_offset(date, Utc)),
+        }
+    }
+
+    fn upload_url(uploads_url: &str, file: &str) -> String {
+        // Offload plugins sometimes store absolute URLs instead of paths
+        // relative to the uploads directory.
+        if file.starts_with("http://") || file.starts_with("https://") {
+            file.to_string()
+        } else {
+            format!("{}/{}", uploads_url, file.trim_start_matches('/'))
+        }
+    }
+
+    fn number(value: Option<&serde_json::Value>) -> Option<u64> {
+        match value? {
+            serde_json::Value::Number(number) => number.as_u64(),
+            serde_json::Value::String(string) => string.trim().parse().ok(),
+            _ => None,
+        }
+    }
+}
+
+impl From<Media> for shortcodes::GalleryImage {
+    fn from(media: Media) -> Self {
+        shortcodes::GalleryImage {
+            url: media.url,
+            alt: media.alt,
+            caption: media.caption,
+            sizes: media
+                .sizes
+                .into_iter()
+                .map(|(name, size)| (name, size.url))
+                .collect(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct FeaturedMedia {
+    pub id: u64,
+    pub url: String,
+    pub alt: String,
+    pub width: Option<u64>,
+    pub height: Option<u64>,
+    pub sizes: HashMap<String, MediaSize>,
+}
+
+impl From<Media> for FeaturedMedia {
+    fn from(media: Media) -> Self {
+        FeaturedMedia {
+            id: media.id,
+            url: media.url,
+            alt: media.alt,
+            width: media.width,
+            height: media.height,
+            sizes: media.sizes,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct ProductAttribute {
+    pub name: String,
+    pub taxonomy: Option<String>,
+    pub visible: bool,
+    pub variation: bool,
+    pub options: Vec<String>,
+    pub terms: Vec<Term>,
+}
+
+#[derive(Serialize)]
+pub struct ProductVariation {
+    pub id: u64,
+    pub sku: String,
+    pub price: Option<String>,
+    pub regular_price: Option<String>,
+    pub sale_price: Option<String>,
+    pub on_sale: bool,
+    pub stock_status: String,
+    pub stock_quantity: Option<i64>,
+    pub image: Option<FeaturedMedia>,
+    pub attributes: HashMap<String, String>,
+}
+
+#[derive(Serialize)]
+pub struct Product {
+    pub id: u64,
+    pub name: String,
+    pub slug: String,
+    pub description: String,
+    pub short_description: String,
+    #[serde(with = "chrono::serde::ts_seconds_option")]
+    pub date: Option<DateTime<Utc>>,
+    pub protected: bool,
+    pub sku: String,
+    pub price: Option<String>,
+    pub regular_price: Option<String>,
+    pub sale_price: Option<String>,
+    pub on_sale: bool,
+    pub stock_status: String,
+    pub stock_quantity: Option<i64>,
+    pub images: Vec<FeaturedMedia>,
+    pub attributes: Vec<ProductAttribute>,
+    pub variations: Vec<ProductVariation>,
+}
+
+impl Product {
+    /// Empties the descriptions of a password-protected product that was
+    /// requested without its password, as `Post::redact` does for posts.
+    pub fn redact(&mut self) {
+        self.description.clear();
+        self.short_description.clear();
+    }
+
+    pub fn from_record(
+        record: ProductRecord,
+        attachments: &HashMap<u64, PostWithMeta>,
+        uploads_url: &str,
+    ) -> Self {
+        let ProductRecord {
+            product,
+            meta,
+            attribute_terms,
+            variations,
+        } = record;
+
+        let image = |id: u64| {
+            attachments.get(&id).map(|(attachment, attachment_meta)| {
+                FeaturedMedia::from(Media::from_attachment(
+                    attachment.clone(),
+                    attachment_meta.clone(),
+                    uploads_url,
+                ))
+            })
+        };
+
+        let images = meta
+            .get("_thumbnail_id")
+            .into_iter()
+            .chain(meta.get("_product_image_gallery"))
+            .flat_map(|ids| ids.split(','))
+            .filter_map(|id| id.trim().parse::<u64>().ok())
+            .filter_map(image)
+            .collect();
+
+        let variations = variations
+            .into_iter()
+            .map(|(variation, variation_meta)| {
+                let (price, regular_price, sale_price) = Self::prices(&variation_meta);
+
+                ProductVariation {
+                    id: variation.id,
+                    sku: Self::meta_string(&variation_meta, "_sku").unwrap_or_default(),
+                    on_sale: Self::is_on_sale(&regular_price, &sale_price),
+                    price,
+                    regular_price,
+                    sale_price,
+                    stock_status: Self::meta_string(&variation_meta, "_stock_status")
+                        .unwrap_or_else(|| "instock".to_string()),
+                    stock_quantity: Self::stock_quantity(&variation_meta),
+                    image: variation_meta
+                        .get("_thumbnail_id")
+                        .and_then(|id| id.trim().parse::<u64>().ok())
+                        .and_then(image),
+                    attributes: variation_meta
+                        .iter()
+                        .filter_map(|(key, value)| {
+                            let name = key.strip_prefix("attribute_")?;
+                            Some((name.to_string(), value.clone()))
+                        })
+                        .collect(),
+                }
+            })
+            .collect();
+
+        let (price, regular_price, sale_price) = Self::prices(&meta);
+
+        Product {
+            id: product.id,
+            name: product.post_title,
+            slug: product.post_name,
+            description: product.post_content,
+            short_description: product.post_excerpt,
+            date: product
+                .post_date_gmt
+                .or(product.post_date)
+                .map(|date| DateTime::<Utc>::from_naive_utc_and_offset(date, Utc)),
+            protected: !product.post_password.is_empty(),
+            sku: Self::meta_string(&meta, "_sku").unwrap_or_default(),
+            on_sale: Self::is_on_sale(&regular_price, &sale_price),
+            price,
+            regular_price,
+            sale_price,
+            stock_status: Self::meta_string(&meta, "_stock_status")
+                .unwrap_or_else(|| "instock".to_string()),
+            stock_quantity: Self::stock_quantity(&meta),
+            images,
+            attributes: Self::attributes(&meta, attribute_terms),
+            variations,
+        }
+    }
+
+    // Taxonomy attributes (`pa_*`) take their options from the assigned
+    // terms; custom attributes keep theirs inline as "a | b | c".
+    fn attributes(
+        meta: &HashMap<String, String>,
+        attribute_terms: Vec<term_relationship::ObjectTerm>,
+    ) -> Vec<ProductAttribute> {
+        let definitions = meta
+            .get("_product_attributes")
+            .and_then(|value| php::unserialize(value))
+            .and_then(|value| value.as_object().cloned())
+            .unwrap_or_default();
+
+        let mut definitions: Vec<&serde_json::Value> = definitions.values().collect();
+        definitions.sort_by_key(|definition| {
+            definition
+                .get("position")
+                .and_then(|position| match position {
+                    serde_json::Value::Number(number) => number.as_i64(),
+                    serde_json::Value::String(string) => string.parse().ok(),
+                    _ => None,
+                })
+                .unwrap_or(0)
+        });
+
+        let flag = |definition: &serde_json::Value, key: &str| match definition.get(key) {
+            Some(serde_json::Value::Bool(value)) => *value,
+            Some(serde_json::Value::Number(number)) => number.as_i64() == Some(1),
+            Some(serde_json::Value::String(string)) => string == "1",
+            _ => false,
+        };
+
+        let mut attributes: Vec<ProductAttribute> = definitions
+            .into_iter()
+            .filter_map(|definition| {
+                let name = definition.get("name")?.as_str()?.to_string();
+                let is_taxonomy = flag(definition, "is_taxonomy");
+
+                let terms: Vec<Term> = attribute_terms
+                    .iter()
+                    .filter(|term| is_taxonomy && term.taxonomy == name)
+                    .cloned()
+                    .map(Term::from)
+                    .collect();
+
+                let options = if is_taxonomy {
+                    terms.iter().map(|term| term.name.clone()).collect()
+                } else {
+                    definition
+                        .get("value")
+                        .and_then(|value| value.as_str())
+                        .unwrap_or_default()
+                        .split('|')
+                        .map(|option| option.trim().to_string())
+                        .filter(|option| !option.is_empty())
+                        .collect()
+                };
+
+                Some(ProductAttribute {
+                    taxonomy: is_taxonomy.then(|| name.clone()),
+                    name,
+                    visible: flag(definition, "is_visible"),
+                    variation: flag(definition, "is_variation"),
+                    options,
+                    terms,
+                })
+            })
+            .collect();
+
+        // Attribute terms can be assigned without a matching definition
+        // (e.g. by imports); expose those as well.
+        for term in attribute_terms {
+            match attributes
+                .iter_mut()
+                .find(|attribute| attribute.taxonomy.as_deref() == Some(term.taxonomy.as_str()))
+            {
+                Some(attribute) => {
+                    if !attribute
+                        .terms
+                        .iter()
+                        .any(|existing| existing.term_taxonomy_id == term.term_taxonomy_id)
+                    {
+                        attribute.options.push(term.name.clone());
+                        attribute.terms.push(Term::from(term));
+                    }
+                }
+                None => attributes.push(ProductAttribute {
+                    name: term.taxonomy.clone(),
+                    taxonomy: Some(term.taxonomy.clone()),
+                    visible: true,
+                    variation: false,
+                    options: vec![term.name.clone()],
+                    terms: vec![Term::from(term)],
+                }),
+            }
+        }
+
+        attributes
+    }
+
+    fn prices(meta: &HashMap<String, String>) -> (Option<String>, Option<String>, Option<String>) {
+        (
+            Self::meta_string(meta, "_price"),
+            Self::meta_string(meta, "_regular_price"),
+            Self::meta_string(meta, "_sale_price"),
+        )
+    }
+
+    fn is_on_sale(regular_price: &Option<String>, sale_price: &Option<String>) -> bool {
+        match (regular_price, sale_price) {
+            (Some(regular_price), Some(sale_price)) => {
+                match (regular_price.parse::<f64>(), sale_price.parse::<f64>()) {
+                    (Ok(regular_price), Ok(sale_price)) => sale_price < regular_price,
+                    _ => false,
+                }
+            }
+            _ => false,
+        }
+    }
+
+    fn stock_quantity(meta: &HashMap<String, String>) -> Option<i64> {
+        if Self::meta_string(meta, "_manage_stock").as_deref() != Some("yes") {
+            return None;
+        }
+
+        Self::meta_string(meta, "_stock")?
+            .parse::<f64>()
+            .ok()
+            .map(|stock| stock as i64)
+    }
+
+    fn meta_string(meta: &HashMap<String, String>, key: &str) -> Option<String> {
+        meta.get(key)
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+    }
+}
+
+#[derive(Serialize)]
+pub struct Menu {
+    pub term_id: u64,
+    pub name: String,
+    pub slug: String,
+    pub description: String,
+    pub count: i32,
+    pub locations: Vec<String>,
+}
+
+impl Menu {
+    pub fn new(
+        taxonomy: term_taxonomy::Model,
+        term: term::Model,
+        locations: &HashMap<u64, Vec<String>>,
+    ) -> Self {
+        Menu {
+            locations: locations.get(&term.term_id).cloned().unwrap_or_default(),
+            term_id: term.term_id,
+            name: term.name,
+            slug: term.slug,
+            description: taxonomy.description,
+            count: taxonomy.count,
         }
     }
-}
\ No newline at end of file
+}
+
+#[derive(Serialize)]
+pub struct MenuItem {
+    pub id: u64,
+    pub title: String,
+    pub url: Option<String>,
+    pub target: String,
+    pub classes: Vec<String>,
+    pub item_type: String,
+    pub object: String,
+    pub object_id: u64,
+    pub object_slug: Option<String>,
+    pub parent: u64,
+    pub menu_order: i32,
+    pub children: Vec<MenuItem>,
+}
+
+impl MenuItem {
+    // Items reference their parent by menu item ID; items whose parent is
+    // missing from the menu are promoted to the top level.
+    pub fn tree(records: Vec<MenuItemRecord>) -> Vec<MenuItem> {
+        let item_ids: Vec<u64> = records.iter().map(|record| record.item.id).collect();
+
+        let mut roots = Vec::new();
+        let mut children: HashMap<u64, Vec<MenuItem>> = HashMap::new();
+
+        for record in records {
+            let item = MenuItem::from(record);
+            if item.parent != 0 && item.parent != item.id && item_ids.contains(&item.parent) {
+                children.entry(item.parent).or_default().push(item);
+            } else {
+                roots.push(item);
+            }
+        }
+
+        roots
+            .into_iter()
+            .map(|item| item.attach_children(&mut children))
+            .collect()
+    }
+
+    fn attach_children(mut self, children: &mut HashMap<u64, Vec<MenuItem>>) -> Self {
+        self.children = children
+            .remove(&self.id)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|child| child.attach_children(children))
+            .collect();
+        self
+    }
+}
+
+impl From<MenuItemRecord> for MenuItem {
+    fn from(record: MenuItemRecord) -> Self {
+        let MenuItemRecord {
+            item,
+            meta,
+            object_title,
+            object_slug,
+        } = record;
+
+        let meta_value = |key: &str| meta.get(key).cloned().unwrap_or_default();
+
+        let title = if item.post_title.is_empty() {
+            object_title.unwrap_or_default()
+        } else {
+            item.post_title
+        };
+
+        let url = Some(meta_value("_menu_item_url")).filter(|url| !url.is_empty());
+
+        let classes = meta
+            .get("_menu_item_classes")
+            .map(|classes| php::maybe_unserialize(classes))
+            .and_then(|classes| match classes {
+                serde_json::Value::Array(classes) => Some(
+                    classes
+                        .into_iter()
+                        .filter_map(|class| class.as_str().map(str::to_string))
+                        .filter(|class| !class.is_empty())
+                        .collect(),
+                ),
+                _ => None,
+            })
+            .unwrap_or_default();
+
+        MenuItem {
+            id: item.id,
+            title,
+            url,
+            target: meta_value("_menu_item_target"),
+            classes,
+            item_type: meta_value("_menu_item_type"),
+            object: meta_value("_menu_item_object"),
+            object_id: meta_value("_menu_item_object_id").parse().unwrap_or(0),
+            object_slug,
+            parent: meta_value("_menu_item_menu_item_parent")
+                .parse()
+                .unwrap_or(0),
+            menu_order: item.menu_order,
+            children: Vec::new(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct MenuWithItems {
+    #[serde(flatten)]
+    pub menu: Menu,
+    pub items: Vec<MenuItem>,
+}
@@ -1,24 +1,92 @@
-use actix_web::web;
 use super::handlers;
+use actix_web::web;
 
 pub fn configure(cfg: &mut web::ServiceConfig) {
     cfg.route("/", web::get().to(handlers::root)).service(
         web::scope("/api/v1")
+            .route("/site", web::get().to(handlers::get_site))
+            .route("/resolve", web::get().to(handlers::resolve_path))
+            .route("/sites", web::get().to(handlers::get_network_sites))
+            .route(
+                "/sites/{blog_id}",
+                web::get().to(handlers::get_network_site),
+            )
             .route("/posts", web::get().to(handlers::get_posts))
             .route("/posts/{post_id}", web::get().to(handlers::get_post))
             .route(
                 "/posts/{post_id}/meta",
                 web::get().to(handlers::get_post_meta),
             )
+            .route(
+                "/posts/{post_id}/terms",
+                web::get().to(handlers::get_post_terms),
+            )
+            .route(
+                "/posts/{post_id}/comments",
+                web::get().to(handlers::get_post_comments),
+            )
+            .route(
+                "/posts/{post_id}/children",
+                web::get().to(handlers::get_post_children),
+            )
+            .route(
+                "/posts/{post_id}/ancestors",
+                web::get().to(handlers::get_post_ancestors),
+            )
+            .route(
+                "/posts/{post_id}/revisions",
+                web::get().to(handlers::get_post_revisions),
+            )
+            .route(
+                "/posts/{post_id}/revisions/{revision_id}/diff",
+                web::get().to(handlers::get_revision_diff),
+            )
+            .route("/pages/tree", web::get().to(handlers::get_page_tree))
             .route("/post-types", web::get().to(handlers::get_post_types))
             .route(
                 "/post-types/{post_type}/posts",
                 web::get().to(handlers::get_posts_by_type),
             )
             .route("/categories", web::get().to(handlers::get_categories))
+            .route(
+                "/categories/tree",
+                web::get().to(handlers::get_category_tree),
+            )
+            .route(
+                "/categories/by-slug/{slug}",
+                web::get().to(handlers::get_category_by_slug),
+            )
+            .route(
+                "/categories/by-slug/{slug}/posts",
+                web::get().to(handlers::get_posts_by_category_slug),
+            )
             .route(
                 "/categories/{category_id}/posts",
                 web::get().to(handlers::get_posts_by_category),
+            )
+            .route("/taxonomies", web::get().to(handlers::get_taxonomies))
+            .route(
+                "/taxonomies/{taxonomy}/terms",
+                web::get().to(handlers::get_terms),
+            )
+            .route(
+                "/taxonomies/{taxonomy}/terms/{term_id}/posts",
+                web::get().to(handlers::get_posts_by_term),
+            )
+            .route("/menus", web::get().to(handlers::get_menus))
+            .route("/menus/{slug}", web::get().to(handlers::get_menu))
+            .route("/products", web::get().to(handlers::get_products))
+            .route(
+                "/products/{product_id}",
+                web::get().to(handlers::get_product),
+            )
+            .route("/media", web::get().to(handlers::get_media))
+            .route("/media/{media_id}", web::get().to(handlers::get_media_item))
+            .route("/authors", web::get().to(handlers::get_authors))
+            .route("/authors/{author_id}", web::get().to(handlers::get_author))
+            .route(
+                "/authors/{author_id}/posts",
+                web::get().to(handlers::get_posts_by_author),
             ),
     );
-}
\ No newline at end of file
+}
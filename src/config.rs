@@ -1,15 +1,20 @@
 use serde::Deserialize;
+use std::collections::HashMap;
 use std::env;
 
 #[derive(Debug, Deserialize, Clone)]
 pub struct Config {
     pub database: DatabaseConfig,
     pub server: ServerConfig,
+    pub site: SiteConfig,
+    pub shop: ShopConfig,
+    pub auth: AuthConfig,
 }
 
 #[derive(Debug, Deserialize, Clone)]
 pub struct DatabaseConfig {
     pub url: String,
+    pub table_prefix: String,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -18,13 +23,55 @@ pub struct ServerConfig {
     pub port: u16,
 }
 
+#[derive(Debug, Deserialize, Clone)]
+pub struct SiteConfig {
+    pub option_allowlist: Vec<String>,
+    pub post_type_bases: HashMap<String, String>,
+    pub hierarchical_post_types: Vec<String>,
+    pub multisite: bool,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct ShopConfig {
+    pub product_post_type: String,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct AuthConfig {
+    pub api_keys: Vec<String>,
+}
+
+const DEFAULT_SITE_OPTIONS: [&str; 9] = [
+    "blogname",
+    "blogdescription",
+    "siteurl",
+    "home",
+    "timezone_string",
+    "gmt_offset",
+    "permalink_structure",
+    "posts_per_page",
+    "WPLANG",
+];
+
+impl SiteConfig {
+    /// Whether posts of a type can have parents: pages and the configured
+    /// hierarchical post types.
+    pub fn is_hierarchical(&self, post_type: &str) -> bool {
+        post_type == "page"
+            || self
+                .hierarchical_post_types
+                .iter()
+                .any(|hierarchical| hierarchical == post_type)
+    }
+}
 
 impl Config {
     pub fn from_env() -> Result<Self, env::VarError> {
         let database = DatabaseConfig {
             url: env::var("DATABASE_URL")?,
+            table_prefix: env::var("TABLE_PREFIX").unwrap_or_else(|_| String::from("wp_")),
         };
-        
+
         let server = ServerConfig {
             host: env::var("SERVER_HOST").unwrap_or_else(|_| String::from("127.0.0.1")),
             port: env::var("SERVER_PORT")
@@ -32,10 +79,79 @@ impl Config {
                 .parse::<u16>()
                 .expect("Invalid SERVER_PORT value"),
         };
-        
+
+        let site = SiteConfig {
+            option_allowlist: env::var("SITE_OPTIONS")
+                .map(|options| {
+                    options
+                        .split(',')
+                        .map(|option| option.trim().to_string())
+                        .filter(|option| !option.is_empty())
+                        .collect()
+                })
+                .unwrap_or_else(|_| {
+                    DEFAULT_SITE_OPTIONS
+                        .iter()
+                        .map(|option| option.to_string())
+                        .collect()
+                }),
+            // `post_type:base` pairs for custom post types registered with a
+            // rewrite slug other than their name.
+            post_type_bases: env::var("POST_TYPE_BASES")
+                .map(|bases| {
+                    bases
+                        .split(',')
+                        .filter_map(|pair| pair.split_once(':'))
+                        .map(|(post_type, base)| {
+                            (
+                                post_type.trim().to_string(),
+                                base.trim().trim_matches('/').to_string(),
+                            )
+                        })
+                        .filter(|(post_type, base)| !post_type.is_empty() && !base.is_empty())
+                        .collect()
+                })
+                .unwrap_or_default(),
+            // Custom post types registered with `hierarchical => true`, in
+            // addition to `page`.
+            hierarchical_post_types: env::var("HIERARCHICAL_POST_TYPES")
+                .map(|post_types| {
+                    post_types
+                        .split(',')
+                        .map(|post_type| post_type.trim().to_string())
+                        .filter(|post_type| !post_type.is_empty())
+                        .collect()
+                })
+                .unwrap_or_default(),
+            multisite: env::var("MULTISITE")
+                .map(|value| matches!(value.trim(), "1" | "true"))
+                .unwrap_or(false),
+        };
+
+        let shop = ShopConfig {
+            product_post_type: env::var("PRODUCT_POST_TYPE")
+                .unwrap_or_else(|_| String::from("product")),
+        };
+
+        // Without any keys configured, endpoints that need authentication
+        // reject every request.
+        let auth = AuthConfig {
+            api_keys: env::var("API_KEYS")
+                .map(|keys| {
+                    keys.split(',')
+                        .map(|key| key.trim().to_string())
+                        .filter(|key| !key.is_empty())
+                        .collect()
+                })
+                .unwrap_or_default(),
+        };
+
         Ok(Config {
             database,
             server,
+            site,
+            shop,
+            auth,
         })
     }
-}
\ No newline at end of file
+}
@@ -1,2 +1,3 @@
 pub mod connection;
-pub mod queries;
\ No newline at end of file
+pub mod queries;
+pub mod tables;
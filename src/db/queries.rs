@@ -1,8 +1,16 @@
+use crate::db::tables;
 use crate::error::ApiError;
-use crate::models::{post, postmeta, term, term_taxonomy};
-use sea_orm::DatabaseConnection;
+use crate::models::language::{LanguageFilter, Plugin};
+use crate::models::{
+    blog, comment, icl_translation, option, post, postmeta, term, term_relationship, term_taxonomy,
+    termmeta, user,
+};
+use crate::utils::permalinks::{self, Permalinks, PostLink, Route};
+use crate::utils::{acf, blocks, formatting, php, seo};
+use sea_orm::{DatabaseConnection, DbErr};
 use std::collections::HashMap;
 
+#[allow(clippy::too_many_arguments)]
 pub async fn get_posts(
     db: &DatabaseConnection,
     post_type: Option<String>,
@@ -11,6 +19,9 @@ pub async fn get_posts(
     page_size: u64,
     search: Option<String>,
     author_id: Option<u64>,
+    sticky: Option<String>,
+    meta: Option<&post::MetaFilter>,
+    lang: Option<&LanguageFilter>,
 ) -> Result<(Vec<post::Model>, u64), ApiError> {
     if page_size > 100 {
         return Err(ApiError::BadRequest(
@@ -32,6 +43,24 @@ pub async fn get_posts(
         }
     }
 
+    let sticky = match sticky.as_deref() {
+        Some(mode) => {
+            let ids = get_sticky_post_ids(db).await?;
+            match mode {
+                "only" => Some(post::StickyFilter::Only(ids)),
+                "exclude" => Some(post::StickyFilter::Exclude(ids)),
+                "first" => Some(post::StickyFilter::First(ids)),
+                _ => {
+                    return Err(ApiError::BadRequest(format!(
+                        "Invalid sticky mode: {}. Valid modes are: only, exclude, first",
+                        mode
+                    )))
+                }
+            }
+        }
+        None => None,
+    };
+
     let (posts, total) = match post::Entity::find_posts(
         db,
         post_type.clone(),
@@ -40,14 +69,17 @@ pub async fn get_posts(
         page_size,
         search.clone(),
         author_id,
+        sticky,
+        meta,
+        lang,
     )
     .await
     {
         Ok(result) => result,
         Err(err) => {
             let error_msg = format!(
-                "Failed to retrieve posts with filters - type: {:?}, status: {:?}, page: {}, page_size: {}, search: {:?}, author: {:?}. Error: {}",
-                post_type, post_status, page, page_size, search, author_id, err
+                "Failed to retrieve posts with filters - type: {:?}, status: {:?}, page: {}, page_size: {}, search: {:?}, author: {:?}, meta: {:?}. Error: {}",
+                post_type, post_status, page, page_size, search, author_id, meta, err
             );
             return Err(ApiError::InternalServerError(error_msg));
         }
@@ -79,7 +111,7 @@ pub async fn get_post_by_id(
 
     match post {
         Some(post) => {
-            if ensure_published && post.post_status != "publish" {
+            if ensure_published && !post.is_published() {
                 Err(ApiError::NotFound(format!(
                     "Published post with ID {} not found",
                     post_id
@@ -95,6 +127,7 @@ pub async fn get_post_by_id(
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub async fn get_posts_by_type(
     db: &DatabaseConnection,
     post_type: &str,
@@ -102,6 +135,8 @@ pub async fn get_posts_by_type(
     page: u64,
     page_size: u64,
     search: Option<String>,
+    meta: Option<&post::MetaFilter>,
+    lang: Option<&LanguageFilter>,
 ) -> Result<(Vec<post::Model>, u64), ApiError> {
     if post_type.is_empty() {
         return Err(ApiError::BadRequest(
@@ -135,14 +170,25 @@ pub async fn get_posts_by_type(
         }
     }
 
-    match post::Entity::find_by_type(db, post_type, post_status, page, page_size, search).await {
+    // Like `/posts`, only published posts are listed unless a status is
+    // asked for.
+    match post::Entity::find_by_type(
+        db,
+        post_type,
+        post_status.or(Some("publish")),
+        page,
+        page_size,
+        search,
+        meta,
+        lang,
+    )
+    .await
+    {
         Ok(result) => Ok(result),
-        Err(err) => {
-            return Err(ApiError::InternalServerError(format!(
-                "Failed to retrieve posts of type '{}': {}",
-                post_type, err
-            )));
-        }
+        Err(err) => Err(ApiError::InternalServerError(format!(
+            "Failed to retrieve posts of type '{}': {}",
+            post_type, err
+        ))),
     }
 }
 
@@ -159,9 +205,11 @@ pub async fn get_post_types(db: &DatabaseConnection) -> Result<Vec<(String, i64,
 pub async fn get_posts_by_category(
     db: &DatabaseConnection,
     category_id: i32,
+    include_children: bool,
     page: u64,
     page_size: u64,
     search: Option<String>,
+    lang: Option<&LanguageFilter>,
 ) -> Result<(Vec<post::Model>, u64), ApiError> {
     if category_id <= 0 {
         return Err(ApiError::BadRequest(
@@ -195,7 +243,17 @@ pub async fn get_posts_by_category(
         }
     }
 
-    match post::Entity::find_by_category(db, category_id, page, page_size, search).await {
+    match post::Entity::find_by_category(
+        db,
+        category_id,
+        include_children,
+        page,
+        page_size,
+        search,
+        lang,
+    )
+    .await
+    {
         Ok(result) => Ok(result),
         Err(err) => Err(ApiError::InternalServerError(format!(
             "Failed to retrieve posts for category ID {}: {}",
@@ -214,9 +272,7 @@ pub async fn get_post_meta(
         ));
     }
 
-    let post = get_post_by_id(db, post_id, true).await?;
-
-    match postmeta::Entity::find_metadata_map(db, post.id).await {
+    match postmeta::Entity::find_metadata_map(db, post_id).await {
         Ok(meta) => Ok(meta),
         Err(err) => Err(ApiError::InternalServerError(format!(
             "Failed to retrieve metadata for post ID {}: {}",
@@ -225,10 +281,327 @@ pub async fn get_post_meta(
     }
 }
 
+/// Returns the terms of every given post across all taxonomies, loaded with
+/// a single join for the whole page.
+pub async fn get_post_terms(
+    db: &DatabaseConnection,
+    post_ids: Vec<u64>,
+) -> Result<HashMap<u64, Vec<term_relationship::ObjectTerm>>, ApiError> {
+    let object_terms =
+        match term_relationship::Entity::find_terms_for_objects(db, post_ids, None).await {
+            Ok(terms) => terms,
+            Err(err) => {
+                return Err(ApiError::InternalServerError(format!(
+                    "Failed to retrieve post terms: {}",
+                    err
+                )));
+            }
+        };
+
+    let mut terms_by_post: HashMap<u64, Vec<term_relationship::ObjectTerm>> = HashMap::new();
+    for term in object_terms {
+        terms_by_post.entry(term.object_id).or_default().push(term);
+    }
+
+    Ok(terms_by_post)
+}
+
+/// Resolves the `lang` parameter of a listing with the multilingual plugin
+/// the site uses.
+pub async fn get_language_filter(
+    db: &DatabaseConnection,
+    lang: Option<&str>,
+) -> Result<Option<LanguageFilter>, ApiError> {
+    let Some(lang) = lang.map(str::trim) else {
+        return Ok(None);
+    };
+
+    if lang.is_empty() {
+        return Err(ApiError::BadRequest("Language cannot be empty".to_string()));
+    }
+
+    match Plugin::detect(db).await {
+        Ok(Some(plugin)) => Ok(Some(LanguageFilter::new(plugin, lang.to_string()))),
+        Ok(None) => Err(ApiError::BadRequest(
+            "Filtering by language requires Polylang or WPML".to_string(),
+        )),
+        Err(err) => Err(ApiError::InternalServerError(format!(
+            "Failed to detect the site's languages: {}",
+            err
+        ))),
+    }
+}
+
+/// Returns the published translations of a post by language code, the post
+/// itself included, or `None` when the site is not multilingual.
+pub async fn get_post_translations(
+    db: &DatabaseConnection,
+    post: &post::Model,
+) -> Result<Option<Vec<(String, post::Model)>>, ApiError> {
+    let plugin = match Plugin::detect(db).await {
+        Ok(Some(plugin)) => plugin,
+        Ok(None) => return Ok(None),
+        Err(err) => {
+            return Err(ApiError::InternalServerError(format!(
+                "Failed to detect the site's languages: {}",
+                err
+            )));
+        }
+    };
+
+    let translations = match plugin {
+        // Polylang groups translations under a `post_translations` term
+        // whose description maps language codes to post IDs.
+        Plugin::Polylang => {
+            term_taxonomy::Entity::find_object_description(db, "post_translations", post.id)
+                .await
+                .map(|group| {
+                    group
+                        .and_then(|group| php::unserialize(&group))
+                        .and_then(|group| group.as_object().cloned())
+                        .unwrap_or_default()
+                        .into_iter()
+                        .filter_map(|(lang, id)| {
+                            let id = match id {
+                                serde_json::Value::Number(id) => id.as_u64(),
+                                serde_json::Value::String(id) => id.parse().ok(),
+                                _ => None,
+                            };
+                            id.map(|id| (lang, id))
+                        })
+                        .collect::<Vec<_>>()
+                })
+        }
+        Plugin::Wpml => {
+            let element_type = format!("post_{}", post.post_type);
+            icl_translation::Entity::find_translations(db, &element_type, post.id).await
+        }
+    };
+
+    let translations = match translations {
+        Ok(translations) => translations,
+        Err(err) => {
+            return Err(ApiError::InternalServerError(format!(
+                "Failed to retrieve translations of post ID {}: {}",
+                post.id, err
+            )));
+        }
+    };
+
+    let ids = translations.iter().map(|(_, id)| *id).collect();
+    let mut posts: HashMap<u64, post::Model> = match post::Entity::find_by_ids(db, ids).await {
+        Ok(posts) => posts
+            .into_iter()
+            .filter(post::Model::is_published)
+            .map(|post| (post.id, post))
+            .collect(),
+        Err(err) => {
+            return Err(ApiError::InternalServerError(format!(
+                "Failed to retrieve translations of post ID {}: {}",
+                post.id, err
+            )));
+        }
+    };
+
+    Ok(Some(
+        translations
+            .into_iter()
+            .filter_map(|(lang, id)| posts.remove(&id).map(|post| (lang, post)))
+            .collect(),
+    ))
+}
+
+pub async fn get_post_children(
+    db: &DatabaseConnection,
+    post_id: u64,
+    page: u64,
+    page_size: u64,
+    lang: Option<&LanguageFilter>,
+) -> Result<(Vec<post::Model>, u64), ApiError> {
+    if page == 0 {
+        return Err(ApiError::BadRequest(
+            "Page number must be greater than zero".to_string(),
+        ));
+    }
+
+    if page_size == 0 {
+        return Err(ApiError::BadRequest(
+            "Page size must be greater than zero".to_string(),
+        ));
+    }
+
+    if page_size > 100 {
+        return Err(ApiError::BadRequest(
+            "Page size exceeds maximum limit of 100".to_string(),
+        ));
+    }
+
+    let parent = get_post_by_id(db, post_id, true).await?;
+
+    // Children share the parent's post type; this leaves out attachments
+    // and revisions, which also point at their post through `post_parent`.
+    match post::Entity::find_children(db, parent.id, &parent.post_type, page, page_size, lang).await
+    {
+        Ok(result) => Ok(result),
+        Err(err) => Err(ApiError::InternalServerError(format!(
+            "Failed to retrieve children of post ID {}: {}",
+            post_id, err
+        ))),
+    }
+}
+
+// Deep hierarchies are rare; the limit guards against `post_parent` cycles.
+const MAX_ANCESTOR_DEPTH: usize = 32;
+
+/// Returns the published ancestors of a post, starting with the top-level
+/// one and ending with its direct parent.
+pub async fn get_post_ancestors(
+    db: &DatabaseConnection,
+    post: &post::Model,
+) -> Result<Vec<post::Model>, ApiError> {
+    let mut ancestors = Vec::new();
+    let mut parent_id = post.post_parent;
+
+    while parent_id != 0 && parent_id != post.id && ancestors.len() < MAX_ANCESTOR_DEPTH {
+        let parent = match post::Entity::find_by_id(db, parent_id).await {
+            Ok(Some(parent)) => parent,
+            Ok(None) => break,
+            Err(err) => {
+                return Err(ApiError::InternalServerError(format!(
+                    "Failed to retrieve ancestors of post ID {}: {}",
+                    post.id, err
+                )));
+            }
+        };
+
+        if ancestors
+            .iter()
+            .any(|ancestor: &post::Model| ancestor.id == parent.id)
+        {
+            break;
+        }
+
+        parent_id = parent.post_parent;
+        ancestors.push(parent);
+    }
+
+    ancestors.retain(post::Model::is_published);
+    ancestors.reverse();
+
+    Ok(ancestors)
+}
+
+pub async fn get_page_tree(
+    db: &DatabaseConnection,
+    post_type: &str,
+) -> Result<Vec<post::PageSummary>, ApiError> {
+    match post::Entity::find_page_summaries(db, post_type).await {
+        Ok(pages) => Ok(pages),
+        Err(err) => Err(ApiError::InternalServerError(format!(
+            "Failed to retrieve {} hierarchy: {}",
+            post_type, err
+        ))),
+    }
+}
+
+pub async fn get_page_template(
+    db: &DatabaseConnection,
+    post_id: u64,
+) -> Result<Option<String>, ApiError> {
+    match postmeta::Entity::find_metadata_for_posts(db, vec![post_id], &["_wp_page_template"]).await
+    {
+        Ok(mut meta) => Ok(meta
+            .remove(&post_id)
+            .and_then(|mut meta| meta.remove("_wp_page_template"))),
+        Err(err) => Err(ApiError::InternalServerError(format!(
+            "Failed to retrieve page template for post ID {}: {}",
+            post_id, err
+        ))),
+    }
+}
+
+pub async fn get_revisions(
+    db: &DatabaseConnection,
+    post_id: u64,
+    page: u64,
+    page_size: u64,
+) -> Result<(Vec<post::Model>, u64), ApiError> {
+    if page == 0 {
+        return Err(ApiError::BadRequest(
+            "Page number must be greater than zero".to_string(),
+        ));
+    }
+
+    if page_size == 0 {
+        return Err(ApiError::BadRequest(
+            "Page size must be greater than zero".to_string(),
+        ));
+    }
+
+    if page_size > 100 {
+        return Err(ApiError::BadRequest(
+            "Page size exceeds maximum limit of 100".to_string(),
+        ));
+    }
+
+    let post = get_post_by_id(db, post_id, false).await?;
+
+    match post::Entity::find_revisions(db, post.id, page, page_size).await {
+        Ok(result) => Ok(result),
+        Err(err) => Err(ApiError::InternalServerError(format!(
+            "Failed to retrieve revisions of post ID {}: {}",
+            post_id, err
+        ))),
+    }
+}
+
+pub async fn get_revision(
+    db: &DatabaseConnection,
+    post_id: u64,
+    revision_id: u64,
+) -> Result<post::Model, ApiError> {
+    let revision = get_post_by_id(db, revision_id, false).await?;
+
+    if revision.post_type != "revision" || revision.post_parent != post_id {
+        return Err(ApiError::NotFound(format!(
+            "Revision with ID {} of post ID {} not found",
+            revision_id, post_id
+        )));
+    }
+
+    Ok(revision)
+}
+
+/// Resolves what a revision is compared with: the post itself or another of
+/// its revisions when `against` is given, the revision saved before it
+/// otherwise. The first revision has nothing to compare with.
+pub async fn get_revision_base(
+    db: &DatabaseConnection,
+    revision: &post::Model,
+    against: Option<u64>,
+) -> Result<Option<post::Model>, ApiError> {
+    match against {
+        Some(against) if against == revision.post_parent => {
+            get_post_by_id(db, against, false).await.map(Some)
+        }
+        Some(against) => get_revision(db, revision.post_parent, against)
+            .await
+            .map(Some),
+        None => match post::Entity::find_previous_revision(db, revision).await {
+            Ok(previous) => Ok(previous),
+            Err(err) => Err(ApiError::InternalServerError(format!(
+                "Failed to retrieve the revision before ID {}: {}",
+                revision.id, err
+            ))),
+        },
+    }
+}
+
 pub async fn get_categories(
     db: &DatabaseConnection,
     page: u64,
     page_size: u64,
+    lang: Option<&LanguageFilter>,
 ) -> Result<(Vec<(term_taxonomy::Model, term::Model)>, u64), ApiError> {
     if page == 0 {
         return Err(ApiError::BadRequest(
@@ -248,7 +621,7 @@ pub async fn get_categories(
         ));
     }
 
-    match term_taxonomy::Entity::find_categories(db, page, page_size).await {
+    match term_taxonomy::Entity::find_categories(db, page, page_size, lang).await {
         Ok(categories) => Ok(categories),
         Err(err) => Err(ApiError::InternalServerError(format!(
             "Failed to retrieve categories: {}",
@@ -256,3 +629,1781 @@ pub async fn get_categories(
         ))),
     }
 }
+
+/// Finds a category by slug. Slugs with non-ASCII characters are accepted
+/// both decoded and in the percent-encoded form WordPress stores them in.
+pub async fn get_category_by_slug(
+    db: &DatabaseConnection,
+    slug: &str,
+) -> Result<(term_taxonomy::Model, term::Model), ApiError> {
+    if slug.trim().is_empty() {
+        return Err(ApiError::BadRequest(
+            "Category slug cannot be empty".to_string(),
+        ));
+    }
+
+    for slug_form in permalinks::slug_forms(slug.trim()) {
+        match term_taxonomy::Entity::find_term_by_slug(db, "category", &slug_form).await {
+            Ok(Some(category)) => return Ok(category),
+            Ok(None) => {}
+            Err(err) => {
+                return Err(ApiError::InternalServerError(format!(
+                    "Failed to retrieve category with slug {}: {}",
+                    slug, err
+                )));
+            }
+        }
+    }
+
+    Err(ApiError::NotFound(format!(
+        "Category with slug {} not found",
+        slug
+    )))
+}
+
+/// Returns every category along with the published posts filed under each,
+/// keyed by term ID, for aggregating counts over subtrees.
+pub async fn get_category_tree(
+    db: &DatabaseConnection,
+    lang: Option<&LanguageFilter>,
+) -> Result<
+    (
+        Vec<(term_taxonomy::Model, term::Model)>,
+        HashMap<u64, Vec<u64>>,
+    ),
+    ApiError,
+> {
+    let categories = match term_taxonomy::Entity::find_all_terms(db, "category", lang).await {
+        Ok(categories) => categories,
+        Err(err) => {
+            return Err(ApiError::InternalServerError(format!(
+                "Failed to retrieve categories: {}",
+                err
+            )));
+        }
+    };
+
+    let objects =
+        match term_relationship::Entity::find_published_objects(db, "category", lang).await {
+            Ok(objects) => objects,
+            Err(err) => {
+                return Err(ApiError::InternalServerError(format!(
+                    "Failed to retrieve posts of categories: {}",
+                    err
+                )));
+            }
+        };
+
+    let term_ids: HashMap<u64, u64> = categories
+        .iter()
+        .map(|(taxonomy, term)| (taxonomy.term_taxonomy_id, term.term_id))
+        .collect();
+
+    let mut posts: HashMap<u64, Vec<u64>> = HashMap::new();
+    for (term_taxonomy_id, object_id) in objects {
+        if let Some(term_id) = term_ids.get(&term_taxonomy_id) {
+            posts.entry(*term_id).or_default().push(object_id);
+        }
+    }
+
+    Ok((categories, posts))
+}
+
+pub async fn get_taxonomies(db: &DatabaseConnection) -> Result<Vec<(String, i64, i64)>, ApiError> {
+    match term_taxonomy::Entity::find_taxonomies(db).await {
+        Ok(taxonomies) => Ok(taxonomies),
+        Err(err) => Err(ApiError::InternalServerError(format!(
+            "Failed to retrieve taxonomies: {}",
+            err
+        ))),
+    }
+}
+
+pub async fn get_terms(
+    db: &DatabaseConnection,
+    taxonomy: &str,
+    page: u64,
+    page_size: u64,
+    search: Option<String>,
+    lang: Option<&LanguageFilter>,
+) -> Result<(Vec<(term_taxonomy::Model, term::Model)>, u64), ApiError> {
+    if taxonomy.is_empty() {
+        return Err(ApiError::BadRequest("Taxonomy cannot be empty".to_string()));
+    }
+
+    if page == 0 {
+        return Err(ApiError::BadRequest(
+            "Page number must be greater than zero".to_string(),
+        ));
+    }
+
+    if page_size == 0 {
+        return Err(ApiError::BadRequest(
+            "Page size must be greater than zero".to_string(),
+        ));
+    }
+
+    if page_size > 100 {
+        return Err(ApiError::BadRequest(
+            "Page size exceeds maximum limit of 100".to_string(),
+        ));
+    }
+
+    match term_taxonomy::Entity::find_terms(db, taxonomy, page, page_size, search, lang).await {
+        Ok(terms) => Ok(terms),
+        Err(err) => Err(ApiError::InternalServerError(format!(
+            "Failed to retrieve terms for taxonomy '{}': {}",
+            taxonomy, err
+        ))),
+    }
+}
+
+pub async fn get_posts_by_term(
+    db: &DatabaseConnection,
+    taxonomy: &str,
+    term_id: u64,
+    page: u64,
+    page_size: u64,
+    search: Option<String>,
+    lang: Option<&LanguageFilter>,
+) -> Result<(Vec<post::Model>, u64), ApiError> {
+    if taxonomy.is_empty() {
+        return Err(ApiError::BadRequest("Taxonomy cannot be empty".to_string()));
+    }
+
+    if term_id == 0 {
+        return Err(ApiError::BadRequest(
+            "Term ID must be a positive integer".to_string(),
+        ));
+    }
+
+    if page == 0 {
+        return Err(ApiError::BadRequest(
+            "Page number must be greater than zero".to_string(),
+        ));
+    }
+
+    if page_size == 0 {
+        return Err(ApiError::BadRequest(
+            "Page size must be greater than zero".to_string(),
+        ));
+    }
+
+    if page_size > 100 {
+        return Err(ApiError::BadRequest(
+            "Page size exceeds maximum limit of 100".to_string(),
+        ));
+    }
+
+    if let Some(search_term) = &search {
+        if search_term.chars().count() < 3 {
+            return Err(ApiError::BadRequest(
+                "Search term must be at least 3 characters long".to_string(),
+            ));
+        }
+    }
+
+    match post::Entity::find_by_term(db, taxonomy, term_id, page, page_size, search, lang).await {
+        Ok(result) => Ok(result),
+        Err(err) => Err(ApiError::InternalServerError(format!(
+            "Failed to retrieve posts for term ID {} in taxonomy '{}': {}",
+            term_id, taxonomy, err
+        ))),
+    }
+}
+
+pub async fn get_authors(
+    db: &DatabaseConnection,
+    page: u64,
+    page_size: u64,
+    search: Option<String>,
+) -> Result<(Vec<user::AuthorProfile>, u64), ApiError> {
+    if page == 0 {
+        return Err(ApiError::BadRequest(
+            "Page number must be greater than zero".to_string(),
+        ));
+    }
+
+    if page_size == 0 {
+        return Err(ApiError::BadRequest(
+            "Page size must be greater than zero".to_string(),
+        ));
+    }
+
+    if page_size > 100 {
+        return Err(ApiError::BadRequest(
+            "Page size exceeds maximum limit of 100".to_string(),
+        ));
+    }
+
+    let (users, total) = match user::Entity::find_authors(db, page, page_size, search).await {
+        Ok(result) => result,
+        Err(err) => {
+            return Err(ApiError::InternalServerError(format!(
+                "Failed to retrieve authors: {}",
+                err
+            )));
+        }
+    };
+
+    match user::Entity::load_profiles(db, users).await {
+        Ok(profiles) => Ok((profiles, total)),
+        Err(err) => Err(ApiError::InternalServerError(format!(
+            "Failed to retrieve author profiles: {}",
+            err
+        ))),
+    }
+}
+
+pub async fn get_author_by_id(
+    db: &DatabaseConnection,
+    author_id: u64,
+) -> Result<user::AuthorProfile, ApiError> {
+    if author_id == 0 {
+        return Err(ApiError::BadRequest(
+            "Author ID must be a positive integer".to_string(),
+        ));
+    }
+
+    let author = match user::Entity::find_author_by_id(db, author_id).await {
+        Ok(Some(author)) => author,
+        Ok(None) => {
+            return Err(ApiError::NotFound(format!(
+                "Author with ID {} not found",
+                author_id
+            )));
+        }
+        Err(err) => {
+            return Err(ApiError::InternalServerError(format!(
+                "Failed to retrieve author with ID {}: {}",
+                author_id, err
+            )));
+        }
+    };
+
+    match user::Entity::load_profiles(db, vec![author]).await {
+        Ok(mut profiles) => profiles
+            .pop()
+            .ok_or_else(|| ApiError::NotFound(format!("Author with ID {} not found", author_id))),
+        Err(err) => Err(ApiError::InternalServerError(format!(
+            "Failed to retrieve profile for author ID {}: {}",
+            author_id, err
+        ))),
+    }
+}
+
+pub async fn get_post_comments(
+    db: &DatabaseConnection,
+    post_id: u64,
+    page: u64,
+    page_size: u64,
+) -> Result<(Vec<comment::CommentThread>, u64), ApiError> {
+    if page == 0 {
+        return Err(ApiError::BadRequest(
+            "Page number must be greater than zero".to_string(),
+        ));
+    }
+
+    if page_size == 0 {
+        return Err(ApiError::BadRequest(
+            "Page size must be greater than zero".to_string(),
+        ));
+    }
+
+    if page_size > 100 {
+        return Err(ApiError::BadRequest(
+            "Page size exceeds maximum limit of 100".to_string(),
+        ));
+    }
+
+    let post = get_post_by_id(db, post_id, true).await?;
+
+    match comment::Entity::find_threads(db, post.id, page, page_size).await {
+        Ok(result) => Ok(result),
+        Err(err) => Err(ApiError::InternalServerError(format!(
+            "Failed to retrieve comments for post ID {}: {}",
+            post_id, err
+        ))),
+    }
+}
+
+pub async fn get_site_options(
+    db: &DatabaseConnection,
+    option_names: &[String],
+) -> Result<HashMap<String, String>, ApiError> {
+    match option::Entity::find_options_map(db, option_names).await {
+        Ok(options) => Ok(options),
+        Err(err) => Err(ApiError::InternalServerError(format!(
+            "Failed to retrieve site options: {}",
+            err
+        ))),
+    }
+}
+
+/// How the site's locale counts words in excerpts. The locale is the
+/// `WPLANG` option, empty for the default `en_US`.
+pub async fn get_word_count_type(
+    db: &DatabaseConnection,
+) -> Result<formatting::WordCountType, ApiError> {
+    let options = get_site_options(db, &["WPLANG".to_string()]).await?;
+    let locale = options.get("WPLANG").map_or("", |locale| locale.trim());
+
+    Ok(formatting::WordCountType::from_locale(locale))
+}
+
+/// IDs of the posts stuck to the front page. WordPress keeps them in the
+/// `sticky_posts` option as a serialized array, which loses its sequential
+/// keys once a post is unstuck.
+pub async fn get_sticky_post_ids(db: &DatabaseConnection) -> Result<Vec<u64>, ApiError> {
+    let options = get_site_options(db, &["sticky_posts".to_string()]).await?;
+
+    let values = match options
+        .get("sticky_posts")
+        .and_then(|value| php::unserialize(value))
+    {
+        Some(serde_json::Value::Array(values)) => values,
+        Some(serde_json::Value::Object(values)) => values.into_iter().map(|(_, id)| id).collect(),
+        _ => Vec::new(),
+    };
+
+    Ok(values
+        .iter()
+        .filter_map(|id| match id {
+            serde_json::Value::Number(id) => id.as_u64(),
+            serde_json::Value::String(id) => id.parse().ok(),
+            _ => None,
+        })
+        .filter(|&id| id > 0)
+        .collect())
+}
+
+pub type SiteWithOptions = (blog::Model, HashMap<String, String>);
+
+const SITE_OPTION_NAMES: [&str; 3] = ["blogname", "blogdescription", "home"];
+
+pub async fn get_network_sites(
+    db: &DatabaseConnection,
+    page: u64,
+    page_size: u64,
+) -> Result<(Vec<SiteWithOptions>, u64), ApiError> {
+    if page == 0 {
+        return Err(ApiError::BadRequest(
+            "Page number must be greater than zero".to_string(),
+        ));
+    }
+
+    if page_size == 0 {
+        return Err(ApiError::BadRequest(
+            "Page size must be greater than zero".to_string(),
+        ));
+    }
+
+    if page_size > 100 {
+        return Err(ApiError::BadRequest(
+            "Page size exceeds maximum limit of 100".to_string(),
+        ));
+    }
+
+    let (blogs, total) = match blog::Entity::find_active(db, page, page_size).await {
+        Ok(result) => result,
+        Err(err) => {
+            return Err(ApiError::InternalServerError(format!(
+                "Failed to retrieve sites: {}",
+                err
+            )));
+        }
+    };
+
+    let mut sites = Vec::with_capacity(blogs.len());
+    for blog in blogs {
+        let options = get_network_site_options(db, blog.blog_id).await?;
+        sites.push((blog, options));
+    }
+
+    Ok((sites, total))
+}
+
+pub async fn get_network_site(
+    db: &DatabaseConnection,
+    blog_id: u64,
+) -> Result<SiteWithOptions, ApiError> {
+    if blog_id == 0 {
+        return Err(ApiError::BadRequest(
+            "Site ID must be a positive integer".to_string(),
+        ));
+    }
+
+    let blog = match blog::Entity::find_active_by_id(db, blog_id).await {
+        Ok(Some(blog)) => blog,
+        Ok(None) => {
+            return Err(ApiError::NotFound(format!(
+                "Site with ID {} not found",
+                blog_id
+            )));
+        }
+        Err(err) => {
+            return Err(ApiError::InternalServerError(format!(
+                "Failed to retrieve site with ID {}: {}",
+                blog_id, err
+            )));
+        }
+    };
+
+    let options = get_network_site_options(db, blog.blog_id).await?;
+
+    Ok((blog, options))
+}
+
+// Each site keeps its name and URLs in its own options table.
+async fn get_network_site_options(
+    db: &DatabaseConnection,
+    blog_id: u64,
+) -> Result<HashMap<String, String>, ApiError> {
+    let option_names: Vec<String> = SITE_OPTION_NAMES
+        .iter()
+        .map(|name| name.to_string())
+        .collect();
+
+    tables::for_site(blog_id, get_site_options(db, &option_names)).await
+}
+
+pub async fn get_permalinks(
+    db: &DatabaseConnection,
+    post_type_bases: &HashMap<String, String>,
+) -> Result<Permalinks, ApiError> {
+    let options = get_site_options(db, &Permalinks::option_names()).await?;
+    Ok(Permalinks::new(&options, post_type_bases))
+}
+
+/// Builds the permalink of each post. Parent slugs, categories and author
+/// nicenames are loaded in batches, and only when the links need them.
+pub async fn get_post_links(
+    db: &DatabaseConnection,
+    permalinks: &Permalinks,
+    posts: &[post::Model],
+) -> Result<HashMap<u64, String>, ApiError> {
+    let parent_paths = get_parent_paths(db, posts).await?;
+
+    let post_ids: Vec<u64> = posts
+        .iter()
+        .filter(|post| post.post_type == "post")
+        .map(|post| post.id)
+        .collect();
+
+    let categories = if permalinks.uses("%category%") && !post_ids.is_empty() {
+        get_category_paths(db, permalinks, post_ids).await?
+    } else {
+        HashMap::new()
+    };
+
+    let authors = if permalinks.uses("%author%") {
+        let author_ids = posts.iter().map(|post| post.post_author).collect();
+        match user::Entity::find_nicenames(db, author_ids).await {
+            Ok(nicenames) => nicenames,
+            Err(err) => {
+                return Err(ApiError::InternalServerError(format!(
+                    "Failed to retrieve author nicenames: {}",
+                    err
+                )));
+            }
+        }
+    } else {
+        HashMap::new()
+    };
+
+    Ok(posts
+        .iter()
+        .map(|post| {
+            let mut path: Vec<&str> = parent_paths
+                .get(&post.id)
+                .map(|path| path.iter().map(String::as_str).collect())
+                .unwrap_or_default();
+            path.push(&post.post_name);
+
+            let link = permalinks.post_link(&PostLink {
+                id: post.id,
+                post_type: &post.post_type,
+                post_status: &post.post_status,
+                date: post.post_date,
+                path,
+                category: categories.get(&post.id).map_or("", String::as_str),
+                author: authors.get(&post.post_author).map_or("", String::as_str),
+            });
+
+            (post.id, link)
+        })
+        .collect())
+}
+
+// Slugs of every ancestor of hierarchical posts, top-level first, loaded
+// one level of the hierarchy at a time. Like `get_page_uri()`, ancestors of
+// any status are included.
+async fn get_parent_paths(
+    db: &DatabaseConnection,
+    posts: &[post::Model],
+) -> Result<HashMap<u64, Vec<String>>, ApiError> {
+    let mut summaries: HashMap<u64, post::PageSummary> = HashMap::new();
+    let mut pending: Vec<u64> = posts
+        .iter()
+        .filter(|post| post.post_type != "post" && post.post_parent != 0)
+        .map(|post| post.post_parent)
+        .collect();
+
+    for _ in 0..MAX_ANCESTOR_DEPTH {
+        pending.sort_unstable();
+        pending.dedup();
+        pending.retain(|id| !summaries.contains_key(id));
+        if pending.is_empty() {
+            break;
+        }
+
+        let parents = match post::Entity::find_summaries_by_ids(db, pending).await {
+            Ok(parents) => parents,
+            Err(err) => {
+                return Err(ApiError::InternalServerError(format!(
+                    "Failed to retrieve parent posts: {}",
+                    err
+                )));
+            }
+        };
+
+        pending = parents
+            .iter()
+            .map(|parent| parent.post_parent)
+            .filter(|id| *id != 0)
+            .collect();
+        summaries.extend(parents.into_iter().map(|parent| (parent.id, parent)));
+    }
+
+    Ok(posts
+        .iter()
+        .filter(|post| post.post_parent != 0)
+        .map(|post| {
+            let mut path = Vec::new();
+            let mut parent_id = post.post_parent;
+
+            while let Some(parent) = summaries.get(&parent_id) {
+                if parent.id == post.id || path.len() >= MAX_ANCESTOR_DEPTH {
+                    break;
+                }
+                path.push(parent.post_name.clone());
+                parent_id = parent.post_parent;
+            }
+
+            path.reverse();
+            (post.id, path)
+        })
+        .collect())
+}
+
+// The `%category%` value of each post: the slug path of its category with
+// the lowest ID, or of the default category, as in `get_permalink()`.
+async fn get_category_paths(
+    db: &DatabaseConnection,
+    permalinks: &Permalinks,
+    post_ids: Vec<u64>,
+) -> Result<HashMap<u64, String>, ApiError> {
+    let object_terms = match term_relationship::Entity::find_terms_for_objects(
+        db,
+        post_ids.clone(),
+        Some("category"),
+    )
+    .await
+    {
+        Ok(terms) => terms,
+        Err(err) => {
+            return Err(ApiError::InternalServerError(format!(
+                "Failed to retrieve post categories: {}",
+                err
+            )));
+        }
+    };
+
+    let categories: HashMap<u64, (String, u64)> =
+        match term_taxonomy::Entity::find_all_terms(db, "category", None).await {
+            Ok(categories) => categories
+                .into_iter()
+                .map(|(taxonomy, term)| (term.term_id, (term.slug, taxonomy.parent)))
+                .collect(),
+            Err(err) => {
+                return Err(ApiError::InternalServerError(format!(
+                    "Failed to retrieve categories: {}",
+                    err
+                )));
+            }
+        };
+
+    let mut main_categories: HashMap<u64, u64> = HashMap::new();
+    for term in object_terms
+        .iter()
+        .filter(|term| term.taxonomy == "category")
+    {
+        main_categories
+            .entry(term.object_id)
+            .and_modify(|term_id| *term_id = (*term_id).min(term.term_id))
+            .or_insert(term.term_id);
+    }
+
+    Ok(post_ids
+        .into_iter()
+        .map(|post_id| {
+            let term_id = main_categories
+                .get(&post_id)
+                .copied()
+                .unwrap_or(permalinks.default_category());
+            (post_id, category_path(&categories, term_id))
+        })
+        .collect())
+}
+
+// The slug path of a category, top-level first, from the slug and parent of
+// every category.
+fn category_path(categories: &HashMap<u64, (String, u64)>, term_id: u64) -> String {
+    let mut slugs = Vec::new();
+    let mut current = term_id;
+    while let Some((slug, parent)) = categories.get(&current) {
+        if slugs.len() >= MAX_ANCESTOR_DEPTH {
+            break;
+        }
+        slugs.push(slug.as_str());
+        current = *parent;
+    }
+    slugs.reverse();
+    slugs.join("/")
+}
+
+// The link of each term, keyed by term taxonomy ID. Categories are linked by
+// their slug path, so all categories are loaded when there is one among the
+// terms.
+async fn get_term_links(
+    db: &DatabaseConnection,
+    permalinks: &Permalinks,
+    terms: &[(term_taxonomy::Model, term::Model)],
+) -> Result<HashMap<u64, String>, ApiError> {
+    let categories: HashMap<u64, (String, u64)> = if terms
+        .iter()
+        .any(|(taxonomy, _)| taxonomy.taxonomy == "category")
+    {
+        match term_taxonomy::Entity::find_all_terms(db, "category", None).await {
+            Ok(categories) => categories
+                .into_iter()
+                .map(|(taxonomy, term)| (term.term_id, (term.slug, taxonomy.parent)))
+                .collect(),
+            Err(err) => {
+                return Err(ApiError::InternalServerError(format!(
+                    "Failed to retrieve categories: {}",
+                    err
+                )));
+            }
+        }
+    } else {
+        HashMap::new()
+    };
+
+    Ok(terms
+        .iter()
+        .map(|(taxonomy, term)| {
+            let path = if taxonomy.taxonomy == "category" {
+                category_path(&categories, term.term_id)
+            } else {
+                term.slug.clone()
+            };
+
+            let link = permalinks.term_link(&taxonomy.taxonomy, term.term_id, &path);
+            (taxonomy.term_taxonomy_id, link)
+        })
+        .collect())
+}
+
+pub async fn get_seo_settings(db: &DatabaseConnection) -> Result<seo::Settings, ApiError> {
+    let options = get_site_options(db, &seo::Settings::option_names()).await?;
+    Ok(seo::Settings::new(&options))
+}
+
+/// Returns the meta the site's SEO plugin keeps for each post, without a
+/// query when the site has none.
+pub async fn get_seo_meta(
+    db: &DatabaseConnection,
+    settings: &seo::Settings,
+    post_ids: Vec<u64>,
+) -> Result<HashMap<u64, HashMap<String, String>>, ApiError> {
+    let meta_keys = settings.meta_keys();
+    if meta_keys.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    match postmeta::Entity::find_metadata_for_posts(db, post_ids, meta_keys).await {
+        Ok(meta) => Ok(meta),
+        Err(err) => Err(ApiError::InternalServerError(format!(
+            "Failed to retrieve SEO metadata: {}",
+            err
+        ))),
+    }
+}
+
+/// Resolves the SEO metadata of terms, keyed by term taxonomy ID. Only
+/// Rank Math keeps it in term meta; Yoast SEO keeps it in an option.
+pub async fn get_term_seo(
+    db: &DatabaseConnection,
+    permalinks: &Permalinks,
+    terms: &[(term_taxonomy::Model, term::Model)],
+) -> Result<HashMap<u64, seo::Seo>, ApiError> {
+    let settings = get_seo_settings(db).await?;
+    let links = get_term_links(db, permalinks, terms).await?;
+
+    let mut meta = if settings.plugin() == Some(seo::Plugin::RankMath) {
+        let term_ids = terms.iter().map(|(_, term)| term.term_id).collect();
+        match termmeta::Entity::find_metadata_for_terms(db, term_ids, &seo::RANK_MATH_META_KEYS)
+            .await
+        {
+            Ok(meta) => meta,
+            Err(err) => {
+                return Err(ApiError::InternalServerError(format!(
+                    "Failed to retrieve SEO metadata: {}",
+                    err
+                )));
+            }
+        }
+    } else {
+        HashMap::new()
+    };
+
+    Ok(terms
+        .iter()
+        .map(|(taxonomy, term)| {
+            let meta = meta.remove(&term.term_id).unwrap_or_default();
+            let description = formatting::wp_strip_all_tags(&taxonomy.description);
+
+            let seo = settings.resolve(&seo::Source {
+                object: seo::Object::Term {
+                    taxonomy: &taxonomy.taxonomy,
+                    term_id: term.term_id,
+                },
+                title: &term.name,
+                excerpt: &description,
+                link: links
+                    .get(&taxonomy.term_taxonomy_id)
+                    .map_or("", String::as_str),
+                image: None,
+                meta: &meta,
+            });
+
+            (taxonomy.term_taxonomy_id, seo)
+        })
+        .collect())
+}
+
+/// What a site path resolves to.
+pub enum Resource {
+    Post(Box<post::Model>),
+    Term(term_taxonomy::Model, term::Model),
+    Author(user::AuthorProfile),
+}
+
+pub async fn resolve_path(
+    db: &DatabaseConnection,
+    permalinks: &Permalinks,
+    path: &str,
+) -> Result<Resource, ApiError> {
+    for route in permalinks.routes(path) {
+        match find_route(db, permalinks, route).await {
+            Ok(Some(resource)) => return Ok(resource),
+            Ok(None) => {}
+            Err(err) => {
+                return Err(ApiError::InternalServerError(format!(
+                    "Failed to resolve path {}: {}",
+                    path, err
+                )));
+            }
+        }
+    }
+
+    Err(ApiError::NotFound(format!(
+        "Nothing found at path {}",
+        path
+    )))
+}
+
+async fn find_route(
+    db: &DatabaseConnection,
+    permalinks: &Permalinks,
+    route: Route,
+) -> Result<Option<Resource>, DbErr> {
+    let published = |post: &post::Model| post.is_published();
+
+    match route {
+        Route::FrontPage => match permalinks.front_page() {
+            Some(id) => Ok(post::Entity::find_by_id(db, id)
+                .await?
+                .filter(published)
+                .map(|post| Resource::Post(Box::new(post)))),
+            None => Ok(None),
+        },
+        Route::PostId(id) => Ok(post::Entity::find_by_id(db, id)
+            .await?
+            .filter(published)
+            .map(|post| Resource::Post(Box::new(post)))),
+        Route::Term { taxonomy, path } => find_term_by_path(db, taxonomy, &path).await,
+        Route::Author(nicename) => {
+            let nicenames = permalinks::slug_forms(&nicename);
+            match user::Entity::find_author_by_nicename(db, nicenames).await? {
+                Some(author) => Ok(user::Entity::load_profiles(db, vec![author])
+                    .await?
+                    .pop()
+                    .map(Resource::Author)),
+                None => Ok(None),
+            }
+        }
+        Route::PostType { post_type, path } => find_by_path(db, &post_type, &path).await,
+        Route::Page(path) => find_by_path(db, "page", &path).await,
+        Route::Post(link) => {
+            let candidates = match (link.post_id, &link.slug) {
+                (Some(id), _) => post::Entity::find_by_id(db, id)
+                    .await?
+                    .into_iter()
+                    .filter(|post| post.post_type == "post" && published(post))
+                    .filter(|post| {
+                        link.slug.as_ref().is_none_or(|slug| {
+                            permalinks::slug_forms(slug).contains(&post.post_name)
+                        })
+                    })
+                    .collect(),
+                (None, Some(slug)) => {
+                    post::Entity::find_published_by_slugs(db, "post", permalinks::slug_forms(slug))
+                        .await?
+                }
+                (None, None) => Vec::new(),
+            };
+
+            let authors = match &link.author {
+                Some(_) => {
+                    let author_ids = candidates.iter().map(|post| post.post_author).collect();
+                    user::Entity::find_nicenames(db, author_ids).await?
+                }
+                None => HashMap::new(),
+            };
+
+            Ok(candidates
+                .into_iter()
+                .find(|post| {
+                    link.matches_date(post.post_date)
+                        && link.author.as_ref().is_none_or(|author| {
+                            authors.get(&post.post_author).is_some_and(|nicename| {
+                                permalinks::slug_forms(author).contains(nicename)
+                            })
+                        })
+                })
+                .map(|post| Resource::Post(Box::new(post))))
+        }
+    }
+}
+
+// Finds a published post by the slugs of its hierarchy, checking each
+// ancestor against the path from the bottom up.
+async fn find_by_path(
+    db: &DatabaseConnection,
+    post_type: &str,
+    path: &[String],
+) -> Result<Option<Resource>, DbErr> {
+    let Some((slug, parents)) = path.split_last() else {
+        return Ok(None);
+    };
+
+    let candidates =
+        post::Entity::find_published_by_slugs(db, post_type, permalinks::slug_forms(slug)).await?;
+
+    'candidates: for candidate in candidates {
+        let mut parent_id = candidate.post_parent;
+
+        for segment in parents.iter().rev() {
+            let parent = match post::Entity::find_by_id(db, parent_id).await? {
+                Some(parent) if parent_id != 0 => parent,
+                _ => continue 'candidates,
+            };
+            if !permalinks::slug_forms(segment).contains(&parent.post_name) {
+                continue 'candidates;
+            }
+            parent_id = parent.post_parent;
+        }
+
+        if parent_id == 0 {
+            return Ok(Some(Resource::Post(Box::new(candidate))));
+        }
+    }
+
+    Ok(None)
+}
+
+// Like `get_category_by_path()` with `full_match`, the slugs of the term's
+// ancestors have to match the rest of the path.
+async fn find_term_by_path(
+    db: &DatabaseConnection,
+    taxonomy: &str,
+    path: &[String],
+) -> Result<Option<Resource>, DbErr> {
+    let Some((slug, parents)) = path.split_last() else {
+        return Ok(None);
+    };
+
+    let mut found = None;
+    for slug in permalinks::slug_forms(slug) {
+        found = term_taxonomy::Entity::find_term_by_slug(db, taxonomy, &slug).await?;
+        if found.is_some() {
+            break;
+        }
+    }
+    let Some((term_taxonomy, term)) = found else {
+        return Ok(None);
+    };
+
+    let mut parent_id = term_taxonomy.parent;
+    if !parents.is_empty() {
+        let terms: HashMap<u64, (String, u64)> =
+            term_taxonomy::Entity::find_all_terms(db, taxonomy, None)
+                .await?
+                .into_iter()
+                .map(|(taxonomy, term)| (term.term_id, (term.slug, taxonomy.parent)))
+                .collect();
+
+        for segment in parents.iter().rev() {
+            match terms.get(&parent_id) {
+                Some((slug, parent)) if permalinks::slug_forms(segment).contains(slug) => {
+                    parent_id = *parent;
+                }
+                _ => return Ok(None),
+            }
+        }
+    }
+
+    Ok((parent_id == 0).then_some(Resource::Term(term_taxonomy, term)))
+}
+
+pub type PostWithMeta = (post::Model, HashMap<String, String>);
+
+pub const MEDIA_META_KEYS: [&str; 3] = [
+    "_wp_attached_file",
+    "_wp_attachment_metadata",
+    "_wp_attachment_image_alt",
+];
+
+pub async fn get_uploads_url(db: &DatabaseConnection) -> Result<String, ApiError> {
+    let option_names = [
+        "siteurl".to_string(),
+        "upload_path".to_string(),
+        "upload_url_path".to_string(),
+    ];
+    let options = get_site_options(db, &option_names).await?;
+
+    let option = |name: &str| {
+        options
+            .get(name)
+            .map(|value| value.trim().trim_end_matches('/').to_string())
+            .unwrap_or_default()
+    };
+
+    // Mirrors wp_upload_dir(): an explicit upload URL wins, otherwise the
+    // upload path is resolved relative to the site URL.
+    let upload_url_path = option("upload_url_path");
+    if !upload_url_path.is_empty() {
+        return Ok(upload_url_path);
+    }
+
+    let upload_path = option("upload_path");
+    let upload_path = upload_path.trim_start_matches('/');
+    let upload_path = if upload_path.is_empty() {
+        "wp-content/uploads"
+    } else {
+        upload_path
+    };
+
+    Ok(format!("{}/{}", option("siteurl"), upload_path))
+}
+
+pub async fn get_media(
+    db: &DatabaseConnection,
+    page: u64,
+    page_size: u64,
+    search: Option<String>,
+    mime_type: Option<String>,
+    lang: Option<&LanguageFilter>,
+) -> Result<(Vec<PostWithMeta>, u64), ApiError> {
+    if page == 0 {
+        return Err(ApiError::BadRequest(
+            "Page number must be greater than zero".to_string(),
+        ));
+    }
+
+    if page_size == 0 {
+        return Err(ApiError::BadRequest(
+            "Page size must be greater than zero".to_string(),
+        ));
+    }
+
+    if page_size > 100 {
+        return Err(ApiError::BadRequest(
+            "Page size exceeds maximum limit of 100".to_string(),
+        ));
+    }
+
+    if let Some(search_term) = &search {
+        if search_term.chars().count() < 3 {
+            return Err(ApiError::BadRequest(
+                "Search term must be at least 3 characters long".to_string(),
+            ));
+        }
+    }
+
+    let (attachments, total) =
+        match post::Entity::find_attachments(db, page, page_size, search, mime_type, lang).await {
+            Ok(result) => result,
+            Err(err) => {
+                return Err(ApiError::InternalServerError(format!(
+                    "Failed to retrieve media: {}",
+                    err
+                )));
+            }
+        };
+
+    let attachment_ids = attachments.iter().map(|attachment| attachment.id).collect();
+    let mut meta =
+        match postmeta::Entity::find_metadata_for_posts(db, attachment_ids, &MEDIA_META_KEYS).await
+        {
+            Ok(meta) => meta,
+            Err(err) => {
+                return Err(ApiError::InternalServerError(format!(
+                    "Failed to retrieve media metadata: {}",
+                    err
+                )));
+            }
+        };
+
+    let media = attachments
+        .into_iter()
+        .map(|attachment| {
+            let attachment_meta = meta.remove(&attachment.id).unwrap_or_default();
+            (attachment, attachment_meta)
+        })
+        .collect();
+
+    Ok((media, total))
+}
+
+pub async fn get_media_by_id(
+    db: &DatabaseConnection,
+    media_id: u64,
+) -> Result<PostWithMeta, ApiError> {
+    if media_id == 0 {
+        return Err(ApiError::BadRequest(
+            "Media ID must be a positive integer".to_string(),
+        ));
+    }
+
+    let attachment = match post::Entity::find_by_id(db, media_id).await {
+        Ok(Some(post))
+            if post.post_type == "attachment"
+                && (post.post_status == "inherit" || post.post_status == "publish") =>
+        {
+            post
+        }
+        Ok(_) => {
+            return Err(ApiError::NotFound(format!(
+                "Media with ID {} not found",
+                media_id
+            )));
+        }
+        Err(err) => {
+            return Err(ApiError::InternalServerError(format!(
+                "Failed to retrieve media with ID {}: {}",
+                media_id, err
+            )));
+        }
+    };
+
+    match postmeta::Entity::find_metadata_for_posts(db, vec![attachment.id], &MEDIA_META_KEYS).await
+    {
+        Ok(mut meta) => {
+            let attachment_meta = meta.remove(&attachment.id).unwrap_or_default();
+            Ok((attachment, attachment_meta))
+        }
+        Err(err) => Err(ApiError::InternalServerError(format!(
+            "Failed to retrieve metadata for media ID {}: {}",
+            media_id, err
+        ))),
+    }
+}
+
+pub async fn get_featured_media(
+    db: &DatabaseConnection,
+    post_ids: Vec<u64>,
+) -> Result<HashMap<u64, PostWithMeta>, ApiError> {
+    let thumbnails =
+        match postmeta::Entity::find_metadata_for_posts(db, post_ids, &["_thumbnail_id"]).await {
+            Ok(meta) => meta,
+            Err(err) => {
+                return Err(ApiError::InternalServerError(format!(
+                    "Failed to retrieve featured image IDs: {}",
+                    err
+                )));
+            }
+        };
+
+    let thumbnails: HashMap<u64, u64> = thumbnails
+        .into_iter()
+        .filter_map(|(post_id, meta)| {
+            let thumbnail_id = meta.get("_thumbnail_id")?.trim().parse::<u64>().ok()?;
+            Some((post_id, thumbnail_id))
+        })
+        .filter(|(_, thumbnail_id)| *thumbnail_id > 0)
+        .collect();
+
+    if thumbnails.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let attachments = get_attachments(db, thumbnails.values().copied().collect()).await?;
+
+    Ok(thumbnails
+        .into_iter()
+        .filter_map(|(post_id, thumbnail_id)| {
+            let attachment = attachments.get(&thumbnail_id)?.clone();
+            Some((post_id, attachment))
+        })
+        .collect())
+}
+
+pub async fn get_attachments(
+    db: &DatabaseConnection,
+    mut attachment_ids: Vec<u64>,
+) -> Result<HashMap<u64, PostWithMeta>, ApiError> {
+    attachment_ids.retain(|attachment_id| *attachment_id > 0);
+    attachment_ids.sort_unstable();
+    attachment_ids.dedup();
+
+    if attachment_ids.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let attachments = match post::Entity::find_by_ids(db, attachment_ids.clone()).await {
+        Ok(attachments) => attachments,
+        Err(err) => {
+            return Err(ApiError::InternalServerError(format!(
+                "Failed to retrieve attachments: {}",
+                err
+            )));
+        }
+    };
+
+    let mut meta =
+        match postmeta::Entity::find_metadata_for_posts(db, attachment_ids, &MEDIA_META_KEYS).await
+        {
+            Ok(meta) => meta,
+            Err(err) => {
+                return Err(ApiError::InternalServerError(format!(
+                    "Failed to retrieve attachment metadata: {}",
+                    err
+                )));
+            }
+        };
+
+    Ok(attachments
+        .into_iter()
+        .filter(|attachment| attachment.post_type == "attachment")
+        .map(|attachment| {
+            let attachment_meta = meta.remove(&attachment.id).unwrap_or_default();
+            (attachment.id, (attachment, attachment_meta))
+        })
+        .collect())
+}
+
+/// ACF values of a page of posts, keyed by post ID, along with the
+/// attachments and published posts the values refer to.
+pub struct AcfValues {
+    pub values: HashMap<u64, Vec<(String, acf::Value)>>,
+    pub media: HashMap<u64, PostWithMeta>,
+    pub posts: Vec<post::Model>,
+}
+
+/// Decodes the ACF fields of the given posts, or returns `None` when the site
+/// has no ACF field definitions.
+pub async fn get_acf_values(
+    db: &DatabaseConnection,
+    post_ids: Vec<u64>,
+) -> Result<Option<AcfValues>, ApiError> {
+    let definitions = match post::Entity::find_acf_fields(db).await {
+        Ok(definitions) => definitions,
+        Err(err) => {
+            return Err(ApiError::InternalServerError(format!(
+                "Failed to retrieve ACF field definitions: {}",
+                err
+            )));
+        }
+    };
+
+    let fields = acf::Fields::new(
+        definitions
+            .into_iter()
+            .map(|field| acf::Definition {
+                id: field.id,
+                key: field.post_name,
+                name: field.post_excerpt,
+                parent: field.post_parent,
+                menu_order: field.menu_order,
+                settings: php::unserialize(&field.post_content).unwrap_or_default(),
+            })
+            .collect(),
+    );
+
+    if fields.is_empty() {
+        return Ok(None);
+    }
+
+    let meta = match postmeta::Entity::find_all_metadata_for_posts(db, post_ids).await {
+        Ok(meta) => meta,
+        Err(err) => {
+            return Err(ApiError::InternalServerError(format!(
+                "Failed to retrieve post metadata: {}",
+                err
+            )));
+        }
+    };
+
+    let values: HashMap<u64, Vec<(String, acf::Value)>> = meta
+        .iter()
+        .map(|(post_id, meta)| (*post_id, fields.values(meta)))
+        .collect();
+
+    let mut media_ids = Vec::new();
+    let mut related_ids = Vec::new();
+    for (_, value) in values.values().flatten() {
+        value.collect_ids(&mut media_ids, &mut related_ids);
+    }
+
+    let media = get_attachments(db, media_ids).await?;
+
+    related_ids.sort_unstable();
+    related_ids.dedup();
+    let posts = match post::Entity::find_by_ids(db, related_ids).await {
+        Ok(posts) => posts
+            .into_iter()
+            .filter(post::Model::is_published)
+            .collect(),
+        Err(err) => {
+            return Err(ApiError::InternalServerError(format!(
+                "Failed to retrieve related posts: {}",
+                err
+            )));
+        }
+    };
+
+    Ok(Some(AcfValues {
+        values,
+        media,
+        posts,
+    }))
+}
+
+pub type GalleryAttachments = (HashMap<u64, PostWithMeta>, HashMap<u64, Vec<u64>>);
+
+// Loads the images shown by `[gallery]` shortcodes: the attachments listed
+// explicitly plus the images attached to each parent post, keyed by parent in
+// gallery order.
+pub async fn get_gallery_attachments(
+    db: &DatabaseConnection,
+    mut attachment_ids: Vec<u64>,
+    parent_ids: Vec<u64>,
+) -> Result<GalleryAttachments, ApiError> {
+    let children = match post::Entity::find_attached_images(db, parent_ids).await {
+        Ok(children) => children,
+        Err(err) => {
+            return Err(ApiError::InternalServerError(format!(
+                "Failed to retrieve attached images: {}",
+                err
+            )));
+        }
+    };
+
+    let mut children_by_parent: HashMap<u64, Vec<u64>> = HashMap::new();
+    for child in children {
+        attachment_ids.push(child.id);
+        children_by_parent
+            .entry(child.post_parent)
+            .or_default()
+            .push(child.id);
+    }
+
+    let attachments = get_attachments(db, attachment_ids).await?;
+
+    Ok((attachments, children_by_parent))
+}
+
+pub const PRODUCT_META_KEYS: [&str; 10] = [
+    "_price",
+    "_regular_price",
+    "_sale_price",
+    "_sku",
+    "_stock_status",
+    "_stock",
+    "_manage_stock",
+    "_thumbnail_id",
+    "_product_image_gallery",
+    "_product_attributes",
+];
+
+pub struct ProductRecord {
+    pub product: post::Model,
+    pub meta: HashMap<String, String>,
+    pub attribute_terms: Vec<term_relationship::ObjectTerm>,
+    pub variations: Vec<PostWithMeta>,
+}
+
+impl ProductRecord {
+    pub fn image_ids(&self) -> Vec<u64> {
+        let mut image_ids: Vec<u64> = self
+            .meta
+            .get("_thumbnail_id")
+            .into_iter()
+            .chain(self.meta.get("_product_image_gallery"))
+            .flat_map(|ids| ids.split(','))
+            .filter_map(|id| id.trim().parse().ok())
+            .collect();
+
+        image_ids.extend(
+            self.variations
+                .iter()
+                .filter_map(|(_, meta)| meta.get("_thumbnail_id")?.trim().parse::<u64>().ok()),
+        );
+
+        image_ids
+    }
+}
+
+pub async fn get_products(
+    db: &DatabaseConnection,
+    post_type: &str,
+    filter: post::ProductFilter,
+    page: u64,
+    page_size: u64,
+    search: Option<String>,
+    lang: Option<&LanguageFilter>,
+) -> Result<(Vec<ProductRecord>, u64), ApiError> {
+    if page == 0 {
+        return Err(ApiError::BadRequest(
+            "Page number must be greater than zero".to_string(),
+        ));
+    }
+
+    if page_size == 0 {
+        return Err(ApiError::BadRequest(
+            "Page size must be greater than zero".to_string(),
+        ));
+    }
+
+    if page_size > 100 {
+        return Err(ApiError::BadRequest(
+            "Page size exceeds maximum limit of 100".to_string(),
+        ));
+    }
+
+    if let Some(search_term) = &search {
+        if search_term.chars().count() < 3 {
+            return Err(ApiError::BadRequest(
+                "Search term must be at least 3 characters long".to_string(),
+            ));
+        }
+    }
+
+    if let (Some(min_price), Some(max_price)) = (filter.min_price, filter.max_price) {
+        if min_price > max_price {
+            return Err(ApiError::BadRequest(
+                "Minimum price cannot be greater than maximum price".to_string(),
+            ));
+        }
+    }
+
+    let (products, total) =
+        match post::Entity::find_products(db, post_type, filter, page, page_size, search, lang)
+            .await
+        {
+            Ok(result) => result,
+            Err(err) => {
+                return Err(ApiError::InternalServerError(format!(
+                    "Failed to retrieve products: {}",
+                    err
+                )));
+            }
+        };
+
+    let records = load_product_records(db, products).await?;
+
+    Ok((records, total))
+}
+
+pub async fn get_product_by_id(
+    db: &DatabaseConnection,
+    post_type: &str,
+    product_id: u64,
+) -> Result<ProductRecord, ApiError> {
+    if product_id == 0 {
+        return Err(ApiError::BadRequest(
+            "Product ID must be a positive integer".to_string(),
+        ));
+    }
+
+    let product = get_post_by_id(db, product_id, true).await?;
+
+    if product.post_type != post_type {
+        return Err(ApiError::NotFound(format!(
+            "Product with ID {} not found",
+            product_id
+        )));
+    }
+
+    load_product_records(db, vec![product])
+        .await?
+        .pop()
+        .ok_or_else(|| ApiError::NotFound(format!("Product with ID {} not found", product_id)))
+}
+
+async fn load_product_records(
+    db: &DatabaseConnection,
+    products: Vec<post::Model>,
+) -> Result<Vec<ProductRecord>, ApiError> {
+    let product_ids: Vec<u64> = products.iter().map(|product| product.id).collect();
+
+    let mut meta = match postmeta::Entity::find_metadata_for_posts(
+        db,
+        product_ids.clone(),
+        &PRODUCT_META_KEYS,
+    )
+    .await
+    {
+        Ok(meta) => meta,
+        Err(err) => {
+            return Err(ApiError::InternalServerError(format!(
+                "Failed to retrieve product metadata: {}",
+                err
+            )));
+        }
+    };
+
+    let attribute_terms = match term_relationship::Entity::find_terms_for_objects(
+        db,
+        product_ids.clone(),
+        Some("pa_"),
+    )
+    .await
+    {
+        Ok(terms) => terms,
+        Err(err) => {
+            return Err(ApiError::InternalServerError(format!(
+                "Failed to retrieve product attributes: {}",
+                err
+            )));
+        }
+    };
+
+    let variations = match post::Entity::find_variations(db, product_ids).await {
+        Ok(variations) => variations,
+        Err(err) => {
+            return Err(ApiError::InternalServerError(format!(
+                "Failed to retrieve product variations: {}",
+                err
+            )));
+        }
+    };
+
+    let variation_ids = variations.iter().map(|variation| variation.id).collect();
+    let mut variation_meta =
+        match postmeta::Entity::find_all_metadata_for_posts(db, variation_ids).await {
+            Ok(meta) => meta,
+            Err(err) => {
+                return Err(ApiError::InternalServerError(format!(
+                    "Failed to retrieve product variation metadata: {}",
+                    err
+                )));
+            }
+        };
+
+    let mut terms_by_product: HashMap<u64, Vec<term_relationship::ObjectTerm>> = HashMap::new();
+    for term in attribute_terms {
+        terms_by_product
+            .entry(term.object_id)
+            .or_default()
+            .push(term);
+    }
+
+    let mut variations_by_product: HashMap<u64, Vec<PostWithMeta>> = HashMap::new();
+    for variation in variations {
+        let meta = variation_meta.remove(&variation.id).unwrap_or_default();
+        variations_by_product
+            .entry(variation.post_parent)
+            .or_default()
+            .push((variation, meta));
+    }
+
+    Ok(products
+        .into_iter()
+        .map(|product| ProductRecord {
+            meta: meta.remove(&product.id).unwrap_or_default(),
+            attribute_terms: terms_by_product.remove(&product.id).unwrap_or_default(),
+            variations: variations_by_product
+                .remove(&product.id)
+                .unwrap_or_default(),
+            product,
+        })
+        .collect())
+}
+
+pub const MENU_ITEM_META_KEYS: [&str; 7] = [
+    "_menu_item_type",
+    "_menu_item_menu_item_parent",
+    "_menu_item_object",
+    "_menu_item_object_id",
+    "_menu_item_url",
+    "_menu_item_target",
+    "_menu_item_classes",
+];
+
+pub struct MenuItemRecord {
+    pub item: post::Model,
+    pub meta: HashMap<String, String>,
+    pub object_title: Option<String>,
+    pub object_slug: Option<String>,
+}
+
+pub async fn get_menus(
+    db: &DatabaseConnection,
+) -> Result<Vec<(term_taxonomy::Model, term::Model)>, ApiError> {
+    match term_taxonomy::Entity::find_all_terms(db, "nav_menu", None).await {
+        Ok(menus) => Ok(menus),
+        Err(err) => Err(ApiError::InternalServerError(format!(
+            "Failed to retrieve menus: {}",
+            err
+        ))),
+    }
+}
+
+pub async fn get_menu_locations(
+    db: &DatabaseConnection,
+) -> Result<HashMap<u64, Vec<String>>, ApiError> {
+    // Menu locations are a theme modification, stored serialized in the
+    // `theme_mods_{stylesheet}` option of the active theme.
+    let stylesheet = get_site_options(db, &["stylesheet".to_string()])
+        .await?
+        .remove("stylesheet")
+        .unwrap_or_default();
+
+    if stylesheet.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let theme_mods_name = format!("theme_mods_{}", stylesheet);
+    let theme_mods = get_site_options(db, std::slice::from_ref(&theme_mods_name))
+        .await?
+        .remove(&theme_mods_name)
+        .and_then(|value| php::unserialize(&value))
+        .unwrap_or_default();
+
+    let mut locations: HashMap<u64, Vec<String>> = HashMap::new();
+    if let Some(menu_locations) = theme_mods
+        .get("nav_menu_locations")
+        .and_then(|value| value.as_object())
+    {
+        for (location, menu_id) in menu_locations {
+            let menu_id = match menu_id {
+                serde_json::Value::Number(number) => number.as_u64(),
+                serde_json::Value::String(string) => string.parse().ok(),
+                _ => None,
+            };
+
+            if let Some(menu_id) = menu_id.filter(|menu_id| *menu_id > 0) {
+                locations.entry(menu_id).or_default().push(location.clone());
+            }
+        }
+    }
+
+    for menu_locations in locations.values_mut() {
+        menu_locations.sort();
+    }
+
+    Ok(locations)
+}
+
+pub async fn get_menu_by_slug(
+    db: &DatabaseConnection,
+    slug: &str,
+) -> Result<(term_taxonomy::Model, term::Model, Vec<MenuItemRecord>), ApiError> {
+    if slug.is_empty() {
+        return Err(ApiError::BadRequest(
+            "Menu slug cannot be empty".to_string(),
+        ));
+    }
+
+    let (menu_taxonomy, menu_term) =
+        match term_taxonomy::Entity::find_term_by_slug(db, "nav_menu", slug).await {
+            Ok(Some(menu)) => menu,
+            Ok(None) => {
+                return Err(ApiError::NotFound(format!(
+                    "Menu with slug '{}' not found",
+                    slug
+                )));
+            }
+            Err(err) => {
+                return Err(ApiError::InternalServerError(format!(
+                    "Failed to retrieve menu '{}': {}",
+                    slug, err
+                )));
+            }
+        };
+
+    let items = match post::Entity::find_menu_items(db, menu_taxonomy.term_taxonomy_id).await {
+        Ok(items) => items,
+        Err(err) => {
+            return Err(ApiError::InternalServerError(format!(
+                "Failed to retrieve items for menu '{}': {}",
+                slug, err
+            )));
+        }
+    };
+
+    let item_ids = items.iter().map(|item| item.id).collect();
+    let mut meta =
+        match postmeta::Entity::find_metadata_for_posts(db, item_ids, &MENU_ITEM_META_KEYS).await {
+            Ok(meta) => meta,
+            Err(err) => {
+                return Err(ApiError::InternalServerError(format!(
+                    "Failed to retrieve metadata for menu '{}': {}",
+                    slug, err
+                )));
+            }
+        };
+
+    let object_ids = |item_type: &str| -> Vec<u64> {
+        meta.values()
+            .filter(|item_meta| {
+                item_meta.get("_menu_item_type").map(String::as_str) == Some(item_type)
+            })
+            .filter_map(|item_meta| item_meta.get("_menu_item_object_id")?.parse().ok())
+            .collect()
+    };
+    let post_ids = object_ids("post_type");
+    let term_ids = object_ids("taxonomy");
+
+    let posts: HashMap<u64, (String, String)> = match post::Entity::find_by_ids(db, post_ids).await
+    {
+        Ok(posts) => posts
+            .into_iter()
+            .filter(post::Model::is_published)
+            .map(|post| (post.id, (post.post_title, post.post_name)))
+            .collect(),
+        Err(err) => {
+            return Err(ApiError::InternalServerError(format!(
+                "Failed to resolve menu item posts: {}",
+                err
+            )));
+        }
+    };
+
+    let terms: HashMap<u64, (String, String)> = match term::Entity::find_by_ids(db, term_ids).await
+    {
+        Ok(terms) => terms
+            .into_iter()
+            .map(|term| (term.term_id, (term.name, term.slug)))
+            .collect(),
+        Err(err) => {
+            return Err(ApiError::InternalServerError(format!(
+                "Failed to resolve menu item terms: {}",
+                err
+            )));
+        }
+    };
+
+    // Like `_is_valid_nav_menu_item()`, items pointing at a post that is not
+    // published or a term that no longer exists are left out.
+    let records = items
+        .into_iter()
+        .filter_map(|item| {
+            let item_meta = meta.remove(&item.id).unwrap_or_default();
+            let object_id = item_meta
+                .get("_menu_item_object_id")
+                .and_then(|id| id.parse::<u64>().ok())
+                .unwrap_or(0);
+
+            let object = match item_meta.get("_menu_item_type").map(String::as_str) {
+                Some("post_type") => Some(posts.get(&object_id)?),
+                Some("taxonomy") => Some(terms.get(&object_id)?),
+                _ => None,
+            };
+
+            Some(MenuItemRecord {
+                object_title: object.map(|(title, _)| title.clone()),
+                object_slug: object.map(|(_, slug)| slug.clone()),
+                meta: item_meta,
+                item,
+            })
+        })
+        .collect();
+
+    Ok((menu_taxonomy, menu_term, records))
+}
+
+const MAX_REUSABLE_BLOCK_DEPTH: usize = 5;
+
+/// Expands the `core/block` references in the parsed content of every given
+/// document, loading the reusable blocks of all of them together.
+pub async fn resolve_reusable_blocks(
+    db: &DatabaseConnection,
+    documents: &mut [Vec<blocks::Block>],
+) -> Result<(), ApiError> {
+    let mut resolved: HashMap<u64, Vec<blocks::Block>> = HashMap::new();
+
+    // Reusable blocks can contain other reusable blocks; expanding a bounded
+    // number of levels also protects against blocks that reference themselves.
+    for _ in 0..MAX_REUSABLE_BLOCK_DEPTH {
+        let refs: Vec<u64> = documents
+            .iter()
+            .flat_map(|parsed_blocks| blocks::reusable_block_refs(parsed_blocks))
+            .collect();
+        if refs.is_empty() {
+            break;
+        }
+
+        let mut missing: Vec<u64> = refs
+            .into_iter()
+            .filter(|id| !resolved.contains_key(id))
+            .collect();
+        missing.sort_unstable();
+        missing.dedup();
+
+        if !missing.is_empty() {
+            let reusable_blocks = match post::Entity::find_by_ids(db, missing.clone()).await {
+                Ok(posts) => posts,
+                Err(err) => {
+                    return Err(ApiError::InternalServerError(format!(
+                        "Failed to retrieve reusable blocks: {}",
+                        err
+                    )));
+                }
+            };
+
+            for id in missing {
+                resolved.insert(id, Vec::new());
+            }
+
+            for reusable_block in reusable_blocks {
+                if reusable_block.post_type == "wp_block" && reusable_block.is_published() {
+                    resolved.insert(
+                        reusable_block.id,
+                        blocks::parse(&reusable_block.post_content),
+                    );
+                }
+            }
+        }
+
+        for parsed_blocks in documents.iter_mut() {
+            blocks::attach_reusable_blocks(parsed_blocks, &resolved);
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,100 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{LazyLock, Mutex, OnceLock, PoisonError};
+
+// Table names are resolved when queries are built rather than fixed at
+// compile time: the prefix comes from the configuration, and in a multisite
+// network each site has its own set of tables (`wp_3_posts`), chosen per
+// request with `for_site`.
+
+// Tables every site of a network has its own copy of.
+const SITE_TABLES: [&str; 10] = [
+    "posts",
+    "postmeta",
+    "options",
+    "comments",
+    "commentmeta",
+    "terms",
+    "termmeta",
+    "term_taxonomy",
+    "term_relationships",
+    "icl_translations",
+];
+
+// Tables shared by every site of a network.
+const GLOBAL_TABLES: [&str; 3] = ["users", "usermeta", "blogs"];
+
+static BASE_PREFIX: OnceLock<String> = OnceLock::new();
+
+// Entities hand out table names as `&str` borrowed from themselves, so the
+// names of a site are built and leaked once, the first time the site is
+// served, and then looked up without locking or allocating.
+static MAIN_SITE: LazyLock<Names> = LazyLock::new(|| Names::new(base_prefix(), &SITE_TABLES));
+static GLOBAL: LazyLock<Names> = LazyLock::new(|| Names::new(base_prefix(), &GLOBAL_TABLES));
+static SITES: LazyLock<Mutex<HashMap<u64, &'static Names>>> = LazyLock::new(Default::default);
+
+tokio::task_local! {
+    static SITE: &'static Names;
+}
+
+// The prefixed names of a list of tables.
+struct Names {
+    tables: &'static [&'static str],
+    names: Vec<&'static str>,
+}
+
+impl Names {
+    fn new(prefix: &str, tables: &'static [&'static str]) -> Self {
+        let names = tables
+            .iter()
+            .map(|table| &*Box::leak(format!("{}{}", prefix, table).into_boxed_str()))
+            .collect();
+
+        Names { tables, names }
+    }
+
+    fn get(&self, table: &str) -> &'static str {
+        match self.tables.iter().position(|name| *name == table) {
+            Some(index) => self.names[index],
+            None => panic!("unknown table: {}", table),
+        }
+    }
+}
+
+/// Sets the table prefix of the main site. Only the first call has effect,
+/// and it must come before any table name is resolved.
+pub fn set_base_prefix(prefix: &str) {
+    let _ = BASE_PREFIX.set(prefix.to_string());
+}
+
+fn base_prefix() -> &'static str {
+    BASE_PREFIX.get().map_or("wp_", String::as_str)
+}
+
+/// Name of a per-site table, for the site the current request is served
+/// from.
+pub fn site_table(table: &str) -> &'static str {
+    SITE.try_with(|site| site.get(table))
+        .unwrap_or_else(|_| MAIN_SITE.get(table))
+}
+
+/// Name of a table shared by every site of a network, such as `users`.
+pub fn global_table(table: &str) -> &'static str {
+    GLOBAL.get(table)
+}
+
+/// Runs a future with queries going to the tables of a site. The main site
+/// (blog 1) uses the base prefix, the others `{prefix}{blog_id}_`.
+pub async fn for_site<F: Future>(blog_id: u64, future: F) -> F::Output {
+    let site: &'static Names = if blog_id == 1 {
+        &MAIN_SITE
+    } else {
+        let mut sites = SITES.lock().unwrap_or_else(PoisonError::into_inner);
+        sites.entry(blog_id).or_insert_with(|| {
+            let prefix = format!("{}{}_", base_prefix(), blog_id);
+            Box::leak(Box::new(Names::new(&prefix, &SITE_TABLES)))
+        })
+    };
+
+    SITE.scope(site, future).await
+}
@@ -8,6 +8,8 @@ pub enum ApiError {
     DatabaseError(DbErr),
     NotFound(String),
     BadRequest(String),
+    Unauthorized(String),
+    Forbidden(String),
     InternalServerError(String),
 }
 
@@ -17,6 +19,8 @@ impl fmt::Display for ApiError {
             ApiError::DatabaseError(err) => write!(f, "Database error: {}", err),
             ApiError::NotFound(msg) => write!(f, "Not found: {}", msg),
             ApiError::BadRequest(msg) => write!(f, "Bad request: {}", msg),
+            ApiError::Unauthorized(msg) => write!(f, "Unauthorized: {}", msg),
+            ApiError::Forbidden(msg) => write!(f, "Forbidden: {}", msg),
             ApiError::InternalServerError(msg) => write!(f, "Internal server error: {}", msg),
         }
     }
@@ -62,6 +66,8 @@ impl ResponseError for ApiError {
             ApiError::DatabaseError(_) => StatusCode::INTERNAL_SERVER_ERROR,
             ApiError::NotFound(_) => StatusCode::NOT_FOUND,
             ApiError::BadRequest(_) => StatusCode::BAD_REQUEST,
+            ApiError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            ApiError::Forbidden(_) => StatusCode::FORBIDDEN,
             ApiError::InternalServerError(_) => StatusCode::INTERNAL_SERVER_ERROR,
         }
     }
@@ -3,11 +3,13 @@ mod config;
 mod db;
 mod error;
 mod models;
+mod utils;
 
 use actix_cors::Cors;
 use actix_web::{middleware, web, App, HttpServer};
-use api::routes;
+use api::{multisite, routes};
 use db::connection::establish_connection;
+use db::tables;
 use error::ApiError;
 use log::{error, info};
 use std::sync::Arc;
@@ -22,25 +24,26 @@ async fn main() -> std::io::Result<()> {
         Ok(cfg) => cfg,
         Err(err) => {
             error!("Failed to load configuration: {}", err);
-            return Err(std::io::Error::new(
-                std::io::ErrorKind::Other,
-                ApiError::InternalServerError(format!("Configuration error: {}", err)),
-            ));
+            return Err(std::io::Error::other(ApiError::InternalServerError(
+                format!("Configuration error: {}", err),
+            )));
         }
     };
 
+    tables::set_base_prefix(&config.database.table_prefix);
+
     let db_conn = match establish_connection(&config.database.url).await {
         Ok(conn) => conn,
         Err(err) => {
             error!("Failed to establish database connection: {}", err);
-            return Err(std::io::Error::new(
-                std::io::ErrorKind::Other,
-                ApiError::InternalServerError(format!("Database connection error: {}", err)),
-            ));
+            return Err(std::io::Error::other(ApiError::InternalServerError(
+                format!("Database connection error: {}", err),
+            )));
         }
     };
 
     let db_conn = Arc::new(db_conn);
+    let app_config = config.clone();
 
     info!(
         "Starting server at {}:{}",
@@ -55,6 +58,7 @@ async fn main() -> std::io::Result<()> {
             .max_age(3600);
 
         App::new()
+            .wrap(middleware::from_fn(multisite::route_to_site))
             .wrap(middleware::Logger::default())
             .wrap(cors)
             .app_data(web::QueryConfig::default().error_handler(|err, _| {
@@ -63,6 +67,7 @@ async fn main() -> std::io::Result<()> {
                 actix_web::error::ErrorBadRequest(error_message)
             }))
             .app_data(web::Data::new(db_conn.clone()))
+            .app_data(web::Data::new(app_config.clone()))
             .configure(routes::configure)
     })
     .bind((config.server.host.clone(), config.server.port))
@@ -0,0 +1,75 @@
+use chrono::NaiveDateTime;
+use sea_orm::entity::prelude::*;
+use sea_orm::{Order, QueryOrder};
+use serde::{Deserialize, Serialize};
+
+use crate::db::tables;
+
+#[derive(Copy, Clone, Default, Debug, DeriveEntity)]
+pub struct Entity;
+
+impl EntityName for Entity {
+    fn table_name(&self) -> &str {
+        tables::global_table("blogs")
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub blog_id: u64,
+    pub site_id: u64,
+    pub domain: String,
+    pub path: String,
+    pub registered: Option<NaiveDateTime>,
+    pub last_updated: Option<NaiveDateTime>,
+    pub public: i8,
+    pub archived: i8,
+    pub mature: i8,
+    pub spam: i8,
+    pub deleted: i8,
+    pub lang_id: i32,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Entity {
+    // Archived, spam and deleted sites are not served by WordPress either.
+    fn active() -> Select<Self> {
+        Self::find()
+            .filter(Column::Archived.eq(0))
+            .filter(Column::Spam.eq(0))
+            .filter(Column::Deleted.eq(0))
+    }
+
+    pub async fn find_active_by_id(
+        db: &DatabaseConnection,
+        blog_id: u64,
+    ) -> Result<Option<Model>, DbErr> {
+        Self::active()
+            .filter(Column::BlogId.eq(blog_id))
+            .one(db)
+            .await
+    }
+
+    pub async fn find_active(
+        db: &DatabaseConnection,
+        page: u64,
+        page_size: u64,
+    ) -> Result<(Vec<Model>, u64), DbErr> {
+        let query = Self::active();
+
+        let total = query.clone().count(db).await?;
+
+        let blogs = query
+            .order_by(Column::BlogId, Order::Asc)
+            .paginate(db, page_size)
+            .fetch_page(page - 1)
+            .await?;
+
+        Ok((blogs, total))
+    }
+}
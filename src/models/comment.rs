@@ -0,0 +1,148 @@
+use chrono::NaiveDateTime;
+use sea_orm::entity::prelude::*;
+use sea_orm::{Order, QueryOrder};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::db::tables;
+
+#[derive(Copy, Clone, Default, Debug, DeriveEntity)]
+pub struct Entity;
+
+impl EntityName for Entity {
+    fn table_name(&self) -> &str {
+        tables::site_table("comments")
+    }
+}
+
+// `comment_author_email` and `comment_author_IP` are deliberately not mapped so
+// they can never leak through the API.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+pub struct Model {
+    #[sea_orm(primary_key, column_name = "comment_ID")]
+    pub comment_id: u64,
+    #[sea_orm(column_name = "comment_post_ID")]
+    pub comment_post_id: u64,
+    pub comment_author: String,
+    pub comment_author_url: String,
+    pub comment_date: Option<NaiveDateTime>,
+    pub comment_date_gmt: Option<NaiveDateTime>,
+    pub comment_content: String,
+    pub comment_approved: String,
+    pub comment_type: String,
+    pub comment_parent: u64,
+    pub user_id: u64,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::post::Entity",
+        from = "Column::CommentPostId",
+        to = "super::post::Column::Id"
+    )]
+    Post,
+    #[sea_orm(has_many = "super::commentmeta::Entity")]
+    Commentmeta,
+}
+
+impl Related<super::post::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Post.def()
+    }
+}
+
+impl Related<super::commentmeta::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Commentmeta.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+#[derive(Clone, Debug)]
+pub struct CommentThread {
+    pub comment: Model,
+    pub rating: Option<String>,
+    pub replies: Vec<CommentThread>,
+}
+
+impl Entity {
+    fn approved_for_post(post_id: u64) -> Select<Entity> {
+        Self::find()
+            .filter(Column::CommentPostId.eq(post_id))
+            .filter(Column::CommentApproved.eq("1"))
+    }
+
+    pub async fn find_threads(
+        db: &DatabaseConnection,
+        post_id: u64,
+        page: u64,
+        page_size: u64,
+    ) -> Result<(Vec<CommentThread>, u64), DbErr> {
+        let query = Self::approved_for_post(post_id).filter(Column::CommentParent.eq(0));
+
+        let total = query.clone().count(db).await?;
+
+        let roots = query
+            .order_by(Column::CommentDateGmt, Order::Asc)
+            .order_by(Column::CommentId, Order::Asc)
+            .paginate(db, page_size)
+            .fetch_page(page - 1)
+            .await?;
+
+        let mut comment_ids: Vec<u64> = roots.iter().map(|comment| comment.comment_id).collect();
+        let mut children: HashMap<u64, Vec<Model>> = HashMap::new();
+        let mut parent_ids = comment_ids.clone();
+
+        // Walk the reply tree one level at a time so only the threads on this
+        // page are loaded.
+        while !parent_ids.is_empty() {
+            let replies = Self::approved_for_post(post_id)
+                .filter(Column::CommentParent.is_in(parent_ids))
+                .order_by(Column::CommentDateGmt, Order::Asc)
+                .order_by(Column::CommentId, Order::Asc)
+                .all(db)
+                .await?;
+
+            parent_ids = replies.iter().map(|reply| reply.comment_id).collect();
+            comment_ids.extend(parent_ids.iter().copied());
+
+            for reply in replies {
+                children
+                    .entry(reply.comment_parent)
+                    .or_default()
+                    .push(reply);
+            }
+        }
+
+        let mut ratings =
+            super::commentmeta::Entity::find_values(db, comment_ids, "rating").await?;
+
+        let threads = roots
+            .into_iter()
+            .map(|comment| Self::build_thread(comment, &mut children, &mut ratings))
+            .collect();
+
+        Ok((threads, total))
+    }
+
+    fn build_thread(
+        comment: Model,
+        children: &mut HashMap<u64, Vec<Model>>,
+        ratings: &mut HashMap<u64, String>,
+    ) -> CommentThread {
+        let replies = children
+            .remove(&comment.comment_id)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|reply| Self::build_thread(reply, children, ratings))
+            .collect();
+
+        CommentThread {
+            rating: ratings.remove(&comment.comment_id),
+            comment,
+            replies,
+        }
+    }
+}
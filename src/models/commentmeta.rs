@@ -0,0 +1,64 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::db::tables;
+
+#[derive(Copy, Clone, Default, Debug, DeriveEntity)]
+pub struct Entity;
+
+impl EntityName for Entity {
+    fn table_name(&self) -> &str {
+        tables::site_table("commentmeta")
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub meta_id: u64,
+    pub comment_id: u64,
+    pub meta_key: String,
+    pub meta_value: String,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::comment::Entity",
+        from = "Column::CommentId",
+        to = "super::comment::Column::CommentId"
+    )]
+    Comment,
+}
+
+impl Related<super::comment::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Comment.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Entity {
+    pub async fn find_values(
+        db: &DatabaseConnection,
+        comment_ids: Vec<u64>,
+        meta_key: &str,
+    ) -> Result<HashMap<u64, String>, DbErr> {
+        if comment_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let metadata = Self::find()
+            .filter(Column::CommentId.is_in(comment_ids))
+            .filter(Column::MetaKey.eq(meta_key))
+            .all(db)
+            .await?;
+
+        Ok(metadata
+            .into_iter()
+            .map(|meta| (meta.comment_id, meta.meta_value))
+            .collect())
+    }
+}
@@ -0,0 +1,67 @@
+use sea_orm::entity::prelude::*;
+use sea_orm::QuerySelect;
+use serde::{Deserialize, Serialize};
+
+use crate::db::tables;
+
+#[derive(Copy, Clone, Default, Debug, DeriveEntity)]
+pub struct Entity;
+
+impl EntityName for Entity {
+    fn table_name(&self) -> &str {
+        tables::site_table("icl_translations")
+    }
+}
+
+/// WPML's record of the language of a post (`element_type` `post_{type}`) or
+/// term (`tax_{taxonomy}`, keyed by term taxonomy ID). Translations of the
+/// same element share a `trid`.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub translation_id: u64,
+    pub element_type: String,
+    pub element_id: Option<u64>,
+    pub trid: u64,
+    pub language_code: String,
+    pub source_language_code: Option<String>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Entity {
+    /// `(language_code, element_id)` of every translation of an element,
+    /// the element itself included.
+    pub async fn find_translations(
+        db: &DatabaseConnection,
+        element_type: &str,
+        element_id: u64,
+    ) -> Result<Vec<(String, u64)>, DbErr> {
+        let trid = Self::find()
+            .select_only()
+            .column(Column::Trid)
+            .filter(Column::ElementType.eq(element_type))
+            .filter(Column::ElementId.eq(element_id))
+            .into_tuple::<u64>()
+            .one(db)
+            .await?;
+
+        let Some(trid) = trid else {
+            return Ok(Vec::new());
+        };
+
+        Self::find()
+            .select_only()
+            .column(Column::LanguageCode)
+            .column(Column::ElementId)
+            .filter(Column::Trid.eq(trid))
+            .filter(Column::ElementType.eq(element_type))
+            .filter(Column::ElementId.is_not_null())
+            .into_tuple::<(String, u64)>()
+            .all(db)
+            .await
+    }
+}
@@ -0,0 +1,147 @@
+use sea_orm::entity::prelude::*;
+use sea_orm::sea_query::{Expr, Query, SelectStatement, SimpleExpr};
+use sea_orm::{DbBackend, Statement};
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex, MutexGuard, PoisonError};
+
+use super::{icl_translation, post, term, term_relationship, term_taxonomy};
+use crate::db::tables;
+
+/// The plugin a multilingual site manages its languages with.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Plugin {
+    /// Polylang stores languages and translation groups as terms: posts
+    /// belong to a `language` term and terms to a `term_language` term.
+    Polylang,
+    /// WPML records the language of every post and term in its own
+    /// `icl_translations` table.
+    Wpml,
+}
+
+// The plugin found for each site, keyed by the name of the site's WPML
+// table, which differs between the sites of a network.
+static DETECTED: LazyLock<Mutex<HashMap<&'static str, Option<Plugin>>>> =
+    LazyLock::new(Default::default);
+
+impl Plugin {
+    /// Finds the plugin from its data rather than from the active plugins,
+    /// so a site keeps its languages when the plugin is briefly disabled.
+    /// Each site is only looked at once; the result is kept until restart.
+    pub async fn detect(db: &DatabaseConnection) -> Result<Option<Plugin>, DbErr> {
+        let site = tables::site_table("icl_translations");
+
+        if let Some(plugin) = Self::detected().get(site) {
+            return Ok(*plugin);
+        }
+
+        let plugin = Self::find(db, site).await?;
+        Self::detected().insert(site, plugin);
+
+        Ok(plugin)
+    }
+
+    fn detected() -> MutexGuard<'static, HashMap<&'static str, Option<Plugin>>> {
+        DETECTED.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    async fn find(
+        db: &DatabaseConnection,
+        wpml_table: &'static str,
+    ) -> Result<Option<Plugin>, DbErr> {
+        let languages = term_taxonomy::Entity::find()
+            .filter(term_taxonomy::Column::Taxonomy.eq("language"))
+            .count(db)
+            .await?;
+        if languages > 0 {
+            return Ok(Some(Plugin::Polylang));
+        }
+
+        let statement = Statement::from_sql_and_values(
+            DbBackend::MySql,
+            "SELECT COUNT(*) AS count FROM information_schema.tables \
+             WHERE table_schema = DATABASE() AND table_name = ?",
+            [wpml_table.into()],
+        );
+        let tables = match db.query_one(statement).await? {
+            Some(row) => row.try_get::<i64>("", "count")?,
+            None => 0,
+        };
+
+        Ok((tables > 0).then_some(Plugin::Wpml))
+    }
+}
+
+/// Restricts a listing to the posts or terms of one language.
+#[derive(Clone, Debug)]
+pub struct LanguageFilter {
+    plugin: Plugin,
+    code: String,
+}
+
+impl LanguageFilter {
+    pub fn new(plugin: Plugin, code: String) -> Self {
+        LanguageFilter { plugin, code }
+    }
+
+    /// Condition on the posts table.
+    pub fn posts(&self) -> SimpleExpr {
+        let subquery = match self.plugin {
+            Plugin::Polylang => Self::polylang_objects("language", &self.code),
+            Plugin::Wpml => Self::wpml_elements("post_", &self.code),
+        };
+
+        post::Column::Id.in_subquery(subquery)
+    }
+
+    /// Condition on the term taxonomy table.
+    pub fn terms(&self) -> SimpleExpr {
+        match self.plugin {
+            // The `term_language` terms are the language slugs prefixed
+            // with `pll_`.
+            Plugin::Polylang => term_taxonomy::Column::TermId.in_subquery(Self::polylang_objects(
+                "term_language",
+                &format!("pll_{}", self.code),
+            )),
+            Plugin::Wpml => term_taxonomy::Column::TermTaxonomyId
+                .in_subquery(Self::wpml_elements("tax_", &self.code)),
+        }
+    }
+
+    fn polylang_objects(taxonomy: &str, slug: &str) -> SelectStatement {
+        Query::select()
+            .column((
+                term_relationship::Entity,
+                term_relationship::Column::ObjectId,
+            ))
+            .from(term_relationship::Entity)
+            .inner_join(
+                term_taxonomy::Entity,
+                Expr::col((term_taxonomy::Entity, term_taxonomy::Column::TermTaxonomyId)).equals((
+                    term_relationship::Entity,
+                    term_relationship::Column::TermTaxonomyId,
+                )),
+            )
+            .inner_join(
+                term::Entity,
+                Expr::col((term::Entity, term::Column::TermId))
+                    .equals((term_taxonomy::Entity, term_taxonomy::Column::TermId)),
+            )
+            .and_where(
+                Expr::col((term_taxonomy::Entity, term_taxonomy::Column::Taxonomy)).eq(taxonomy),
+            )
+            .and_where(Expr::col((term::Entity, term::Column::Slug)).eq(slug))
+            .to_owned()
+    }
+
+    fn wpml_elements(element_type_prefix: &str, code: &str) -> SelectStatement {
+        Query::select()
+            .column(icl_translation::Column::ElementId)
+            .from(icl_translation::Entity)
+            .and_where(icl_translation::Column::LanguageCode.eq(code))
+            .and_where(
+                icl_translation::Column::ElementType
+                    .starts_with(post::esc_like(element_type_prefix)),
+            )
+            .to_owned()
+    }
+}
@@ -1,5 +1,14 @@
+pub mod blog;
+pub mod comment;
+pub mod commentmeta;
+pub mod icl_translation;
+pub mod language;
+pub mod option;
 pub mod post;
 pub mod postmeta;
 pub mod term;
 pub mod term_relationship;
 pub mod term_taxonomy;
+pub mod termmeta;
+pub mod user;
+pub mod usermeta;
@@ -0,0 +1,49 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::db::tables;
+
+#[derive(Copy, Clone, Default, Debug, DeriveEntity)]
+pub struct Entity;
+
+impl EntityName for Entity {
+    fn table_name(&self) -> &str {
+        tables::site_table("options")
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub option_id: u64,
+    pub option_name: String,
+    pub option_value: String,
+    pub autoload: String,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Entity {
+    pub async fn find_options_map(
+        db: &DatabaseConnection,
+        option_names: &[String],
+    ) -> Result<HashMap<String, String>, DbErr> {
+        if option_names.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let options = Self::find()
+            .filter(Column::OptionName.is_in(option_names.iter().cloned()))
+            .all(db)
+            .await?;
+
+        Ok(options
+            .into_iter()
+            .map(|option| (option.option_name, option.option_value))
+            .collect())
+    }
+}
@@ -1,11 +1,23 @@
-use chrono::NaiveDateTime;
+use chrono::{NaiveDateTime, Utc};
 use sea_orm::entity::prelude::*;
-use sea_orm::sea_query::NullOrdering;
-use sea_orm::{Condition, EntityOrSelect, Order, QueryOrder, QuerySelect};
+use sea_orm::sea_query::{Expr, NullOrdering, Query, SelectStatement, SimpleExpr};
+use sea_orm::{Condition, EntityOrSelect, FromQueryResult, Order, QueryOrder, QuerySelect};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use super::language::LanguageFilter;
+use crate::db::tables;
+
+#[derive(Copy, Clone, Default, Debug, DeriveEntity)]
+pub struct Entity;
+
+impl EntityName for Entity {
+    fn table_name(&self) -> &str {
+        tables::site_table("posts")
+    }
+}
 
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
-#[sea_orm(table_name = "wp_posts")]
 pub struct Model {
     #[sea_orm(primary_key, column_name = "ID")]
     pub id: u64,
@@ -35,6 +47,159 @@ pub struct Model {
     pub comment_count: i64,
 }
 
+#[derive(Clone, Debug, Default)]
+pub struct ProductFilter {
+    pub min_price: Option<f64>,
+    pub max_price: Option<f64>,
+    pub in_stock: Option<bool>,
+    pub attributes: Vec<(String, String)>,
+}
+
+/// How posts listed in the `sticky_posts` option are treated in a listing.
+#[derive(Clone, Debug)]
+pub enum StickyFilter {
+    Only(Vec<u64>),
+    Exclude(Vec<u64>),
+    /// Sticky posts are listed before the others, like on the blog home.
+    First(Vec<u64>),
+}
+
+/// Conditions on post meta, like the clauses of a WordPress `meta_query`.
+#[derive(Clone, Debug)]
+pub struct MetaFilter {
+    pub conditions: Vec<MetaCondition>,
+    /// Whether a post has to match any of the conditions rather than all.
+    pub any: bool,
+}
+
+#[derive(Clone, Debug)]
+pub struct MetaCondition {
+    pub key: String,
+    pub compare: MetaCompare,
+    pub value: String,
+    /// Whether the values are compared as numbers rather than strings.
+    pub numeric: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MetaCompare {
+    Equal,
+    NotEqual,
+    Greater,
+    GreaterOrEqual,
+    Less,
+    LessOrEqual,
+    Like,
+    NotLike,
+    Exists,
+    NotExists,
+}
+
+impl MetaCompare {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_uppercase().replace('_', " ").as_str() {
+            "=" => Some(MetaCompare::Equal),
+            "!=" => Some(MetaCompare::NotEqual),
+            ">" => Some(MetaCompare::Greater),
+            ">=" => Some(MetaCompare::GreaterOrEqual),
+            "<" => Some(MetaCompare::Less),
+            "<=" => Some(MetaCompare::LessOrEqual),
+            "LIKE" => Some(MetaCompare::Like),
+            "NOT LIKE" => Some(MetaCompare::NotLike),
+            "EXISTS" => Some(MetaCompare::Exists),
+            "NOT EXISTS" => Some(MetaCompare::NotExists),
+            _ => None,
+        }
+    }
+
+    /// Whether the comparison is made against a value.
+    pub fn takes_value(self) -> bool {
+        !matches!(self, MetaCompare::Exists | MetaCompare::NotExists)
+    }
+}
+
+impl MetaFilter {
+    /// The filter as a condition on post IDs, with a subquery on post meta
+    /// for each condition so posts with several rows for a key are listed
+    /// once.
+    pub fn condition(&self) -> Condition {
+        let condition = if self.any {
+            Condition::any()
+        } else {
+            Condition::all()
+        };
+
+        self.conditions
+            .iter()
+            .fold(condition, |condition, meta| condition.add(meta.expr()))
+    }
+}
+
+impl MetaCondition {
+    // As in `meta_query`, a post matches when any of its rows for the key
+    // does, so `!=` and `NOT LIKE` only match posts that have the key.
+    fn expr(&self) -> SimpleExpr {
+        let mut subquery = Query::select()
+            .column(super::postmeta::Column::PostId)
+            .from(super::postmeta::Entity)
+            .and_where(super::postmeta::Column::MetaKey.eq(self.key.as_str()))
+            .to_owned();
+
+        let meta_value = super::postmeta::Column::MetaValue;
+
+        // Numeric comparisons cast both sides like `meta_type` NUMERIC, and
+        // skip empty values, which MySQL would cast to 0.
+        let (left, right): (Expr, SimpleExpr) = if self.numeric && self.compare.takes_value() {
+            subquery.and_where(meta_value.ne(""));
+            (
+                Expr::expr(Expr::cust("CAST(`meta_value` AS DECIMAL(20, 4))")),
+                Expr::cust_with_values("CAST(? AS DECIMAL(20, 4))", [self.value.as_str()]),
+            )
+        } else {
+            (Expr::col(meta_value), Expr::val(self.value.as_str()).into())
+        };
+
+        let comparison = match self.compare {
+            MetaCompare::Equal => left.eq(right),
+            MetaCompare::NotEqual => left.ne(right),
+            MetaCompare::Greater => left.gt(right),
+            MetaCompare::GreaterOrEqual => left.gte(right),
+            MetaCompare::Less => left.lt(right),
+            MetaCompare::LessOrEqual => left.lte(right),
+            MetaCompare::Like => meta_value.like(format!("%{}%", esc_like(&self.value))),
+            MetaCompare::NotLike => meta_value.not_like(format!("%{}%", esc_like(&self.value))),
+            MetaCompare::Exists => return Column::Id.in_subquery(subquery),
+            MetaCompare::NotExists => return Column::Id.not_in_subquery(subquery),
+        };
+        subquery.and_where(comparison);
+
+        Column::Id.in_subquery(subquery)
+    }
+}
+
+/// Port of `wpdb::esc_like()`: escapes the LIKE wildcards `%` and `_`, and
+/// the backslash MySQL uses to escape them, so the text matches literally.
+pub fn esc_like(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '\\' | '%' | '_') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// The columns needed to build a page hierarchy, without the content.
+#[derive(Clone, Debug, FromQueryResult)]
+pub struct PageSummary {
+    pub id: u64,
+    pub post_title: String,
+    pub post_name: String,
+    pub post_parent: u64,
+    pub menu_order: i32,
+}
+
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
     #[sea_orm(has_many = "super::postmeta::Entity")]
@@ -57,11 +222,52 @@ impl Related<super::term_relationship::Entity> for Entity {
 
 impl ActiveModelBehavior for ActiveModel {}
 
+impl Model {
+    /// Whether the post is published and its date has come. See
+    /// `Entity::published`.
+    pub fn is_published(&self) -> bool {
+        self.post_status == "publish"
+            && self
+                .post_date_gmt
+                .is_none_or(|date| date <= Utc::now().naive_utc())
+    }
+}
+
 impl Entity {
+    /// Published posts, leaving out any whose `post_date_gmt` is still
+    /// ahead. WordPress schedules such posts as `future`, but imports and
+    /// direct edits can leave them marked `publish` before their date.
+    pub fn published() -> Condition {
+        Condition::all().add(Column::PostStatus.eq("publish")).add(
+            Condition::any()
+                .add(Column::PostDateGmt.is_null())
+                .add(Column::PostDateGmt.lte(Utc::now().naive_utc())),
+        )
+    }
+
+    // Like WordPress's search for visitors, password-protected posts never
+    // match, so their content cannot be probed through search.
+    fn search(term: &str) -> Condition {
+        Condition::all().add(Column::PostPassword.eq("")).add(
+            Condition::any()
+                .add(Column::PostTitle.contains(term))
+                .add(Column::PostContent.contains(term)),
+        )
+    }
+
     pub async fn find_by_id(db: &DatabaseConnection, id: u64) -> Result<Option<Model>, DbErr> {
         Entity::find().filter(Column::Id.eq(id)).one(db).await
     }
 
+    pub async fn find_by_ids(db: &DatabaseConnection, ids: Vec<u64>) -> Result<Vec<Model>, DbErr> {
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        Entity::find().filter(Column::Id.is_in(ids)).all(db).await
+    }
+
+    #[allow(clippy::too_many_arguments)]
     pub async fn find_posts(
         db: &DatabaseConnection,
         post_type: Option<String>,
@@ -70,15 +276,28 @@ impl Entity {
         page_size: u64,
         search: Option<String>,
         author_id: Option<u64>,
+        sticky: Option<StickyFilter>,
+        meta: Option<&MetaFilter>,
+        lang: Option<&LanguageFilter>,
     ) -> Result<(Vec<Model>, u64), DbErr> {
         let mut query = Self::find();
 
+        if let Some(lang) = lang {
+            query = query.filter(lang.posts());
+        }
+
+        if let Some(meta) = meta {
+            query = query.filter(meta.condition());
+        }
+
         if let Some(post_type) = post_type {
             query = query.filter(Column::PostType.eq(post_type));
         }
 
-        if let Some(post_status) = post_status {
-            query = query.filter(Column::PostStatus.eq(post_status));
+        match post_status.as_deref() {
+            Some("publish") => query = query.filter(Self::published()),
+            Some(post_status) => query = query.filter(Column::PostStatus.eq(post_status)),
+            None => {}
         }
 
         if let Some(author_id) = author_id {
@@ -86,15 +305,32 @@ impl Entity {
         }
 
         if let Some(search) = search {
-            query = query.filter(
-                Condition::any()
-                    .add(Column::PostTitle.contains(&search))
-                    .add(Column::PostContent.contains(&search)),
-            );
+            query = query.filter(Self::search(&search));
+        }
+
+        match &sticky {
+            Some(StickyFilter::Only(ids)) => {
+                query = query.filter(Column::Id.is_in(ids.clone()));
+            }
+            Some(StickyFilter::Exclude(ids)) if !ids.is_empty() => {
+                query = query.filter(Column::Id.is_not_in(ids.clone()));
+            }
+            _ => {}
         }
 
         let total = query.clone().count(db).await?;
 
+        // Pinning is done in the ordering rather than by merging two queries,
+        // so pages and the total stay consistent.
+        if let Some(StickyFilter::First(ids)) = sticky {
+            if !ids.is_empty() {
+                query = query.order_by(
+                    SimpleExpr::Case(Box::new(Expr::case(Column::Id.is_in(ids), 0).finally(1))),
+                    Order::Asc,
+                );
+            }
+        }
+
         let posts = query
             .order_by_with_nulls(Column::PostDate, Order::Desc, NullOrdering::Last)
             .paginate(db, page_size)
@@ -104,6 +340,7 @@ impl Entity {
         Ok((posts, total))
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub async fn find_by_type(
         db: &DatabaseConnection,
         post_type: &str,
@@ -111,30 +348,386 @@ impl Entity {
         page: u64,
         page_size: u64,
         search: Option<String>,
+        meta: Option<&MetaFilter>,
+        lang: Option<&LanguageFilter>,
     ) -> Result<(Vec<Model>, u64), DbErr> {
         let mut query = Self::find().filter(Column::PostType.eq(post_type));
 
-        if let Some(status) = post_status {
-            query = query.filter(Column::PostStatus.eq(status));
+        if let Some(lang) = lang {
+            query = query.filter(lang.posts());
+        }
+
+        if let Some(meta) = meta {
+            query = query.filter(meta.condition());
+        }
+
+        match post_status {
+            Some("publish") => query = query.filter(Self::published()),
+            Some(status) => query = query.filter(Column::PostStatus.eq(status)),
+            None => {}
+        }
+
+        if let Some(search_term) = search {
+            query = query.filter(Self::search(&search_term));
+        }
+
+        let total = query.clone().count(db).await?;
+
+        let posts = query
+            .order_by_with_nulls(Column::PostDate, Order::Desc, NullOrdering::Last)
+            .paginate(db, page_size)
+            .fetch_page(page - 1)
+            .await?;
+
+        Ok((posts, total))
+    }
+
+    pub async fn find_attachments(
+        db: &DatabaseConnection,
+        page: u64,
+        page_size: u64,
+        search: Option<String>,
+        mime_type: Option<String>,
+        lang: Option<&LanguageFilter>,
+    ) -> Result<(Vec<Model>, u64), DbErr> {
+        let mut query = Self::find()
+            .filter(Column::PostType.eq("attachment"))
+            .filter(Column::PostStatus.is_in(["inherit", "publish"]));
+
+        if let Some(lang) = lang {
+            query = query.filter(lang.posts());
+        }
+
+        if let Some(mime_type) = mime_type {
+            // Accept either a full MIME type ("image/png") or just its
+            // top-level type ("image").
+            if mime_type.contains('/') {
+                query = query.filter(Column::PostMimeType.eq(mime_type));
+            } else {
+                query = query.filter(Column::PostMimeType.starts_with(format!("{}/", mime_type)));
+            }
         }
 
         if let Some(search_term) = search {
             query = query.filter(
                 Condition::any()
                     .add(Column::PostTitle.contains(&search_term))
-                    .add(Column::PostContent.contains(&search_term)),
+                    .add(Column::PostExcerpt.contains(&search_term)),
             );
         }
 
         let total = query.clone().count(db).await?;
 
-        let posts = query
+        let attachments = query
             .order_by_with_nulls(Column::PostDate, Order::Desc, NullOrdering::Last)
             .paginate(db, page_size)
             .fetch_page(page - 1)
             .await?;
 
-        Ok((posts, total))
+        Ok((attachments, total))
+    }
+
+    pub async fn find_products(
+        db: &DatabaseConnection,
+        post_type: &str,
+        filter: ProductFilter,
+        page: u64,
+        page_size: u64,
+        search: Option<String>,
+        lang: Option<&LanguageFilter>,
+    ) -> Result<(Vec<Model>, u64), DbErr> {
+        let mut query = Self::find()
+            .filter(Column::PostType.eq(post_type))
+            .filter(Self::published());
+
+        if let Some(lang) = lang {
+            query = query.filter(lang.posts());
+        }
+
+        // Variable products store one `_price` row per variation, so a
+        // product matches when any of its prices falls inside the range.
+        if let Some(min_price) = filter.min_price {
+            query = query.filter(Column::Id.in_subquery(Self::price_subquery(">=", min_price)));
+        }
+
+        if let Some(max_price) = filter.max_price {
+            query = query.filter(Column::Id.in_subquery(Self::price_subquery("<=", max_price)));
+        }
+
+        if let Some(in_stock) = filter.in_stock {
+            let stock_subquery = Query::select()
+                .column(super::postmeta::Column::PostId)
+                .from(super::postmeta::Entity)
+                .and_where(super::postmeta::Column::MetaKey.eq("_stock_status"))
+                .and_where(super::postmeta::Column::MetaValue.eq("instock"))
+                .to_owned();
+
+            query = if in_stock {
+                query.filter(Column::Id.in_subquery(stock_subquery))
+            } else {
+                query.filter(Column::Id.not_in_subquery(stock_subquery))
+            };
+        }
+
+        for (taxonomy, slug) in filter.attributes {
+            let attribute_subquery = Query::select()
+                .column((
+                    super::term_relationship::Entity,
+                    super::term_relationship::Column::ObjectId,
+                ))
+                .from(super::term_relationship::Entity)
+                .inner_join(
+                    super::term_taxonomy::Entity,
+                    Expr::col((
+                        super::term_taxonomy::Entity,
+                        super::term_taxonomy::Column::TermTaxonomyId,
+                    ))
+                    .equals((
+                        super::term_relationship::Entity,
+                        super::term_relationship::Column::TermTaxonomyId,
+                    )),
+                )
+                .inner_join(
+                    super::term::Entity,
+                    Expr::col((super::term::Entity, super::term::Column::TermId)).equals((
+                        super::term_taxonomy::Entity,
+                        super::term_taxonomy::Column::TermId,
+                    )),
+                )
+                .and_where(
+                    Expr::col((
+                        super::term_taxonomy::Entity,
+                        super::term_taxonomy::Column::Taxonomy,
+                    ))
+                    .eq(taxonomy),
+                )
+                .and_where(Expr::col((super::term::Entity, super::term::Column::Slug)).eq(slug))
+                .to_owned();
+
+            query = query.filter(Column::Id.in_subquery(attribute_subquery));
+        }
+
+        if let Some(search_term) = search {
+            query = query.filter(Self::search(&search_term));
+        }
+
+        let total = query.clone().count(db).await?;
+
+        let products = query
+            .order_by_with_nulls(Column::PostDate, Order::Desc, NullOrdering::Last)
+            .paginate(db, page_size)
+            .fetch_page(page - 1)
+            .await?;
+
+        Ok((products, total))
+    }
+
+    fn price_subquery(operator: &str, price: f64) -> SelectStatement {
+        Query::select()
+            .column(super::postmeta::Column::PostId)
+            .from(super::postmeta::Entity)
+            .and_where(super::postmeta::Column::MetaKey.eq("_price"))
+            .and_where(super::postmeta::Column::MetaValue.ne(""))
+            .and_where(Expr::cust_with_values(
+                format!("CAST(`meta_value` AS DECIMAL(20, 4)) {} ?", operator),
+                [price],
+            ))
+            .to_owned()
+    }
+
+    pub async fn find_variations(
+        db: &DatabaseConnection,
+        product_ids: Vec<u64>,
+    ) -> Result<Vec<Model>, DbErr> {
+        if product_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        Self::find()
+            .filter(Column::PostType.eq("product_variation"))
+            .filter(Self::published())
+            .filter(Column::PostParent.is_in(product_ids))
+            .order_by(Column::PostParent, Order::Asc)
+            .order_by(Column::MenuOrder, Order::Asc)
+            .order_by(Column::Id, Order::Asc)
+            .all(db)
+            .await
+    }
+
+    pub async fn find_attached_images(
+        db: &DatabaseConnection,
+        parent_ids: Vec<u64>,
+    ) -> Result<Vec<Model>, DbErr> {
+        if parent_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        Self::find()
+            .filter(Column::PostType.eq("attachment"))
+            .filter(Column::PostStatus.eq("inherit"))
+            .filter(Column::PostMimeType.starts_with("image/"))
+            .filter(Column::PostParent.is_in(parent_ids))
+            .order_by(Column::PostParent, Order::Asc)
+            .order_by(Column::MenuOrder, Order::Asc)
+            .order_by(Column::Id, Order::Asc)
+            .all(db)
+            .await
+    }
+
+    pub async fn find_children(
+        db: &DatabaseConnection,
+        parent_id: u64,
+        post_type: &str,
+        page: u64,
+        page_size: u64,
+        lang: Option<&LanguageFilter>,
+    ) -> Result<(Vec<Model>, u64), DbErr> {
+        let mut query = Self::find()
+            .filter(Column::PostParent.eq(parent_id))
+            .filter(Column::PostType.eq(post_type))
+            .filter(Self::published());
+
+        if let Some(lang) = lang {
+            query = query.filter(lang.posts());
+        }
+
+        let total = query.clone().count(db).await?;
+
+        let children = query
+            .order_by(Column::MenuOrder, Order::Asc)
+            .order_by(Column::PostTitle, Order::Asc)
+            .paginate(db, page_size)
+            .fetch_page(page - 1)
+            .await?;
+
+        Ok((children, total))
+    }
+
+    // Autosaves are stored as revisions too, but are named
+    // `{parent}-autosave-v1` and are not part of the saved history.
+    pub async fn find_revisions(
+        db: &DatabaseConnection,
+        parent_id: u64,
+        page: u64,
+        page_size: u64,
+    ) -> Result<(Vec<Model>, u64), DbErr> {
+        let query = Self::find()
+            .filter(Column::PostType.eq("revision"))
+            .filter(Column::PostParent.eq(parent_id))
+            .filter(Column::PostName.not_like("%-autosave-v%"));
+
+        let total = query.clone().count(db).await?;
+
+        let revisions = query
+            .order_by_with_nulls(Column::PostDate, Order::Desc, NullOrdering::Last)
+            .order_by(Column::Id, Order::Desc)
+            .paginate(db, page_size)
+            .fetch_page(page - 1)
+            .await?;
+
+        Ok((revisions, total))
+    }
+
+    pub async fn find_previous_revision(
+        db: &DatabaseConnection,
+        revision: &Model,
+    ) -> Result<Option<Model>, DbErr> {
+        Self::find()
+            .filter(Column::PostType.eq("revision"))
+            .filter(Column::PostParent.eq(revision.post_parent))
+            .filter(Column::PostName.not_like("%-autosave-v%"))
+            .filter(Column::Id.lt(revision.id))
+            .order_by(Column::Id, Order::Desc)
+            .one(db)
+            .await
+    }
+
+    pub async fn find_page_summaries(
+        db: &DatabaseConnection,
+        post_type: &str,
+    ) -> Result<Vec<PageSummary>, DbErr> {
+        Self::find()
+            .select_only()
+            .column_as(Column::Id, "id")
+            .column(Column::PostTitle)
+            .column(Column::PostName)
+            .column(Column::PostParent)
+            .column(Column::MenuOrder)
+            .filter(Column::PostType.eq(post_type))
+            .filter(Self::published())
+            .order_by(Column::MenuOrder, Order::Asc)
+            .order_by(Column::PostTitle, Order::Asc)
+            .into_model::<PageSummary>()
+            .all(db)
+            .await
+    }
+
+    pub async fn find_summaries_by_ids(
+        db: &DatabaseConnection,
+        ids: Vec<u64>,
+    ) -> Result<Vec<PageSummary>, DbErr> {
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        Self::find()
+            .select_only()
+            .column_as(Column::Id, "id")
+            .column(Column::PostTitle)
+            .column(Column::PostName)
+            .column(Column::PostParent)
+            .column(Column::MenuOrder)
+            .filter(Column::Id.is_in(ids))
+            .into_model::<PageSummary>()
+            .all(db)
+            .await
+    }
+
+    pub async fn find_published_by_slugs(
+        db: &DatabaseConnection,
+        post_type: &str,
+        slugs: Vec<String>,
+    ) -> Result<Vec<Model>, DbErr> {
+        Self::find()
+            .filter(Column::PostType.eq(post_type))
+            .filter(Self::published())
+            .filter(Column::PostName.is_in(slugs))
+            .order_by(Column::Id, Order::Asc)
+            .all(db)
+            .await
+    }
+
+    /// ACF field definitions. Disabled fields have the `acf-disabled` status.
+    pub async fn find_acf_fields(db: &DatabaseConnection) -> Result<Vec<Model>, DbErr> {
+        Self::find()
+            .filter(Column::PostType.eq("acf-field"))
+            .filter(Column::PostStatus.eq("publish"))
+            .all(db)
+            .await
+    }
+
+    pub async fn find_menu_items(
+        db: &DatabaseConnection,
+        term_taxonomy_id: u64,
+    ) -> Result<Vec<Model>, DbErr> {
+        Self::find()
+            .filter(Column::PostType.eq("nav_menu_item"))
+            .filter(Self::published())
+            .filter(
+                Column::Id.in_subquery(
+                    Query::select()
+                        .column(super::term_relationship::Column::ObjectId)
+                        .from(super::term_relationship::Entity)
+                        .and_where(
+                            super::term_relationship::Column::TermTaxonomyId.eq(term_taxonomy_id),
+                        )
+                        .to_owned(),
+                ),
+            )
+            .order_by(Column::MenuOrder, Order::Asc)
+            .order_by(Column::Id, Order::Asc)
+            .all(db)
+            .await
     }
 
     pub async fn get_post_types(db: &DatabaseConnection) -> Result<Vec<(String, i64, i64)>, DbErr> {
@@ -157,70 +750,148 @@ impl Entity {
 
             let published_count = Self::find()
                 .filter(Column::PostType.eq(&post_type))
-                .filter(Column::PostStatus.eq("publish"))
+                .filter(Self::published())
                 .count(db)
                 .await?;
 
             result.push((post_type, total_count as i64, published_count as i64));
         }
 
-        result.sort_by(|a, b| b.1.cmp(&a.1));
+        result.sort_by_key(|entry| std::cmp::Reverse(entry.1));
 
         Ok(result)
     }
 
+    pub async fn count_published_by_authors(
+        db: &DatabaseConnection,
+        author_ids: Vec<u64>,
+    ) -> Result<HashMap<u64, i64>, DbErr> {
+        if author_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let counts = Self::find()
+            .select_only()
+            .column(Column::PostAuthor)
+            .column_as(Column::Id.count(), "post_count")
+            .filter(Column::PostAuthor.is_in(author_ids))
+            .filter(Column::PostType.eq("post"))
+            .filter(Self::published())
+            .group_by(Column::PostAuthor)
+            .into_tuple::<(u64, i64)>()
+            .all(db)
+            .await?;
+
+        Ok(counts.into_iter().collect())
+    }
+
     pub async fn find_by_category(
         db: &DatabaseConnection,
         category_id: i32,
+        include_children: bool,
+        page: u64,
+        page_size: u64,
+        search: Option<String>,
+        lang: Option<&LanguageFilter>,
+    ) -> Result<(Vec<Model>, u64), DbErr> {
+        if !include_children {
+            return Self::find_by_term(
+                db,
+                "category",
+                category_id as u64,
+                page,
+                page_size,
+                search,
+                lang,
+            )
+            .await;
+        }
+
+        let term_taxonomy_ids =
+            super::term_taxonomy::Entity::find_descendant_ids(db, "category", category_id as u64)
+                .await?;
+
+        Self::find_by_term_taxonomies(db, term_taxonomy_ids, page, page_size, search, lang).await
+    }
+
+    pub async fn find_by_term(
+        db: &DatabaseConnection,
+        taxonomy: &str,
+        term_id: u64,
         page: u64,
         page_size: u64,
         search: Option<String>,
+        lang: Option<&LanguageFilter>,
     ) -> Result<(Vec<Model>, u64), DbErr> {
         let term_taxonomy_id = super::term_taxonomy::Entity::find()
-            .filter(super::term_taxonomy::Column::TermId.eq(category_id))
-            .filter(super::term_taxonomy::Column::Taxonomy.eq("category"))
+            .filter(super::term_taxonomy::Column::TermId.eq(term_id))
+            .filter(super::term_taxonomy::Column::Taxonomy.eq(taxonomy))
             .select()
             .column(super::term_taxonomy::Column::TermTaxonomyId)
             .into_tuple::<u64>()
             .one(db)
             .await?;
 
-        if let Some(term_taxonomy_id) = term_taxonomy_id {
-            let post_ids = super::term_relationship::Entity::find()
-                .filter(super::term_relationship::Column::TermTaxonomyId.eq(term_taxonomy_id))
-                .select()
-                .column(super::term_relationship::Column::ObjectId)
-                .into_tuple::<u64>()
-                .all(db)
-                .await?;
-
-            if post_ids.is_empty() {
-                return Ok((Vec::new(), 0));
+        match term_taxonomy_id {
+            Some(term_taxonomy_id) => {
+                Self::find_by_term_taxonomies(
+                    db,
+                    vec![term_taxonomy_id],
+                    page,
+                    page_size,
+                    search,
+                    lang,
+                )
+                .await
             }
+            None => Ok((Vec::new(), 0)),
+        }
+    }
 
-            let mut query = Self::find()
-                .filter(Column::Id.is_in(post_ids))
-                .filter(Column::PostStatus.eq("publish"));
+    pub async fn find_by_term_taxonomies(
+        db: &DatabaseConnection,
+        term_taxonomy_ids: Vec<u64>,
+        page: u64,
+        page_size: u64,
+        search: Option<String>,
+        lang: Option<&LanguageFilter>,
+    ) -> Result<(Vec<Model>, u64), DbErr> {
+        if term_taxonomy_ids.is_empty() {
+            return Ok((Vec::new(), 0));
+        }
 
-            if let Some(search_term) = search {
-                query = query.filter(
-                    Condition::any()
-                        .add(Column::PostTitle.contains(&search_term))
-                        .add(Column::PostContent.contains(&search_term)),
-                );
-            }
+        let post_ids = super::term_relationship::Entity::find()
+            .filter(super::term_relationship::Column::TermTaxonomyId.is_in(term_taxonomy_ids))
+            .select()
+            .column(super::term_relationship::Column::ObjectId)
+            .into_tuple::<u64>()
+            .all(db)
+            .await?;
 
-            let total = query.clone().count(db).await?;
+        if post_ids.is_empty() {
+            return Ok((Vec::new(), 0));
+        }
 
-            let posts = query
-                .order_by_with_nulls(Column::PostDate, Order::Desc, NullOrdering::Last)
-                .paginate(db, page_size)
-                .fetch_page(page - 1)
-                .await?;
+        let mut query = Self::find()
+            .filter(Column::Id.is_in(post_ids))
+            .filter(Self::published());
 
-            Ok((posts, total))
-        } else {
-            Ok((Vec::new(), 0))
+        if let Some(lang) = lang {
+            query = query.filter(lang.posts());
+        }
+
+        if let Some(search_term) = search {
+            query = query.filter(Self::search(&search_term));
         }
+
+        let total = query.clone().count(db).await?;
+
+        let posts = query
+            .order_by_with_nulls(Column::PostDate, Order::Desc, NullOrdering::Last)
+            .paginate(db, page_size)
+            .fetch_page(page - 1)
+            .await?;
+
+        Ok((posts, total))
     }
 }
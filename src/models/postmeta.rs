@@ -1,8 +1,19 @@
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::db::tables;
+
+#[derive(Copy, Clone, Default, Debug, DeriveEntity)]
+pub struct Entity;
+
+impl EntityName for Entity {
+    fn table_name(&self) -> &str {
+        tables::site_table("postmeta")
+    }
+}
 
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
-#[sea_orm(table_name = "wp_postmeta")]
 pub struct Model {
     #[sea_orm(primary_key)]
     pub meta_id: i32,
@@ -43,7 +54,7 @@ impl Entity {
     pub async fn find_metadata_map(
         db: &DatabaseConnection,
         post_id: u64,
-    ) -> Result<std::collections::HashMap<String, String>, DbErr> {
+    ) -> Result<HashMap<String, String>, DbErr> {
         let metadata = Self::find_by_post_id(db, post_id).await?;
         let map = metadata
             .into_iter()
@@ -52,4 +63,49 @@ impl Entity {
 
         Ok(map)
     }
+
+    pub async fn find_all_metadata_for_posts(
+        db: &DatabaseConnection,
+        post_ids: Vec<u64>,
+    ) -> Result<HashMap<u64, HashMap<String, String>>, DbErr> {
+        if post_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let metadata = Self::find()
+            .filter(Column::PostId.is_in(post_ids))
+            .all(db)
+            .await?;
+
+        Ok(Self::group_by_post(metadata))
+    }
+
+    pub async fn find_metadata_for_posts(
+        db: &DatabaseConnection,
+        post_ids: Vec<u64>,
+        meta_keys: &[&str],
+    ) -> Result<HashMap<u64, HashMap<String, String>>, DbErr> {
+        if post_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let metadata = Self::find()
+            .filter(Column::PostId.is_in(post_ids))
+            .filter(Column::MetaKey.is_in(meta_keys.iter().copied()))
+            .all(db)
+            .await?;
+
+        Ok(Self::group_by_post(metadata))
+    }
+
+    fn group_by_post(metadata: Vec<Model>) -> HashMap<u64, HashMap<String, String>> {
+        let mut map: HashMap<u64, HashMap<String, String>> = HashMap::new();
+        for meta in metadata {
+            map.entry(meta.post_id)
+                .or_default()
+                .insert(meta.meta_key, meta.meta_value);
+        }
+
+        map
+    }
 }
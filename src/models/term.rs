@@ -1,8 +1,18 @@
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
 
+use crate::db::tables;
+
+#[derive(Copy, Clone, Default, Debug, DeriveEntity)]
+pub struct Entity;
+
+impl EntityName for Entity {
+    fn table_name(&self) -> &str {
+        tables::site_table("terms")
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
-#[sea_orm(table_name = "wp_terms")]
 pub struct Model {
     #[sea_orm(primary_key)]
     pub term_id: u64,
@@ -23,4 +33,14 @@ impl Related<super::term_taxonomy::Entity> for Entity {
     }
 }
 
-impl ActiveModelBehavior for ActiveModel {}
\ No newline at end of file
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Entity {
+    pub async fn find_by_ids(db: &DatabaseConnection, ids: Vec<u64>) -> Result<Vec<Model>, DbErr> {
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        Self::find().filter(Column::TermId.is_in(ids)).all(db).await
+    }
+}
@@ -1,8 +1,20 @@
 use sea_orm::entity::prelude::*;
+use sea_orm::{FromQueryResult, JoinType, Order, QueryOrder, QuerySelect};
 use serde::{Deserialize, Serialize};
 
+use super::language::LanguageFilter;
+use crate::db::tables;
+
+#[derive(Copy, Clone, Default, Debug, DeriveEntity)]
+pub struct Entity;
+
+impl EntityName for Entity {
+    fn table_name(&self) -> &str {
+        tables::site_table("term_relationships")
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
-#[sea_orm(table_name = "wp_term_relationships")]
 pub struct Model {
     #[sea_orm(primary_key, column_name = "object_id")]
     pub object_id: u64,
@@ -39,4 +51,84 @@ impl Related<super::term_taxonomy::Entity> for Entity {
     }
 }
 
-impl ActiveModelBehavior for ActiveModel {}
\ No newline at end of file
+impl ActiveModelBehavior for ActiveModel {}
+
+#[derive(Clone, Debug, FromQueryResult)]
+pub struct ObjectTerm {
+    pub object_id: u64,
+    pub term_taxonomy_id: u64,
+    pub term_id: u64,
+    pub taxonomy: String,
+    pub name: String,
+    pub slug: String,
+    pub description: String,
+    pub parent: u64,
+    pub count: i32,
+}
+
+impl Entity {
+    /// `(term_taxonomy_id, object_id)` pairs of the published posts in a
+    /// taxonomy.
+    pub async fn find_published_objects(
+        db: &DatabaseConnection,
+        taxonomy: &str,
+        lang: Option<&LanguageFilter>,
+    ) -> Result<Vec<(u64, u64)>, DbErr> {
+        let mut query = Self::find()
+            .select_only()
+            .column(Column::TermTaxonomyId)
+            .column(Column::ObjectId)
+            .join(JoinType::InnerJoin, Relation::TermTaxonomy.def())
+            .join(JoinType::InnerJoin, Relation::Post.def())
+            .filter(super::term_taxonomy::Column::Taxonomy.eq(taxonomy))
+            .filter(super::post::Entity::published());
+
+        if let Some(lang) = lang {
+            query = query.filter(lang.posts());
+        }
+
+        query.into_tuple::<(u64, u64)>().all(db).await
+    }
+
+    pub async fn find_terms_for_objects(
+        db: &DatabaseConnection,
+        object_ids: Vec<u64>,
+        taxonomy_prefix: Option<&str>,
+    ) -> Result<Vec<ObjectTerm>, DbErr> {
+        if object_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut query = Self::find()
+            .select_only()
+            .column(Column::ObjectId)
+            .column(super::term_taxonomy::Column::TermTaxonomyId)
+            .column(super::term_taxonomy::Column::TermId)
+            .column(super::term_taxonomy::Column::Taxonomy)
+            .column(super::term::Column::Name)
+            .column(super::term::Column::Slug)
+            .column(super::term_taxonomy::Column::Description)
+            .column(super::term_taxonomy::Column::Parent)
+            .column(super::term_taxonomy::Column::Count)
+            .join(JoinType::InnerJoin, Relation::TermTaxonomy.def())
+            .join(
+                JoinType::InnerJoin,
+                super::term_taxonomy::Relation::Term.def(),
+            )
+            .filter(Column::ObjectId.is_in(object_ids));
+
+        if let Some(prefix) = taxonomy_prefix {
+            query = query.filter(
+                super::term_taxonomy::Column::Taxonomy.starts_with(super::post::esc_like(prefix)),
+            );
+        }
+
+        query
+            .order_by(Column::ObjectId, Order::Asc)
+            .order_by(Column::TermOrder, Order::Asc)
+            .order_by(super::term::Column::Name, Order::Asc)
+            .into_model::<ObjectTerm>()
+            .all(db)
+            .await
+    }
+}
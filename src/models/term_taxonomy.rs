@@ -1,8 +1,22 @@
 use sea_orm::entity::prelude::*;
+use sea_orm::sea_query::{Alias, Expr};
+use sea_orm::{Condition, JoinType, Order, QueryOrder, QuerySelect};
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+use super::language::LanguageFilter;
+use crate::db::tables;
+
+#[derive(Copy, Clone, Default, Debug, DeriveEntity)]
+pub struct Entity;
+
+impl EntityName for Entity {
+    fn table_name(&self) -> &str {
+        tables::site_table("term_taxonomy")
+    }
+}
 
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
-#[sea_orm(table_name = "wp_term_taxonomy")]
 pub struct Model {
     #[sea_orm(primary_key)]
     pub term_taxonomy_id: u64,
@@ -40,39 +54,166 @@ impl Related<super::term_relationship::Entity> for Entity {
 impl ActiveModelBehavior for ActiveModel {}
 
 impl Entity {
-    pub async fn find_categories(
+    pub async fn find_taxonomies(
         db: &DatabaseConnection,
+    ) -> Result<Vec<(String, i64, i64)>, DbErr> {
+        Self::find()
+            .select_only()
+            .column(Column::Taxonomy)
+            .column_as(Column::TermTaxonomyId.count(), "term_count")
+            .column_as(
+                Expr::expr(Column::Count.into_expr().sum()).cast_as(Alias::new("SIGNED")),
+                "object_count",
+            )
+            .group_by(Column::Taxonomy)
+            .order_by(Column::Taxonomy, Order::Asc)
+            .into_tuple::<(String, i64, i64)>()
+            .all(db)
+            .await
+    }
+
+    pub async fn find_terms(
+        db: &DatabaseConnection,
+        taxonomy: &str,
         page: u64,
         page_size: u64,
+        search: Option<String>,
+        lang: Option<&LanguageFilter>,
     ) -> Result<(Vec<(Model, super::term::Model)>, u64), DbErr> {
-        let taxonomies = Self::find()
-            .filter(Column::Taxonomy.eq("category"))
+        let mut query = Self::find()
+            .filter(Column::Taxonomy.eq(taxonomy))
+            .find_also_related(super::term::Entity)
+            .filter(super::term::Column::TermId.is_not_null());
+
+        if let Some(lang) = lang {
+            query = query.filter(lang.terms());
+        }
+
+        if let Some(search_term) = search {
+            query = query.filter(
+                Condition::any()
+                    .add(super::term::Column::Name.contains(&search_term))
+                    .add(super::term::Column::Slug.contains(&search_term)),
+            );
+        }
+
+        let total = query.clone().count(db).await?;
+
+        let results = query
+            .order_by(Column::TermTaxonomyId, Order::Asc)
+            .paginate(db, page_size)
+            .fetch_page(page - 1)
+            .await?
+            .into_iter()
+            .filter_map(|(taxonomy, term)| term.map(|term| (taxonomy, term)))
+            .collect();
+
+        Ok((results, total))
+    }
+
+    pub async fn find_term_by_slug(
+        db: &DatabaseConnection,
+        taxonomy: &str,
+        slug: &str,
+    ) -> Result<Option<(Model, super::term::Model)>, DbErr> {
+        let result = Self::find()
+            .filter(Column::Taxonomy.eq(taxonomy))
+            .find_also_related(super::term::Entity)
+            .filter(super::term::Column::Slug.eq(slug))
+            .one(db)
+            .await?;
+
+        Ok(result.and_then(|(taxonomy, term)| term.map(|term| (taxonomy, term))))
+    }
+
+    pub async fn find_all_terms(
+        db: &DatabaseConnection,
+        taxonomy: &str,
+        lang: Option<&LanguageFilter>,
+    ) -> Result<Vec<(Model, super::term::Model)>, DbErr> {
+        let mut query = Self::find().filter(Column::Taxonomy.eq(taxonomy));
+
+        if let Some(lang) = lang {
+            query = query.filter(lang.terms());
+        }
+
+        let results = query
+            .find_also_related(super::term::Entity)
+            .order_by(super::term::Column::Name, Order::Asc)
             .all(db)
             .await?;
 
-        let mut results = Vec::new();
-        for taxonomy in taxonomies {
-            if let Some(term) = super::term::Entity::find_by_id(taxonomy.term_id)
-                .one(db)
-                .await?
-            {
-                results.push((taxonomy, term));
-            }
+        Ok(results
+            .into_iter()
+            .filter_map(|(taxonomy, term)| term.map(|term| (taxonomy, term)))
+            .collect())
+    }
+
+    /// Term taxonomy IDs of a term and all its descendants.
+    pub async fn find_descendant_ids(
+        db: &DatabaseConnection,
+        taxonomy: &str,
+        term_id: u64,
+    ) -> Result<Vec<u64>, DbErr> {
+        let terms = Self::find()
+            .select_only()
+            .column(Column::TermTaxonomyId)
+            .column(Column::TermId)
+            .column(Column::Parent)
+            .filter(Column::Taxonomy.eq(taxonomy))
+            .into_tuple::<(u64, u64, u64)>()
+            .all(db)
+            .await?;
+
+        let mut children: HashMap<u64, Vec<u64>> = HashMap::new();
+        let mut term_taxonomy_ids: HashMap<u64, u64> = HashMap::new();
+        for (term_taxonomy_id, term_id, parent) in terms {
+            children.entry(parent).or_default().push(term_id);
+            term_taxonomy_ids.insert(term_id, term_taxonomy_id);
         }
 
-        let total = results.len() as u64;
-        let start = (page - 1) * page_size;
-        let end = std::cmp::min(start + page_size, total);
-
-        if start < total {
-            let paginated_results = results
-                .into_iter()
-                .skip(start as usize)
-                .take((end - start) as usize)
-                .collect();
-            Ok((paginated_results, total))
-        } else {
-            Ok((Vec::new(), total))
+        let mut result = Vec::new();
+        let mut visited = HashSet::new();
+        let mut pending = vec![term_id];
+
+        while let Some(term_id) = pending.pop() {
+            if !visited.insert(term_id) {
+                continue;
+            }
+            if let Some(term_taxonomy_id) = term_taxonomy_ids.get(&term_id) {
+                result.push(*term_taxonomy_id);
+                pending.extend(children.get(&term_id).into_iter().flatten());
+            }
         }
+
+        Ok(result)
+    }
+
+    pub async fn find_categories(
+        db: &DatabaseConnection,
+        page: u64,
+        page_size: u64,
+        lang: Option<&LanguageFilter>,
+    ) -> Result<(Vec<(Model, super::term::Model)>, u64), DbErr> {
+        Self::find_terms(db, "category", page, page_size, None, lang).await
+    }
+
+    /// Description of the term of a taxonomy that an object belongs to.
+    /// Polylang keeps its translation groups (`post_translations`) there as
+    /// a serialized `language => post ID` array.
+    pub async fn find_object_description(
+        db: &DatabaseConnection,
+        taxonomy: &str,
+        object_id: u64,
+    ) -> Result<Option<String>, DbErr> {
+        Self::find()
+            .select_only()
+            .column(Column::Description)
+            .join(JoinType::InnerJoin, Relation::TermRelationships.def())
+            .filter(Column::Taxonomy.eq(taxonomy))
+            .filter(super::term_relationship::Column::ObjectId.eq(object_id))
+            .into_tuple::<String>()
+            .one(db)
+            .await
     }
 }
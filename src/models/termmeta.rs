@@ -0,0 +1,68 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::db::tables;
+
+#[derive(Copy, Clone, Default, Debug, DeriveEntity)]
+pub struct Entity;
+
+impl EntityName for Entity {
+    fn table_name(&self) -> &str {
+        tables::site_table("termmeta")
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub meta_id: u64,
+    pub term_id: u64,
+    pub meta_key: String,
+    pub meta_value: String,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::term::Entity",
+        from = "Column::TermId",
+        to = "super::term::Column::TermId"
+    )]
+    Term,
+}
+
+impl Related<super::term::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Term.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Entity {
+    pub async fn find_metadata_for_terms(
+        db: &DatabaseConnection,
+        term_ids: Vec<u64>,
+        meta_keys: &[&str],
+    ) -> Result<HashMap<u64, HashMap<String, String>>, DbErr> {
+        if term_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let metadata = Self::find()
+            .filter(Column::TermId.is_in(term_ids))
+            .filter(Column::MetaKey.is_in(meta_keys.iter().copied()))
+            .all(db)
+            .await?;
+
+        let mut map: HashMap<u64, HashMap<String, String>> = HashMap::new();
+        for meta in metadata {
+            map.entry(meta.term_id)
+                .or_default()
+                .insert(meta.meta_key, meta.meta_value);
+        }
+
+        Ok(map)
+    }
+}
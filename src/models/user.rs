@@ -0,0 +1,180 @@
+use sea_orm::entity::prelude::*;
+use sea_orm::sea_query::{Expr, Query};
+use sea_orm::{Condition, Order, QueryOrder, QuerySelect};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::db::tables;
+
+#[derive(Copy, Clone, Default, Debug, DeriveEntity)]
+pub struct Entity;
+
+impl EntityName for Entity {
+    fn table_name(&self) -> &str {
+        tables::global_table("users")
+    }
+}
+
+// Only the public profile columns are mapped; `user_pass`, `user_email` and
+// `user_activation_key` are deliberately never selected.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+pub struct Model {
+    #[sea_orm(primary_key, column_name = "ID")]
+    pub id: u64,
+    pub user_nicename: String,
+    pub user_url: String,
+    pub display_name: String,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(has_many = "super::usermeta::Entity")]
+    Usermeta,
+}
+
+impl Related<super::usermeta::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Usermeta.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+#[derive(Clone, Debug)]
+pub struct AuthorProfile {
+    pub user: Model,
+    pub description: String,
+    pub gravatar_hash: String,
+    pub post_count: i64,
+}
+
+impl Entity {
+    // Like `count_user_posts()`, only posts of the `post` type count, so
+    // users who only wrote pages or attachments are not listed.
+    fn published_authors() -> Condition {
+        Condition::all().add(
+            Column::Id.in_subquery(
+                Query::select()
+                    .column(super::post::Column::PostAuthor)
+                    .from(super::post::Entity)
+                    .cond_where(super::post::Entity::published())
+                    .and_where(super::post::Column::PostType.eq("post"))
+                    .to_owned(),
+            ),
+        )
+    }
+
+    pub async fn find_author_by_id(
+        db: &DatabaseConnection,
+        id: u64,
+    ) -> Result<Option<Model>, DbErr> {
+        Self::find()
+            .filter(Column::Id.eq(id))
+            .filter(Self::published_authors())
+            .one(db)
+            .await
+    }
+
+    pub async fn find_author_by_nicename(
+        db: &DatabaseConnection,
+        nicenames: Vec<String>,
+    ) -> Result<Option<Model>, DbErr> {
+        Self::find()
+            .filter(Column::UserNicename.is_in(nicenames))
+            .filter(Self::published_authors())
+            .one(db)
+            .await
+    }
+
+    pub async fn find_nicenames(
+        db: &DatabaseConnection,
+        user_ids: Vec<u64>,
+    ) -> Result<HashMap<u64, String>, DbErr> {
+        if user_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let nicenames = Self::find()
+            .select_only()
+            .column(Column::Id)
+            .column(Column::UserNicename)
+            .filter(Column::Id.is_in(user_ids))
+            .into_tuple::<(u64, String)>()
+            .all(db)
+            .await?;
+
+        Ok(nicenames.into_iter().collect())
+    }
+
+    pub async fn find_authors(
+        db: &DatabaseConnection,
+        page: u64,
+        page_size: u64,
+        search: Option<String>,
+    ) -> Result<(Vec<Model>, u64), DbErr> {
+        let mut query = Self::find().filter(Self::published_authors());
+
+        if let Some(search_term) = search {
+            query = query.filter(
+                Condition::any()
+                    .add(Column::DisplayName.contains(&search_term))
+                    .add(Column::UserNicename.contains(&search_term)),
+            );
+        }
+
+        let total = query.clone().count(db).await?;
+
+        let users = query
+            .order_by(Column::DisplayName, Order::Asc)
+            .paginate(db, page_size)
+            .fetch_page(page - 1)
+            .await?;
+
+        Ok((users, total))
+    }
+
+    pub async fn find_gravatar_hashes(
+        db: &DatabaseConnection,
+        user_ids: Vec<u64>,
+    ) -> Result<HashMap<u64, String>, DbErr> {
+        if user_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let hashes = Self::find()
+            .select_only()
+            .column(Column::Id)
+            .column_as(
+                Expr::cust("MD5(LOWER(TRIM(`user_email`)))"),
+                "gravatar_hash",
+            )
+            .filter(Column::Id.is_in(user_ids))
+            .into_tuple::<(u64, String)>()
+            .all(db)
+            .await?;
+
+        Ok(hashes.into_iter().collect())
+    }
+
+    pub async fn load_profiles(
+        db: &DatabaseConnection,
+        users: Vec<Model>,
+    ) -> Result<Vec<AuthorProfile>, DbErr> {
+        let user_ids: Vec<u64> = users.iter().map(|user| user.id).collect();
+
+        let mut descriptions =
+            super::usermeta::Entity::find_values(db, user_ids.clone(), "description").await?;
+        let mut hashes = Self::find_gravatar_hashes(db, user_ids.clone()).await?;
+        let counts = super::post::Entity::count_published_by_authors(db, user_ids).await?;
+
+        Ok(users
+            .into_iter()
+            .map(|user| AuthorProfile {
+                description: descriptions.remove(&user.id).unwrap_or_default(),
+                gravatar_hash: hashes.remove(&user.id).unwrap_or_default(),
+                post_count: counts.get(&user.id).copied().unwrap_or(0),
+                user,
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,64 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::db::tables;
+
+#[derive(Copy, Clone, Default, Debug, DeriveEntity)]
+pub struct Entity;
+
+impl EntityName for Entity {
+    fn table_name(&self) -> &str {
+        tables::global_table("usermeta")
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+pub struct Model {
+    #[sea_orm(primary_key, column_name = "umeta_id")]
+    pub umeta_id: u64,
+    pub user_id: u64,
+    pub meta_key: String,
+    pub meta_value: String,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::UserId",
+        to = "super::user::Column::Id"
+    )]
+    User,
+}
+
+impl Related<super::user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Entity {
+    pub async fn find_values(
+        db: &DatabaseConnection,
+        user_ids: Vec<u64>,
+        meta_key: &str,
+    ) -> Result<HashMap<u64, String>, DbErr> {
+        if user_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let metadata = Self::find()
+            .filter(Column::UserId.is_in(user_ids))
+            .filter(Column::MetaKey.eq(meta_key))
+            .all(db)
+            .await?;
+
+        Ok(metadata
+            .into_iter()
+            .map(|meta| (meta.user_id, meta.meta_value))
+            .collect())
+    }
+}
@@ -0,0 +1,415 @@
+use serde_json::{Number, Value as Json};
+use std::collections::{HashMap, HashSet};
+
+use crate::utils::php;
+
+// Field types whose values are IDs of other posts.
+const POST_FIELD_TYPES: [&str; 3] = ["relationship", "post_object", "page_link"];
+
+// Sub fields nested deeper than this are almost certainly a definition whose
+// parent points back at itself or one of its sub fields.
+const MAX_DEPTH: usize = 16;
+
+/// A field definition, read from an `acf-field` post. Its settings are the
+/// serialized array ACF keeps in `post_content`.
+pub struct Definition {
+    pub id: u64,
+    /// The field key (`field_5f1a...`), stored as the post's slug.
+    pub key: String,
+    /// The field name, stored as the post's excerpt.
+    pub name: String,
+    /// The field group, or the repeater, group or flexible content field the
+    /// field belongs to.
+    pub parent: u64,
+    pub menu_order: i32,
+    pub settings: Json,
+}
+
+/// The site's ACF field definitions, used to decode the values ACF stores in
+/// post meta.
+pub struct Fields {
+    definitions: Vec<Definition>,
+    by_key: HashMap<String, usize>,
+    children: HashMap<u64, Vec<usize>>,
+}
+
+/// A decoded field value. Attachments and posts are kept as IDs until they
+/// have been loaded.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    Number(Number),
+    String(String),
+    List(Vec<Value>),
+    /// Sub fields of a repeater row, flexible content layout or group, in
+    /// field order.
+    Object(Vec<(String, Value)>),
+    Media(u64),
+    Post(u64),
+}
+
+impl Fields {
+    pub fn new(mut definitions: Vec<Definition>) -> Self {
+        definitions.sort_by_key(|definition| (definition.parent, definition.menu_order));
+
+        let by_key = definitions
+            .iter()
+            .enumerate()
+            .map(|(index, definition)| (definition.key.clone(), index))
+            .collect();
+
+        let mut children: HashMap<u64, Vec<usize>> = HashMap::new();
+        for (index, definition) in definitions.iter().enumerate() {
+            children.entry(definition.parent).or_default().push(index);
+        }
+
+        Fields {
+            definitions,
+            by_key,
+            children,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.definitions.is_empty()
+    }
+
+    /// Decodes the fields of a post from its meta. ACF stores every value
+    /// under the field name and the field key under the name prefixed with
+    /// an underscore; sub fields are stored under names built from their
+    /// parent's (`gallery_0_caption`) and are decoded with it.
+    pub fn values(&self, meta: &HashMap<String, String>) -> Vec<(String, Value)> {
+        let field_ids: HashSet<u64> = self.definitions.iter().map(|field| field.id).collect();
+
+        let mut fields: Vec<&Definition> = meta
+            .iter()
+            .filter_map(|(meta_key, field_key)| {
+                let name = meta_key.strip_prefix('_')?;
+                let field = &self.definitions[*self.by_key.get(field_key)?];
+                let top_level = !field_ids.contains(&field.parent);
+                (top_level && field.name == name).then_some(field)
+            })
+            .collect();
+        fields.sort_by_key(|field| (field.parent, field.menu_order));
+
+        fields
+            .into_iter()
+            .map(|field| (field.name.clone(), self.decode(field, &field.name, meta, 0)))
+            .collect()
+    }
+
+    fn decode(
+        &self,
+        field: &Definition,
+        meta_key: &str,
+        meta: &HashMap<String, String>,
+        depth: usize,
+    ) -> Value {
+        if depth > MAX_DEPTH {
+            return Value::Null;
+        }
+
+        let field_type = field.settings["type"].as_str().unwrap_or_default();
+
+        match field_type {
+            "repeater" => {
+                // The stored row count is only trusted as far as rows were
+                // actually saved.
+                let rows = meta
+                    .get(meta_key)
+                    .and_then(|count| count.trim().parse::<usize>().ok())
+                    .unwrap_or(0)
+                    .min(Self::saved_rows(meta_key, meta));
+
+                Value::List(
+                    (0..rows)
+                        .map(|row| {
+                            let prefix = format!("{}_{}", meta_key, row);
+                            Value::Object(self.decode_children(field, None, &prefix, meta, depth))
+                        })
+                        .collect(),
+                )
+            }
+            "flexible_content" => {
+                // The field itself stores the layout name of every row.
+                let layouts = match meta.get(meta_key).and_then(|value| php::unserialize(value)) {
+                    Some(Json::Array(layouts)) => layouts,
+                    _ => Vec::new(),
+                };
+
+                Value::List(
+                    layouts
+                        .iter()
+                        .enumerate()
+                        .filter_map(|(row, layout)| {
+                            let layout = layout.as_str()?;
+                            let layout_key = Self::layout_key(field, layout);
+                            let prefix = format!("{}_{}", meta_key, row);
+
+                            let mut values =
+                                vec![("acf_fc_layout".to_string(), Value::String(layout.into()))];
+                            values.extend(self.decode_children(
+                                field,
+                                Some(layout_key.as_deref().unwrap_or_default()),
+                                &prefix,
+                                meta,
+                                depth,
+                            ));
+                            Some(Value::Object(values))
+                        })
+                        .collect(),
+                )
+            }
+            "group" => Value::Object(self.decode_children(field, None, meta_key, meta, depth)),
+            _ => match meta.get(meta_key) {
+                Some(raw) => Self::decode_scalar(field_type, raw),
+                None => Value::Null,
+            },
+        }
+    }
+
+    fn decode_children(
+        &self,
+        field: &Definition,
+        layout_key: Option<&str>,
+        prefix: &str,
+        meta: &HashMap<String, String>,
+        depth: usize,
+    ) -> Vec<(String, Value)> {
+        self.children
+            .get(&field.id)
+            .into_iter()
+            .flatten()
+            .map(|index| &self.definitions[*index])
+            .filter(|child| {
+                layout_key.is_none_or(|layout_key| {
+                    child.settings["parent_layout"].as_str() == Some(layout_key)
+                })
+            })
+            .map(|child| {
+                let meta_key = format!("{}_{}", prefix, child.name);
+                (
+                    child.name.clone(),
+                    self.decode(child, &meta_key, meta, depth + 1),
+                )
+            })
+            .collect()
+    }
+
+    // One more than the highest row index among the `{meta_key}_{row}_*`
+    // sub field values.
+    fn saved_rows(meta_key: &str, meta: &HashMap<String, String>) -> usize {
+        let prefix = format!("{}_", meta_key);
+
+        meta.keys()
+            .filter_map(|key| {
+                let (row, _) = key.strip_prefix(&prefix)?.split_once('_')?;
+                row.parse::<usize>().ok()
+            })
+            .max()
+            .map_or(0, |row| row + 1)
+    }
+
+    // Layouts are keyed by their layout key since ACF 5 and listed in order
+    // before that; either way every layout carries its key and name.
+    fn layout_key(field: &Definition, name: &str) -> Option<String> {
+        let layouts: Vec<&Json> = match &field.settings["layouts"] {
+            Json::Object(layouts) => layouts.values().collect(),
+            Json::Array(layouts) => layouts.iter().collect(),
+            _ => Vec::new(),
+        };
+
+        layouts
+            .into_iter()
+            .find(|layout| layout["name"].as_str() == Some(name))
+            .and_then(|layout| layout["key"].as_str())
+            .map(str::to_string)
+    }
+
+    fn decode_scalar(field_type: &str, raw: &str) -> Value {
+        let raw = raw.trim();
+
+        match field_type {
+            "number" | "range" => Self::number(raw).map_or(Value::Null, Value::Number),
+            "true_false" => Value::Bool(raw == "1"),
+            "image" | "file" => Self::id(raw).map_or(Value::Null, Value::Media),
+            "gallery" => Value::List(Self::ids(raw).into_iter().map(Value::Media).collect()),
+            _ if POST_FIELD_TYPES.contains(&field_type) => {
+                // `page_link` also accepts archive URLs, which are kept as
+                // they are.
+                if php::is_serialized(raw) {
+                    Value::List(Self::ids(raw).into_iter().map(Value::Post).collect())
+                } else if let Some(id) = Self::id(raw) {
+                    Value::Post(id)
+                } else if raw.is_empty() {
+                    Value::Null
+                } else {
+                    Value::String(raw.to_string())
+                }
+            }
+            _ => Self::from_json(php::maybe_unserialize(raw)),
+        }
+    }
+
+    fn number(raw: &str) -> Option<Number> {
+        if let Ok(number) = raw.parse::<i64>() {
+            return Some(number.into());
+        }
+        raw.parse::<f64>().ok().and_then(Number::from_f64)
+    }
+
+    fn id(raw: &str) -> Option<u64> {
+        raw.parse::<u64>().ok().filter(|id| *id > 0)
+    }
+
+    fn ids(raw: &str) -> Vec<u64> {
+        let values = match php::unserialize(raw) {
+            Some(Json::Array(values)) => values,
+            Some(Json::Object(values)) => values.into_iter().map(|(_, value)| value).collect(),
+            _ => Vec::new(),
+        };
+
+        values
+            .iter()
+            .filter_map(|value| match value {
+                Json::Number(id) => id.as_u64(),
+                Json::String(id) => Self::id(id.trim()),
+                _ => None,
+            })
+            .filter(|id| *id > 0)
+            .collect()
+    }
+
+    fn from_json(value: Json) -> Value {
+        match value {
+            Json::Null => Value::Null,
+            Json::Bool(value) => Value::Bool(value),
+            Json::Number(value) => Value::Number(value),
+            Json::String(value) => Value::String(value),
+            Json::Array(values) => Value::List(values.into_iter().map(Self::from_json).collect()),
+            Json::Object(values) => Value::Object(
+                values
+                    .into_iter()
+                    .map(|(key, value)| (key, Self::from_json(value)))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+impl Value {
+    /// IDs of the attachments and of the posts the value refers to.
+    pub fn collect_ids(&self, media_ids: &mut Vec<u64>, post_ids: &mut Vec<u64>) {
+        match self {
+            Value::Media(id) => media_ids.push(*id),
+            Value::Post(id) => post_ids.push(*id),
+            Value::List(values) => {
+                for value in values {
+                    value.collect_ids(media_ids, post_ids);
+                }
+            }
+            Value::Object(values) => {
+                for (_, value) in values {
+                    value.collect_ids(media_ids, post_ids);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn definition(id: u64, name: &str, parent: u64, field_type: &str) -> Definition {
+        Definition {
+            id,
+            key: format!("field_{}", id),
+            name: name.to_string(),
+            parent,
+            menu_order: 0,
+            settings: json!({ "type": field_type }),
+        }
+    }
+
+    fn meta(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn decodes_repeater_rows() {
+        let fields = Fields::new(vec![
+            definition(10, "slides", 1, "repeater"),
+            definition(11, "image", 10, "image"),
+            definition(12, "caption", 10, "text"),
+        ]);
+        let meta = meta(&[
+            ("slides", "2"),
+            ("_slides", "field_10"),
+            ("slides_0_image", "5"),
+            ("slides_0_caption", "First"),
+            ("slides_1_image", ""),
+            ("slides_1_caption", "Second"),
+        ]);
+
+        assert_eq!(
+            fields.values(&meta),
+            vec![(
+                "slides".to_string(),
+                Value::List(vec![
+                    Value::Object(vec![
+                        ("image".to_string(), Value::Media(5)),
+                        ("caption".to_string(), Value::String("First".to_string())),
+                    ]),
+                    Value::Object(vec![
+                        ("image".to_string(), Value::Null),
+                        ("caption".to_string(), Value::String("Second".to_string())),
+                    ]),
+                ])
+            )]
+        );
+    }
+
+    #[test]
+    fn caps_repeater_rows_at_saved_rows() {
+        let fields = Fields::new(vec![
+            definition(10, "slides", 1, "repeater"),
+            definition(11, "caption", 10, "text"),
+        ]);
+        let meta = meta(&[
+            ("slides", "999999999"),
+            ("_slides", "field_10"),
+            ("slides_0_caption", "Only"),
+        ]);
+
+        match &fields.values(&meta)[0].1 {
+            Value::List(rows) => assert_eq!(rows.len(), 1),
+            value => panic!("unexpected value {:?}", value),
+        }
+    }
+
+    #[test]
+    fn stops_decoding_deeply_nested_groups() {
+        let fields = Fields::new(
+            (10..50)
+                .map(|id| definition(id, "g", if id == 10 { 1 } else { id - 1 }, "group"))
+                .collect(),
+        );
+        let meta = meta(&[("_g", "field_10")]);
+
+        let mut value = &fields.values(&meta)[0].1;
+        let mut depth = 0;
+        while let Value::Object(children) = value {
+            value = &children[0].1;
+            depth += 1;
+        }
+        assert_eq!(depth, MAX_DEPTH + 1);
+        assert_eq!(*value, Value::Null);
+    }
+}
@@ -0,0 +1,590 @@
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+/// A parsed block, serialized in the same shape as WordPress's
+/// `parse_blocks()`. Freeform HTML between blocks has no `blockName`.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Block {
+    pub block_name: Option<String>,
+    pub attrs: Value,
+    pub inner_blocks: Vec<Block>,
+    #[serde(rename = "innerHTML")]
+    pub inner_html: String,
+    pub inner_content: Vec<Option<String>>,
+}
+
+impl Block {
+    fn new(block_name: Option<String>, attrs: Value) -> Self {
+        Block {
+            block_name,
+            attrs,
+            inner_blocks: Vec::new(),
+            inner_html: String::new(),
+            inner_content: Vec::new(),
+        }
+    }
+
+    fn freeform(html: &str) -> Self {
+        Block {
+            block_name: None,
+            attrs: Value::Object(Map::new()),
+            inner_blocks: Vec::new(),
+            inner_html: html.to_string(),
+            inner_content: vec![Some(html.to_string())],
+        }
+    }
+
+    fn push_html(&mut self, html: &str) {
+        if !html.is_empty() {
+            self.inner_html.push_str(html);
+            self.inner_content.push(Some(html.to_string()));
+        }
+    }
+
+    /// ID of the `wp_block` post referenced by a `core/block` block.
+    fn reusable_block_ref(&self) -> Option<u64> {
+        if self.block_name.as_deref() != Some("core/block") {
+            return None;
+        }
+
+        match self.attrs.get("ref")? {
+            Value::Number(number) => number.as_u64(),
+            Value::String(string) => string.parse().ok(),
+            _ => None,
+        }
+    }
+}
+
+/// Parses serialized block content (`<!-- wp:name {"attrs"} -->...`) into a
+/// block tree. This is a port of WordPress's `WP_Block_Parser` and mirrors its
+/// handling of freeform HTML, void blocks and unbalanced delimiters.
+pub fn parse(document: &str) -> Vec<Block> {
+    let mut parser = Parser {
+        document,
+        offset: 0,
+        output: Vec::new(),
+        stack: Vec::new(),
+    };
+
+    while parser.proceed() {}
+
+    parser.output
+}
+
+/// Equivalent of `has_blocks()`.
+pub fn has_blocks(content: &str) -> bool {
+    content.contains("<!-- wp:")
+}
+
+/// Renders parsed blocks back to HTML the way `do_blocks()` does for static
+/// blocks: delimiters are dropped and inner blocks are rendered in place of
+/// their placeholders. Reusable blocks render their expanded content.
+pub fn render(blocks: &[Block]) -> String {
+    blocks.iter().map(render_block).collect()
+}
+
+fn render_block(block: &Block) -> String {
+    if block.reusable_block_ref().is_some() {
+        return render(&block.inner_blocks);
+    }
+
+    let mut html = String::new();
+    let mut inner_blocks = block.inner_blocks.iter();
+
+    for chunk in &block.inner_content {
+        match chunk {
+            Some(chunk) => html.push_str(chunk),
+            None => {
+                if let Some(inner_block) = inner_blocks.next() {
+                    html.push_str(&render_block(inner_block));
+                }
+            }
+        }
+    }
+
+    html
+}
+
+/// Collects the `wp_block` IDs referenced by `core/block` blocks that have not
+/// been expanded yet.
+pub fn reusable_block_refs(blocks: &[Block]) -> Vec<u64> {
+    let mut refs = Vec::new();
+
+    for block in blocks {
+        match block.reusable_block_ref() {
+            Some(id) if block.inner_blocks.is_empty() => refs.push(id),
+            _ => refs.extend(reusable_block_refs(&block.inner_blocks)),
+        }
+    }
+
+    refs
+}
+
+/// Replaces the inner blocks of unexpanded `core/block` references with the
+/// parsed content of the reusable block they point to.
+pub fn attach_reusable_blocks(blocks: &mut [Block], resolved: &HashMap<u64, Vec<Block>>) {
+    for block in blocks {
+        match block.reusable_block_ref() {
+            Some(id) if block.inner_blocks.is_empty() => {
+                if let Some(inner_blocks) = resolved.get(&id) {
+                    block.inner_blocks = inner_blocks.clone();
+                }
+            }
+            _ => attach_reusable_blocks(&mut block.inner_blocks, resolved),
+        }
+    }
+}
+
+enum Token {
+    NoMoreTokens,
+    Opener(Delimiter),
+    Closer(Delimiter),
+    Void(Delimiter),
+}
+
+struct Delimiter {
+    name: String,
+    attrs: Value,
+    start: usize,
+    length: usize,
+}
+
+struct Frame {
+    block: Block,
+    token_start: usize,
+    prev_offset: usize,
+    leading_html_start: Option<usize>,
+}
+
+struct Parser<'a> {
+    document: &'a str,
+    offset: usize,
+    output: Vec<Block>,
+    stack: Vec<Frame>,
+}
+
+impl Parser<'_> {
+    fn proceed(&mut self) -> bool {
+        let token = self.next_token();
+
+        match token {
+            Token::NoMoreTokens => {
+                if self.stack.is_empty() {
+                    self.add_freeform();
+                } else {
+                    // Unclosed blocks swallow the rest of the document.
+                    while !self.stack.is_empty() {
+                        self.add_block_from_stack(None);
+                    }
+                }
+                false
+            }
+            Token::Void(delimiter) => {
+                let end = delimiter.start + delimiter.length;
+                let block = Block::new(Some(delimiter.name), delimiter.attrs);
+
+                if self.stack.is_empty() {
+                    if delimiter.start > self.offset {
+                        self.output.push(Block::freeform(
+                            &self.document[self.offset..delimiter.start],
+                        ));
+                    }
+                    self.output.push(block);
+                } else {
+                    self.add_inner_block(block, delimiter.start, end);
+                }
+
+                self.offset = end;
+                true
+            }
+            Token::Opener(delimiter) => {
+                let end = delimiter.start + delimiter.length;
+                let leading_html_start = if delimiter.start > self.offset {
+                    Some(self.offset)
+                } else {
+                    None
+                };
+
+                self.stack.push(Frame {
+                    block: Block::new(Some(delimiter.name), delimiter.attrs),
+                    token_start: delimiter.start,
+                    prev_offset: end,
+                    leading_html_start,
+                });
+
+                self.offset = end;
+                true
+            }
+            Token::Closer(delimiter) => {
+                let end = delimiter.start + delimiter.length;
+
+                match self.stack.len() {
+                    0 => {
+                        // A closer without an opener: treat everything that
+                        // remains as freeform HTML.
+                        self.add_freeform();
+                        return false;
+                    }
+                    1 => self.add_block_from_stack(Some(delimiter.start)),
+                    _ => {
+                        // Unlike the other cases, WordPress records the
+                        // closing HTML here even when it is empty.
+                        let mut frame = self.stack.pop().expect("stack is not empty");
+                        let html = &self.document[frame.prev_offset..delimiter.start];
+                        frame.block.inner_html.push_str(html);
+                        frame.block.inner_content.push(Some(html.to_string()));
+                        self.add_inner_block(frame.block, frame.token_start, end);
+                    }
+                }
+
+                self.offset = end;
+                true
+            }
+        }
+    }
+
+    fn add_freeform(&mut self) {
+        if self.document.len() > self.offset {
+            self.output
+                .push(Block::freeform(&self.document[self.offset..]));
+        }
+    }
+
+    fn add_inner_block(&mut self, block: Block, token_start: usize, last_offset: usize) {
+        let parent = self.stack.last_mut().expect("inner block needs a parent");
+        let html = &self.document[parent.prev_offset..token_start];
+
+        parent.block.push_html(html);
+        parent.block.inner_blocks.push(block);
+        parent.block.inner_content.push(None);
+        parent.prev_offset = last_offset;
+    }
+
+    fn add_block_from_stack(&mut self, end_offset: Option<usize>) {
+        let mut frame = self.stack.pop().expect("stack is not empty");
+        let end = end_offset.unwrap_or(self.document.len());
+
+        frame
+            .block
+            .push_html(&self.document[frame.prev_offset..end]);
+
+        if let Some(leading_html_start) = frame.leading_html_start {
+            self.output.push(Block::freeform(
+                &self.document[leading_html_start..frame.token_start],
+            ));
+        }
+
+        self.output.push(frame.block);
+    }
+
+    fn next_token(&self) -> Token {
+        let mut search_from = self.offset;
+
+        while let Some(index) = self.document[search_from..].find("<!--") {
+            let start = search_from + index;
+            if let Some(token) = self.match_delimiter(start) {
+                return token;
+            }
+            search_from = start + 4;
+        }
+
+        Token::NoMoreTokens
+    }
+
+    // Hand-rolled equivalent of the delimiter pattern used by WordPress:
+    // <!--\s+(/)?wp:(namespace/)?name\s+({attrs}\s+)?(/)?-->
+    fn match_delimiter(&self, start: usize) -> Option<Token> {
+        let bytes = self.document.as_bytes();
+        let mut pos = start + 4;
+
+        pos += Self::required_whitespace(bytes, pos)?;
+
+        let is_closer = bytes.get(pos) == Some(&b'/');
+        if is_closer {
+            pos += 1;
+        }
+
+        if !bytes[pos..].starts_with(b"wp:") {
+            return None;
+        }
+        pos += 3;
+
+        let first_length = Self::identifier(bytes, pos)?;
+        let first = &self.document[pos..pos + first_length];
+        pos += first_length;
+
+        let name = if bytes.get(pos) == Some(&b'/') {
+            let second_length = Self::identifier(bytes, pos + 1)?;
+            let second = &self.document[pos + 1..pos + 1 + second_length];
+            pos += 1 + second_length;
+            format!("{}/{}", first, second)
+        } else {
+            format!("core/{}", first)
+        };
+
+        pos += Self::required_whitespace(bytes, pos)?;
+
+        let mut attrs = None;
+        if bytes.get(pos) == Some(&b'{') {
+            let (json, next) = self.attributes(pos)?;
+            attrs = Some(json);
+            pos = next;
+        }
+
+        let is_void = bytes.get(pos) == Some(&b'/');
+        if is_void {
+            pos += 1;
+        }
+
+        if !bytes[pos..].starts_with(b"-->") {
+            return None;
+        }
+        pos += 3;
+
+        let attrs = attrs
+            .and_then(|json| serde_json::from_str::<Value>(json).ok())
+            .filter(Value::is_object)
+            .unwrap_or_else(|| Value::Object(Map::new()));
+
+        let delimiter = Delimiter {
+            name,
+            attrs,
+            start,
+            length: pos - start,
+        };
+
+        // A closer carrying attributes or a void flag is malformed, but like
+        // WordPress we let the void flag win.
+        Some(if is_void {
+            Token::Void(delimiter)
+        } else if is_closer {
+            Token::Closer(delimiter)
+        } else {
+            Token::Opener(delimiter)
+        })
+    }
+
+    // The attribute JSON runs up to the first `}` that is followed by
+    // whitespace and the end of the comment.
+    fn attributes(&self, start: usize) -> Option<(&str, usize)> {
+        let bytes = self.document.as_bytes();
+        let mut search_from = start;
+
+        while let Some(index) = self.document[search_from..].find('}') {
+            let close = search_from + index;
+            let after = close + 1;
+
+            if let Some(whitespace) = Self::required_whitespace(bytes, after) {
+                let mut end = after + whitespace;
+                if bytes.get(end) == Some(&b'/') {
+                    end += 1;
+                }
+                if bytes[end..].starts_with(b"-->") {
+                    return Some((&self.document[start..=close], after + whitespace));
+                }
+            }
+
+            search_from = after;
+        }
+
+        None
+    }
+
+    fn required_whitespace(bytes: &[u8], pos: usize) -> Option<usize> {
+        let length = bytes[pos.min(bytes.len())..]
+            .iter()
+            .take_while(|byte| byte.is_ascii_whitespace())
+            .count();
+
+        if length > 0 {
+            Some(length)
+        } else {
+            None
+        }
+    }
+
+    // [a-z][a-z0-9_-]*
+    fn identifier(bytes: &[u8], pos: usize) -> Option<usize> {
+        let rest = &bytes[pos.min(bytes.len())..];
+
+        if !rest.first()?.is_ascii_lowercase() {
+            return None;
+        }
+
+        Some(
+            rest.iter()
+                .take_while(|byte| {
+                    byte.is_ascii_lowercase()
+                        || byte.is_ascii_digit()
+                        || **byte == b'_'
+                        || **byte == b'-'
+                })
+                .count(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn parsed(document: &str) -> Value {
+        serde_json::to_value(parse(document)).unwrap()
+    }
+
+    fn freeform(html: &str) -> Value {
+        json!({
+            "blockName": null,
+            "attrs": {},
+            "innerBlocks": [],
+            "innerHTML": html,
+            "innerContent": [html],
+        })
+    }
+
+    #[test]
+    fn keeps_freeform_html_between_blocks() {
+        assert_eq!(
+            parsed("<p>Classic</p>"),
+            json!([freeform("<p>Classic</p>")])
+        );
+        assert_eq!(
+            parsed("before<!-- wp:paragraph --><p>a</p><!-- /wp:paragraph -->after"),
+            json!([
+                freeform("before"),
+                {
+                    "blockName": "core/paragraph",
+                    "attrs": {},
+                    "innerBlocks": [],
+                    "innerHTML": "<p>a</p>",
+                    "innerContent": ["<p>a</p>"],
+                },
+                freeform("after"),
+            ])
+        );
+    }
+
+    #[test]
+    fn parses_void_blocks() {
+        assert_eq!(
+            parsed("<!-- wp:separator /--><!-- wp:my-plugin/latest {\"count\":3} /-->"),
+            json!([
+                {
+                    "blockName": "core/separator",
+                    "attrs": {},
+                    "innerBlocks": [],
+                    "innerHTML": "",
+                    "innerContent": [],
+                },
+                {
+                    "blockName": "my-plugin/latest",
+                    "attrs": {"count": 3},
+                    "innerBlocks": [],
+                    "innerHTML": "",
+                    "innerContent": [],
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn nests_inner_blocks_with_placeholders() {
+        let document = concat!(
+            "<!-- wp:columns --><div>",
+            "<!-- wp:column --><p>a</p><!-- /wp:column -->",
+            "<!-- wp:spacer /-->",
+            "</div><!-- /wp:columns -->"
+        );
+        assert_eq!(
+            parsed(document),
+            json!([{
+                "blockName": "core/columns",
+                "attrs": {},
+                "innerBlocks": [
+                    {
+                        "blockName": "core/column",
+                        "attrs": {},
+                        "innerBlocks": [],
+                        "innerHTML": "<p>a</p>",
+                        "innerContent": ["<p>a</p>"],
+                    },
+                    {
+                        "blockName": "core/spacer",
+                        "attrs": {},
+                        "innerBlocks": [],
+                        "innerHTML": "",
+                        "innerContent": [],
+                    },
+                ],
+                "innerHTML": "<div></div>",
+                "innerContent": ["<div>", null, null, "</div>"],
+            }])
+        );
+        assert_eq!(render(&parse(document)), "<div><p>a</p></div>");
+    }
+
+    #[test]
+    fn handles_unbalanced_delimiters() {
+        // An unclosed block runs to the end of the document.
+        assert_eq!(
+            parsed("<!-- wp:paragraph --><p>a</p>"),
+            json!([{
+                "blockName": "core/paragraph",
+                "attrs": {},
+                "innerBlocks": [],
+                "innerHTML": "<p>a</p>",
+                "innerContent": ["<p>a</p>"],
+            }])
+        );
+
+        // A stray closer turns the rest of the document into freeform HTML.
+        assert_eq!(
+            parsed("<p>a</p><!-- /wp:paragraph --><p>b</p>"),
+            json!([freeform("<p>a</p><!-- /wp:paragraph --><p>b</p>")])
+        );
+
+        // Comments that are not delimiters are left in the HTML.
+        assert_eq!(
+            parsed("<!-- wp:Paragraph --><!-- more -->"),
+            json!([freeform("<!-- wp:Paragraph --><!-- more -->")])
+        );
+    }
+
+    #[test]
+    fn decodes_attribute_json() {
+        // The block editor escapes `--` in attributes so they cannot end the
+        // comment.
+        let document = concat!(
+            r#"<!-- wp:paragraph {"note":"a\u002d\u002db","brace":"}","list":[1,{"x":2}]} -->"#,
+            "<p>x</p><!-- /wp:paragraph -->"
+        );
+        assert_eq!(
+            parsed(document)[0]["attrs"],
+            json!({"note": "a--b", "brace": "}", "list": [1, {"x": 2}]})
+        );
+    }
+
+    #[test]
+    fn expands_reusable_block_refs() {
+        let mut blocks = parse(concat!(
+            "<!-- wp:block {\"ref\":12} /-->",
+            "<!-- wp:group --><div><!-- wp:block {\"ref\":\"13\"} /--></div><!-- /wp:group -->"
+        ));
+        assert_eq!(reusable_block_refs(&blocks), vec![12, 13]);
+
+        let resolved = HashMap::from([
+            (
+                12,
+                parse("<!-- wp:paragraph --><p>Shared</p><!-- /wp:paragraph -->"),
+            ),
+            (13, parse("<p>Nested</p>")),
+        ]);
+        attach_reusable_blocks(&mut blocks, &resolved);
+
+        assert!(reusable_block_refs(&blocks).is_empty());
+        assert_eq!(render(&blocks), "<p>Shared</p><div><p>Nested</p></div>");
+    }
+}
@@ -0,0 +1,364 @@
+use serde::Serialize;
+
+use crate::utils::formatting;
+
+// Above this many differing tokens the remaining middle section is reported
+// as one deletion and one insertion, which bounds time and memory for texts
+// that were rewritten entirely.
+const MAX_EDIT_DISTANCE: usize = 1000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Operation {
+    Equal,
+    Insert,
+    Delete,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Change {
+    pub op: Operation,
+    pub text: String,
+}
+
+/// Word-level diff between two texts. HTML tags and entities are compared as
+/// whole tokens so markup is never split, and CJK text, which has no spaces
+/// between words, is compared character by character.
+pub fn diff_words(old: &str, new: &str) -> Vec<Change> {
+    let old_tokens = tokenize(old);
+    let new_tokens = tokenize(new);
+
+    let mut changes: Vec<Change> = Vec::new();
+    for (op, token) in diff_tokens(&old_tokens, &new_tokens) {
+        match changes.last_mut() {
+            Some(change) if change.op == op => change.text.push_str(token),
+            _ => changes.push(Change {
+                op,
+                text: token.to_string(),
+            }),
+        }
+    }
+
+    changes
+}
+
+fn tokenize(text: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let mut rest = text;
+
+    while let Some(c) = rest.chars().next() {
+        let length = if c == '<' {
+            tag_length(rest).unwrap_or(1)
+        } else if c == '&' {
+            entity_length(rest).unwrap_or(1)
+        } else if c.is_whitespace() {
+            run_length(rest, char::is_whitespace)
+        } else if formatting::is_cjk(c) {
+            c.len_utf8()
+        } else if is_word_char(c) {
+            run_length(rest, is_word_char)
+        } else {
+            c.len_utf8()
+        };
+
+        tokens.push(&rest[..length]);
+        rest = &rest[length..];
+    }
+
+    tokens
+}
+
+fn tag_length(text: &str) -> Option<usize> {
+    let next = text[1..].chars().next()?;
+    if !(next.is_ascii_alphabetic() || next == '/' || next == '!') {
+        return None;
+    }
+    text.find('>').map(|end| end + 1)
+}
+
+fn entity_length(text: &str) -> Option<usize> {
+    let end = text[1..].find(';')? + 1;
+    let name = &text[1..end];
+    let valid = !name.is_empty()
+        && name.len() <= 10
+        && name
+            .chars()
+            .enumerate()
+            .all(|(index, c)| c.is_ascii_alphanumeric() || (index == 0 && c == '#'));
+    valid.then_some(end + 1)
+}
+
+fn run_length(text: &str, predicate: fn(char) -> bool) -> usize {
+    text.char_indices()
+        .find(|(_, c)| !predicate(*c))
+        .map_or(text.len(), |(index, _)| index)
+}
+
+fn is_word_char(c: char) -> bool {
+    (c.is_alphanumeric() || c == '_') && !formatting::is_cjk(c)
+}
+
+/// Myers' O(ND) diff over tokens, after trimming the common prefix and
+/// suffix.
+fn diff_tokens<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<(Operation, &'a str)> {
+    let prefix = old
+        .iter()
+        .zip(new)
+        .take_while(|(old, new)| old == new)
+        .count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(old, new)| old == new)
+        .count();
+
+    let old_middle = &old[prefix..old.len() - suffix];
+    let new_middle = &new[prefix..new.len() - suffix];
+
+    let mut result: Vec<(Operation, &str)> = old[..prefix]
+        .iter()
+        .map(|token| (Operation::Equal, *token))
+        .collect();
+    result.extend(diff_middle(old_middle, new_middle));
+    result.extend(
+        old[old.len() - suffix..]
+            .iter()
+            .map(|token| (Operation::Equal, *token)),
+    );
+
+    result
+}
+
+fn diff_middle<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<(Operation, &'a str)> {
+    let n = old.len() as isize;
+    let m = new.len() as isize;
+    let max = (old.len() + new.len()).min(MAX_EDIT_DISTANCE) as isize;
+
+    let replace_all = || {
+        old.iter()
+            .map(|token| (Operation::Delete, *token))
+            .chain(new.iter().map(|token| (Operation::Insert, *token)))
+            .collect()
+    };
+
+    if n == 0 || m == 0 {
+        return replace_all();
+    }
+
+    // `v[k]` is the furthest x reached on diagonal k; `trace[d]` keeps the
+    // diagonals -d..=d as they were before step d, for backtracking.
+    let offset = max + 1;
+    let mut v = vec![0isize; (2 * offset + 1) as usize];
+    let mut trace: Vec<Vec<isize>> = Vec::new();
+    let mut found = None;
+
+    'search: for d in 0..=max {
+        trace.push(v[(offset - d) as usize..=(offset + d) as usize].to_vec());
+
+        for k in (-d..=d).step_by(2) {
+            let index = (offset + k) as usize;
+            let mut x = if k == -d || (k != d && v[index - 1] < v[index + 1]) {
+                v[index + 1]
+            } else {
+                v[index - 1] + 1
+            };
+            let mut y = x - k;
+
+            while x < n && y < m && old[x as usize] == new[y as usize] {
+                x += 1;
+                y += 1;
+            }
+
+            v[index] = x;
+
+            if x >= n && y >= m {
+                found = Some(d);
+                break 'search;
+            }
+        }
+    }
+
+    let Some(distance) = found else {
+        return replace_all();
+    };
+
+    let mut result = Vec::new();
+    let (mut x, mut y) = (n, m);
+
+    for d in (1..=distance).rev() {
+        let snapshot = &trace[d as usize];
+        let at = |k: isize| snapshot[(k + d) as usize];
+
+        let k = x - y;
+        let previous_k = if k == -d || (k != d && at(k - 1) < at(k + 1)) {
+            k + 1
+        } else {
+            k - 1
+        };
+        let previous_x = at(previous_k);
+        let previous_y = previous_x - previous_k;
+
+        while x > previous_x && y > previous_y {
+            x -= 1;
+            y -= 1;
+            result.push((Operation::Equal, old[x as usize]));
+        }
+
+        if x == previous_x {
+            y -= 1;
+            result.push((Operation::Insert, new[y as usize]));
+        } else {
+            x -= 1;
+            result.push((Operation::Delete, old[x as usize]));
+        }
+    }
+
+    while x > 0 && y > 0 {
+        x -= 1;
+        y -= 1;
+        result.push((Operation::Equal, old[x as usize]));
+    }
+
+    result.reverse();
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Operation::{Delete, Equal, Insert};
+
+    fn diff(old: &str, new: &str) -> Vec<(Operation, String)> {
+        diff_words(old, new)
+            .into_iter()
+            .map(|change| (change.op, change.text))
+            .collect()
+    }
+
+    fn change(op: Operation, text: &str) -> (Operation, String) {
+        (op, text.to_string())
+    }
+
+    #[test]
+    fn identical_and_empty_texts() {
+        assert_eq!(
+            diff("same text", "same text"),
+            vec![change(Equal, "same text")]
+        );
+        assert_eq!(diff("", ""), vec![]);
+        assert_eq!(diff("", "new text"), vec![change(Insert, "new text")]);
+        assert_eq!(diff("old text", ""), vec![change(Delete, "old text")]);
+    }
+
+    #[test]
+    fn insertions_and_deletions() {
+        assert_eq!(
+            diff("the cat sat", "the black cat sat"),
+            vec![
+                change(Equal, "the "),
+                change(Insert, "black "),
+                change(Equal, "cat sat"),
+            ]
+        );
+        assert_eq!(
+            diff("the black cat sat", "the cat sat"),
+            vec![
+                change(Equal, "the "),
+                change(Delete, "black "),
+                change(Equal, "cat sat"),
+            ]
+        );
+        assert_eq!(
+            diff("one two three", "one 2 three"),
+            vec![
+                change(Equal, "one "),
+                change(Delete, "two"),
+                change(Insert, "2"),
+                change(Equal, " three"),
+            ]
+        );
+    }
+
+    #[test]
+    fn keeps_tags_and_entities_whole() {
+        assert_eq!(
+            tokenize(r#"a <a href="/x">link</a> &amp; &#8217; &bogus entity"#),
+            vec![
+                "a",
+                " ",
+                r#"<a href="/x">"#,
+                "link",
+                "</a>",
+                " ",
+                "&amp;",
+                " ",
+                "&#8217;",
+                " ",
+                "&",
+                "bogus",
+                " ",
+                "entity",
+            ]
+        );
+        assert_eq!(
+            diff("<p>Hello</p>", r#"<p class="x">Hello</p>"#),
+            vec![
+                change(Delete, "<p>"),
+                change(Insert, r#"<p class="x">"#),
+                change(Equal, "Hello</p>"),
+            ]
+        );
+    }
+
+    #[test]
+    fn treats_a_stray_angle_bracket_as_text() {
+        assert_eq!(tokenize("1 < 2"), vec!["1", " ", "<", " ", "2"]);
+        assert_eq!(tokenize("a<"), vec!["a", "<"]);
+        assert_eq!(
+            diff("1 < 2", "1 <= 2"),
+            vec![
+                change(Equal, "1 <"),
+                change(Insert, "="),
+                change(Equal, " 2"),
+            ]
+        );
+    }
+
+    #[test]
+    fn diffs_cjk_text_per_character() {
+        assert_eq!(tokenize("中文。"), vec!["中", "文", "。"]);
+        assert_eq!(
+            diff("我喜欢猫", "我喜欢狗"),
+            vec![
+                change(Equal, "我喜欢"),
+                change(Delete, "猫"),
+                change(Insert, "狗"),
+            ]
+        );
+    }
+
+    #[test]
+    fn falls_back_to_replacing_when_too_different() {
+        let old: Vec<String> = (0..MAX_EDIT_DISTANCE).map(|i| format!("a{}", i)).collect();
+        let new: Vec<String> = (0..MAX_EDIT_DISTANCE).map(|i| format!("b{}", i)).collect();
+        let (old, new) = (old.join(" "), new.join(" "));
+
+        assert_eq!(
+            diff(&old, &new),
+            vec![change(Delete, &old), change(Insert, &new)]
+        );
+
+        // Below the cap the shared spaces are kept.
+        assert_eq!(
+            diff("a0 a1", "b0 b1"),
+            vec![
+                change(Delete, "a0"),
+                change(Insert, "b0"),
+                change(Equal, " "),
+                change(Delete, "a1"),
+                change(Insert, "b1"),
+            ]
+        );
+    }
+}
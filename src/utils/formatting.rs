@@ -0,0 +1,1022 @@
+use fancy_regex::Regex;
+use std::sync::LazyLock;
+
+// Ports of the WordPress content filters from `wp-includes/formatting.php`.
+// The patterns are kept as close to the PHP originals as the regex engine
+// allows; possessive quantifiers become atomic groups and PCRE conditionals
+// are rewritten as alternations.
+
+const OPENING_QUOTE: &str = "&#8220;";
+const CLOSING_QUOTE: &str = "&#8221;";
+const APOS: &str = "&#8217;";
+const PRIME: &str = "&#8242;";
+const DOUBLE_PRIME: &str = "&#8243;";
+const OPENING_SINGLE_QUOTE: &str = "&#8216;";
+const CLOSING_SINGLE_QUOTE: &str = "&#8217;";
+const EN_DASH: &str = "&#8211;";
+const EM_DASH: &str = "&#8212;";
+
+const OPEN_Q_FLAG: &str = "<!--oq-->";
+const OPEN_SQ_FLAG: &str = "<!--osq-->";
+const APOS_FLAG: &str = "<!--apos-->";
+const PRIME_FLAG: &str = "<!--wp-prime-or-quote-->";
+
+// `wp_spaces_regexp()`.
+const SPACES: &str = r"[\r\n\t ]|\x{a0}|&nbsp;";
+
+const ALLBLOCKS: &str = "(?:table|thead|tfoot|caption|col|colgroup|tbody|tr|td|th|div|dl|dd|dt|ul|ol|li|pre|form|map|area|blockquote|address|math|style|p|h[1-6]|hr|fieldset|legend|section|article|aside|hgroup|header|footer|nav|figure|figcaption|details|menu|summary)";
+
+const NO_TEXTURIZE_TAGS: [&str; 6] = ["pre", "code", "kbd", "style", "script", "tt"];
+const NO_TEXTURIZE_SHORTCODES: [&str; 1] = ["code"];
+
+const STATIC_REPLACEMENTS: [(&str, &str); 15] = [
+    ("...", "&#8230;"),
+    ("``", OPENING_QUOTE),
+    ("''", CLOSING_QUOTE),
+    (" (tm)", " &#8482;"),
+    ("'tain't", "&#8217;tain&#8217;t"),
+    ("'twere", "&#8217;twere"),
+    ("'twas", "&#8217;twas"),
+    ("'tis", "&#8217;tis"),
+    ("'twill", "&#8217;twill"),
+    ("'til", "&#8217;til"),
+    ("'bout", "&#8217;bout"),
+    ("'nuff", "&#8217;nuff"),
+    ("'round", "&#8217;round"),
+    ("'cause", "&#8217;cause"),
+    ("'em", "&#8217;em"),
+];
+
+// Excerpt length and "more" string used by `wp_trim_excerpt()`.
+pub const EXCERPT_LENGTH: usize = 55;
+pub const EXCERPT_MORE: &str = " [&hellip;]";
+
+/// Compiles a pattern written for PCRE without the `u` modifier, where `\s`
+/// and `\d` only match ASCII characters.
+fn pattern(source: &str) -> Regex {
+    let source = source
+        .replace(r"\s", r"[\t\n\x0B\x0C\r ]")
+        .replace(r"\d", "[0-9]");
+    Regex::new(&source).expect("invalid formatting pattern")
+}
+
+/// Like `preg_replace()`, a pattern that hits the backtracking limit leaves
+/// the text alone instead of failing the whole request.
+fn replace_all(regex: &Regex, text: &str, replacement: &str) -> String {
+    regex
+        .try_replacen(text, 0, replacement)
+        .map(|replaced| replaced.into_owned())
+        .unwrap_or_else(|_| text.to_string())
+}
+
+struct Patterns {
+    apos: Vec<(Regex, String)>,
+    quote: Vec<(Regex, String)>,
+    dash: Vec<(Regex, String)>,
+    has_times: Regex,
+    times: Regex,
+    ampersand: Regex,
+    single_primes: Primes,
+    double_primes: Primes,
+    html_split: Regex,
+    autop: AutopPatterns,
+    strip_tags: [Regex; 2],
+    words: Regex,
+}
+
+struct Primes {
+    needle: &'static str,
+    prime: &'static str,
+    open_quote: &'static str,
+    close_quote: &'static str,
+    quote: Regex,
+    prime_pattern: Regex,
+    flag_after_digit: Regex,
+    flag_no_digit: Regex,
+}
+
+impl Primes {
+    fn new(
+        needle: &'static str,
+        prime: &'static str,
+        open_quote: &'static str,
+        close_quote: &'static str,
+    ) -> Self {
+        Primes {
+            needle,
+            prime,
+            open_quote,
+            close_quote,
+            quote: pattern(&format!(
+                r"{}(?=\z|[.,:;!?)}}\-\]]|&gt;|{})",
+                needle, SPACES
+            )),
+            prime_pattern: pattern(&format!(r"(?<=\d){}", needle)),
+            flag_after_digit: pattern(&format!(r"(?<=\d){}", PRIME_FLAG)),
+            flag_no_digit: pattern(&format!(r"(?<!\d){}", PRIME_FLAG)),
+        }
+    }
+}
+
+struct AutopPatterns {
+    double_br: Regex,
+    block_open: Regex,
+    block_close: Regex,
+    hr: Regex,
+    option: [Regex; 2],
+    object: [Regex; 3],
+    media: [Regex; 3],
+    figcaption: [Regex; 2],
+    newlines: Regex,
+    paragraphs: Regex,
+    empty_paragraph: Regex,
+    unclosed_paragraph: Regex,
+    wrapped_block: Regex,
+    wrapped_li: Regex,
+    wrapped_blockquote: Regex,
+    block_after_p: Regex,
+    block_before_p: Regex,
+    br: Regex,
+    br_after_block: Regex,
+    br_before_block: Regex,
+    trailing_newline: Regex,
+}
+
+static PATTERNS: LazyLock<Patterns> = LazyLock::new(|| {
+    let apos = vec![
+        // '99' and '99" are ambiguous among other patterns; assume it's an
+        // abbreviated year at the end of a quotation.
+        (
+            format!(r"'(\d\d)'(?=\z|[.,:;!?)}}\-\]]|&gt;|{})", SPACES),
+            format!("{}${{1}}{}", APOS_FLAG, CLOSING_SINGLE_QUOTE),
+        ),
+        (
+            format!(r#"'(\d\d)"(?=\z|[.,:;!?)}}\-\]]|&gt;|{})"#, SPACES),
+            format!("{}${{1}}{}", APOS_FLAG, CLOSING_QUOTE),
+        ),
+        // '99 '99s '99's (apostrophe), but never '9 or '99% or '999 or '99.0.
+        (
+            r"'(?=\d\d(?:\z|(?![%\d]|[.,]\d)))".to_string(),
+            APOS_FLAG.to_string(),
+        ),
+        // Quoted numbers like '0.42'.
+        (
+            format!(r"(?<=\A|{})'(\d[.,\d]*)'", SPACES),
+            format!("{}${{1}}{}", OPEN_SQ_FLAG, CLOSING_SINGLE_QUOTE),
+        ),
+        // Single quote at start, or preceded by (, {, <, [, ", -, or spaces.
+        (
+            format!(r#"(?<=\A|[(\[{{"\-]|&lt;|{})'"#, SPACES),
+            OPEN_SQ_FLAG.to_string(),
+        ),
+        // Apostrophe in a word. No spaces, double apostrophes, or other
+        // punctuation.
+        (
+            format!(
+                r#"(?<!{})'(?!\z|[.,:;!?"'(){{}}\[\]\-]|&[lg]t;|{})"#,
+                SPACES, SPACES
+            ),
+            APOS_FLAG.to_string(),
+        ),
+    ];
+
+    let quote = vec![
+        // Quoted numbers like "42".
+        (
+            format!(r#"(?<=\A|{})"(\d[.,\d]*)""#, SPACES),
+            format!("{}${{1}}{}", OPEN_Q_FLAG, CLOSING_QUOTE),
+        ),
+        // Double quote at start, or preceded by (, {, <, [, -, or spaces, and
+        // not followed by spaces.
+        (
+            format!(r#"(?<=\A|[(\[{{\-]|&lt;|{})"(?!{})"#, SPACES, SPACES),
+            OPEN_Q_FLAG.to_string(),
+        ),
+    ];
+
+    let dash = vec![
+        ("---".to_string(), EM_DASH.to_string()),
+        (
+            format!("(?<=^|{})--(?=$|{})", SPACES, SPACES),
+            EM_DASH.to_string(),
+        ),
+        ("(?<!xn)--".to_string(), EN_DASH.to_string()),
+        (
+            format!("(?<=^|{})-(?=$|{})", SPACES, SPACES),
+            EN_DASH.to_string(),
+        ),
+    ];
+
+    let compile = |patterns: Vec<(String, String)>| {
+        patterns
+            .into_iter()
+            .map(|(source, replacement)| (pattern(&source), replacement))
+            .collect()
+    };
+
+    // `get_html_split_regex()`: comments, CDATA sections and elements.
+    let html_split = pattern(concat!(
+        r"<(?:(?=!--)!(?:-(?!->)[^\-]*)*(?:-->)?",
+        r"|(?=!\[CDATA\[)!\[CDATA\[[^\]]*(?:\](?!\]>)[^\]]*)*(?:\]\]>)?",
+        r"|[^>]*>?)"
+    ));
+
+    Patterns {
+        apos: compile(apos),
+        quote: compile(quote),
+        dash: compile(dash),
+        has_times: pattern(r"(?<=\d)x\d"),
+        // 9x9 (times), but never 0x9999.
+        times: pattern(r"\b(0[\d.,]+|[1-9][\d.,]*)x(\d[\d.,]*)\b"),
+        ampersand: pattern(r"(?i)&(?!#(?:\d+|x[a-f0-9]+);|[a-z1-4]{1,8};)"),
+        single_primes: Primes::new("'", PRIME, OPEN_SQ_FLAG, CLOSING_SINGLE_QUOTE),
+        double_primes: Primes::new("\"", DOUBLE_PRIME, OPEN_Q_FLAG, CLOSING_QUOTE),
+        html_split,
+        autop: AutopPatterns {
+            double_br: pattern(r"<br\s*/?>\s*<br\s*/?>"),
+            block_open: pattern(&format!(r"(<{}[\s/>])", ALLBLOCKS)),
+            block_close: pattern(&format!(r"(</{}>)", ALLBLOCKS)),
+            hr: pattern(r"(<hr\s*?/?>)"),
+            option: [pattern(r"\s*<option"), pattern(r"</option>\s*")],
+            object: [
+                pattern(r"(<object[^>]*>)\s*"),
+                pattern(r"\s*</object>"),
+                pattern(r"\s*(</?(?:param|embed)[^>]*>)\s*"),
+            ],
+            media: [
+                pattern(r"([<\[](?:audio|video)[^>\]]*[>\]])\s*"),
+                pattern(r"\s*([<\[]/(?:audio|video)[>\]])"),
+                pattern(r"\s*(<(?:source|track)[^>]*>)\s*"),
+            ],
+            figcaption: [
+                pattern(r"\s*(<figcaption[^>]*>)"),
+                pattern(r"</figcaption>\s*"),
+            ],
+            newlines: pattern(r"\n\n+"),
+            paragraphs: pattern(r"\n\s*\n"),
+            empty_paragraph: pattern(r"<p>\s*</p>"),
+            unclosed_paragraph: pattern(r"<p>([^<]+)</(div|address|form)>"),
+            wrapped_block: pattern(&format!(r"<p>\s*(</?{}[^>]*>)\s*</p>", ALLBLOCKS)),
+            wrapped_li: pattern(r"<p>(<li.+?)</p>"),
+            wrapped_blockquote: pattern(r"(?i)<p><blockquote([^>]*)>"),
+            block_after_p: pattern(&format!(r"<p>\s*(</?{}[^>]*>)", ALLBLOCKS)),
+            block_before_p: pattern(&format!(r"(</?{}[^>]*>)\s*</p>", ALLBLOCKS)),
+            br: pattern(r"(?<!<br />)\s*\n"),
+            br_after_block: pattern(&format!(r"(</?{}[^>]*>)\s*<br />", ALLBLOCKS)),
+            br_before_block: pattern(r"<br />(\s*</?(?:p|li|div|dl|dd|dt|th|pre|td|ul|ol)[^>]*>)"),
+            trailing_newline: pattern(r"\n</p>(\n?)\z"),
+        },
+        strip_tags: [
+            pattern(r"(?si)<(script|style)[^>]*?>.*?</\1>"),
+            pattern(r"(?s)<!--.*?-->|<[^>]*>"),
+        ],
+        words: pattern(r"[\n\r\t ]+"),
+    }
+});
+
+/// Applies WordPress's formatting filters. Shortcode-aware steps only treat
+/// the registered shortcode tags as shortcodes, as WordPress does.
+pub struct Formatter {
+    split: Regex,
+    shortcode: Option<Regex>,
+    unautop: Option<Regex>,
+}
+
+impl Formatter {
+    pub fn new(shortcode_tags: &[&str]) -> Self {
+        let tags = shortcode_tags
+            .iter()
+            .map(|tag| fancy_regex::escape(tag).into_owned())
+            .collect::<Vec<_>>()
+            .join("|");
+
+        let html = concat!(r"<(?:(?=!--)!(?>(?:-(?!->)[^\-]*)*)(?:-->)?", r"|[^>]*>?)");
+
+        if tags.is_empty() {
+            return Formatter {
+                split: pattern(html),
+                shortcode: None,
+                unautop: None,
+            };
+        }
+
+        // `_get_wptexturize_shortcode_regex()`.
+        let shortcode = format!(
+            r"\[[/\[]?(?:{})(?=[\s\]/])(?>(?:[^\[\]<>]+|<[^\[\]>]*>)*)\]\]?",
+            tags
+        );
+
+        let unautop = format!(
+            concat!(
+                r"<p>(?>(?:{spaces})*)",
+                r"(\[({tags})(?![A-Za-z0-9_-])[^\]/]*(?:/(?!\])[^\]/]*)*?",
+                r"(?:/\]|\](?:(?>[^\[]*)(?:\[(?!/\2\])(?>[^\[]*))*\[/\2\])?))",
+                r"(?>(?:{spaces})*)</p>"
+            ),
+            spaces = SPACES,
+            tags = tags
+        );
+
+        Formatter {
+            split: pattern(&format!("{}|{}", html, shortcode)),
+            shortcode: Some(pattern(&format!("^{}$", shortcode))),
+            unautop: Some(pattern(&unautop)),
+        }
+    }
+
+    /// The `the_content` filter chain up to (but not including) shortcode
+    /// rendering. `autop` is false for block content, where WordPress skips
+    /// `wpautop`.
+    pub fn the_content(&self, content: &str, autop: bool) -> String {
+        let mut content = self.wptexturize(content);
+        if autop {
+            content = wpautop(&content);
+        }
+        self.shortcode_unautop(&content).replace("]]>", "]]&gt;")
+    }
+
+    /// The `the_excerpt` filter chain.
+    pub fn the_excerpt(&self, excerpt: &str) -> String {
+        self.shortcode_unautop(&wpautop(&self.wptexturize(excerpt)))
+    }
+
+    /// Port of `wptexturize()`: curly quotes, apostrophes, primes, dashes,
+    /// ellipses and the multiplication sign, skipping HTML tags, shortcodes
+    /// and the contents of `<pre>`, `<code>` and similar elements.
+    pub fn wptexturize(&self, text: &str) -> String {
+        if text.is_empty() {
+            return String::new();
+        }
+
+        let patterns = &*PATTERNS;
+        let mut no_texturize_tags = Vec::new();
+        let mut no_texturize_shortcodes = Vec::new();
+        let mut output = String::with_capacity(text.len());
+
+        for (piece, is_delimiter) in split_delimiters(&self.split, text) {
+            if is_delimiter && piece.starts_with('<') {
+                if piece.starts_with("<!--") {
+                    output.push_str(piece);
+                } else {
+                    let element = replace_all(&patterns.ampersand, piece, "&#038;");
+                    pushpop_element(&element, &mut no_texturize_tags, &NO_TEXTURIZE_TAGS);
+                    output.push_str(&element);
+                }
+            } else if piece.trim().is_empty() {
+                output.push_str(piece);
+            } else if is_delimiter
+                && piece.starts_with('[')
+                && self
+                    .shortcode
+                    .as_ref()
+                    .is_some_and(|shortcode| shortcode.is_match(piece).unwrap_or(false))
+            {
+                if !piece.starts_with("[[") && !piece.ends_with("]]") {
+                    pushpop_element(
+                        piece,
+                        &mut no_texturize_shortcodes,
+                        &NO_TEXTURIZE_SHORTCODES,
+                    );
+                }
+                output.push_str(piece);
+            } else if no_texturize_shortcodes.is_empty() && no_texturize_tags.is_empty() {
+                output.push_str(&texturize_text(piece));
+            } else {
+                output.push_str(piece);
+            }
+        }
+
+        output
+    }
+
+    /// Port of `shortcode_unautop()`: removes the paragraph `wpautop` wraps
+    /// around shortcodes that stand on their own line.
+    pub fn shortcode_unautop(&self, text: &str) -> String {
+        match &self.unautop {
+            Some(unautop) => replace_all(unautop, text, "${1}"),
+            None => text.to_string(),
+        }
+    }
+}
+
+fn texturize_text(text: &str) -> String {
+    let patterns = &*PATTERNS;
+    let mut text = text.to_string();
+
+    for (from, to) in STATIC_REPLACEMENTS {
+        if text.contains(from) {
+            text = text.replace(from, to);
+        }
+    }
+
+    if text.contains('\'') {
+        for (regex, replacement) in &patterns.apos {
+            text = replace_all(regex, &text, replacement);
+        }
+        text = texturize_primes(&text, &patterns.single_primes);
+        text = text
+            .replace(APOS_FLAG, APOS)
+            .replace(OPEN_SQ_FLAG, OPENING_SINGLE_QUOTE);
+    }
+
+    if text.contains('"') {
+        for (regex, replacement) in &patterns.quote {
+            text = replace_all(regex, &text, replacement);
+        }
+        text = texturize_primes(&text, &patterns.double_primes);
+        text = text.replace(OPEN_Q_FLAG, OPENING_QUOTE);
+    }
+
+    if text.contains('-') {
+        for (regex, replacement) in &patterns.dash {
+            text = replace_all(regex, &text, replacement);
+        }
+    }
+
+    if patterns.has_times.is_match(&text).unwrap_or(false) {
+        text = replace_all(&patterns.times, &text, "${1}&#215;${2}");
+    }
+
+    replace_all(&patterns.ampersand, &text, "&#038;")
+}
+
+/// Port of `wptexturize_primes()`: decides, sentence by sentence, whether a
+/// quote after a digit closes a quotation or is a prime.
+fn texturize_primes(haystack: &str, primes: &Primes) -> String {
+    let sentences: Vec<String> = haystack
+        .split(primes.open_quote)
+        .enumerate()
+        .map(|(index, sentence)| {
+            let mut sentence = sentence.to_string();
+
+            if !sentence.contains(primes.needle) {
+                return sentence;
+            }
+
+            if index != 0 && !sentence.contains(primes.close_quote) {
+                let count = primes.quote.find_iter(&sentence).count();
+                sentence = replace_all(&primes.quote, &sentence, PRIME_FLAG);
+
+                if count > 1 {
+                    // This sentence appears to have multiple closing quotes.
+                    let replaced = primes.flag_no_digit.find_iter(&sentence).count();
+                    sentence = replace_all(&primes.flag_no_digit, &sentence, primes.close_quote);
+
+                    if replaced == 0 {
+                        // Assume the rightmost quote-period match is the end
+                        // of the quotation, or failing that the rightmost
+                        // candidate.
+                        let flag_period = format!("{}.", PRIME_FLAG);
+                        let position = sentence
+                            .rfind(&flag_period)
+                            .or_else(|| sentence.rfind(PRIME_FLAG));
+                        if let Some(position) = position {
+                            sentence.replace_range(
+                                position..position + PRIME_FLAG.len(),
+                                primes.close_quote,
+                            );
+                        }
+                    }
+
+                    sentence = replace_all(&primes.prime_pattern, &sentence, primes.prime);
+                    sentence = replace_all(&primes.flag_after_digit, &sentence, primes.prime);
+                    sentence = sentence.replace(PRIME_FLAG, primes.close_quote);
+                } else if count == 1 {
+                    // Only one closing quote candidate, so it has priority
+                    // over primes.
+                    sentence = sentence.replace(PRIME_FLAG, primes.close_quote);
+                    sentence = replace_all(&primes.prime_pattern, &sentence, primes.prime);
+                } else {
+                    sentence = replace_all(&primes.prime_pattern, &sentence, primes.prime);
+                }
+            } else {
+                sentence = replace_all(&primes.prime_pattern, &sentence, primes.prime);
+                sentence = replace_all(&primes.quote, &sentence, primes.close_quote);
+            }
+
+            if primes.needle == "\"" {
+                sentence = sentence.replace('"', primes.close_quote);
+            }
+
+            sentence
+        })
+        .collect();
+
+    sentences.join(primes.open_quote)
+}
+
+/// Port of `_wptexturize_pushpop_element()`.
+fn pushpop_element(text: &str, stack: &mut Vec<String>, disabled_elements: &[&str]) {
+    let bytes = text.as_bytes();
+
+    let (opening_tag, name_offset) = if bytes.len() > 1 && bytes[1] != b'/' {
+        (true, 1)
+    } else if stack.is_empty() {
+        return;
+    } else {
+        (false, 2)
+    };
+
+    let tag = match text.find(' ') {
+        Some(space) => text.get(name_offset..space),
+        None => text.get(name_offset..text.len().saturating_sub(1)),
+    }
+    .unwrap_or_default();
+
+    if disabled_elements.contains(&tag) {
+        if opening_tag {
+            stack.push(tag.to_string());
+        } else if stack.last().map(String::as_str) == Some(tag) {
+            stack.pop();
+        }
+    }
+}
+
+/// Splits text on the matches of `regex`, keeping the matches. Each piece is
+/// paired with whether it was a match.
+fn split_delimiters<'t>(regex: &Regex, text: &'t str) -> Vec<(&'t str, bool)> {
+    let mut pieces = Vec::new();
+    let mut last = 0;
+
+    for found in regex.find_iter(text).flatten() {
+        if found.start() > last {
+            pieces.push((&text[last..found.start()], false));
+        }
+        if !found.as_str().is_empty() {
+            pieces.push((found.as_str(), true));
+        }
+        last = found.end();
+    }
+
+    if last < text.len() {
+        pieces.push((&text[last..], false));
+    }
+
+    pieces
+}
+
+/// Port of `wpautop()`: turns double line breaks into paragraphs and single
+/// line breaks into `<br />`, leaving block-level elements and `<pre>`
+/// contents alone.
+pub fn wpautop(text: &str) -> String {
+    if text.trim().is_empty() {
+        return String::new();
+    }
+
+    let patterns = &PATTERNS.autop;
+
+    // Pad the end to make things a little easier.
+    let mut text = format!("{}\n", text);
+    let mut pre_tags = Vec::new();
+
+    if text.contains("<pre") {
+        let mut parts: Vec<&str> = text.split("</pre>").collect();
+        let last_part = parts.pop().unwrap_or_default();
+        let mut replaced = String::new();
+
+        for part in parts {
+            match part.find("<pre") {
+                Some(start) => {
+                    let name = format!("<pre wp-pre-tag-{}></pre>", pre_tags.len());
+                    replaced.push_str(&part[..start]);
+                    replaced.push_str(&name);
+                    pre_tags.push((name, format!("{}</pre>", &part[start..])));
+                }
+                None => replaced.push_str(part),
+            }
+        }
+
+        replaced.push_str(last_part);
+        text = replaced;
+    }
+
+    text = replace_all(&patterns.double_br, &text, "\n\n");
+    text = replace_all(&patterns.block_open, &text, "\n\n${1}");
+    text = replace_all(&patterns.block_close, &text, "${1}\n\n");
+    text = replace_all(&patterns.hr, &text, "${1}\n\n");
+    text = text.replace("\r\n", "\n").replace('\r', "\n");
+
+    // Newlines inside tags are protected with placeholders.
+    text = replace_in_html_tags(&text, "\n", " <!-- wpnl --> ");
+
+    if text.contains("<option") {
+        text = replace_all(&patterns.option[0], &text, "<option");
+        text = replace_all(&patterns.option[1], &text, "</option>");
+    }
+
+    if text.contains("</object>") {
+        text = replace_all(&patterns.object[0], &text, "${1}");
+        text = replace_all(&patterns.object[1], &text, "</object>");
+        text = replace_all(&patterns.object[2], &text, "${1}");
+    }
+
+    if text.contains("<source") || text.contains("<track") {
+        for regex in &patterns.media {
+            text = replace_all(regex, &text, "${1}");
+        }
+    }
+
+    if text.contains("<figcaption") {
+        text = replace_all(&patterns.figcaption[0], &text, "${1}");
+        text = replace_all(&patterns.figcaption[1], &text, "</figcaption>");
+    }
+
+    text = replace_all(&patterns.newlines, &text, "\n\n");
+
+    let mut paragraphs = String::new();
+    for paragraph in patterns.paragraphs.split(&text).flatten() {
+        if !paragraph.is_empty() {
+            paragraphs.push_str("<p>");
+            paragraphs.push_str(paragraph.trim_matches('\n'));
+            paragraphs.push_str("</p>\n");
+        }
+    }
+    text = paragraphs;
+
+    text = replace_all(&patterns.empty_paragraph, &text, "");
+    text = replace_all(&patterns.unclosed_paragraph, &text, "<p>${1}</p></${2}>");
+    text = replace_all(&patterns.wrapped_block, &text, "${1}");
+    text = replace_all(&patterns.wrapped_li, &text, "${1}");
+    text = replace_all(&patterns.wrapped_blockquote, &text, "<blockquote${1}><p>");
+    text = text.replace("</blockquote></p>", "</p></blockquote>");
+    text = replace_all(&patterns.block_after_p, &text, "${1}");
+    text = replace_all(&patterns.block_before_p, &text, "${1}");
+
+    // Newlines inside scripts, styles and inline SVG or MathML are kept.
+    text = preserve_newlines(&text);
+    text = text.replace("<br>", "<br />").replace("<br/>", "<br />");
+    text = replace_all(&patterns.br, &text, "<br />\n");
+    text = text.replace("<WPPreserveNewline />", "\n");
+
+    text = replace_all(&patterns.br_after_block, &text, "${1}");
+    text = replace_all(&patterns.br_before_block, &text, "${1}");
+    text = replace_all(&patterns.trailing_newline, &text, "</p>${1}");
+
+    for (name, pre_tag) in &pre_tags {
+        text = text.replace(name, pre_tag);
+    }
+
+    if text.contains("<!-- wpnl -->") {
+        text = text
+            .replace(" <!-- wpnl --> ", "\n")
+            .replace("<!-- wpnl -->", "\n");
+    }
+
+    text
+}
+
+/// Marks the newlines inside `<script>`, `<style>`, `<svg>` and `<math>`
+/// elements, like the `<(script|style|svg|math).*?</\1>` callback in
+/// `wpautop()`. The closing tag is found with a plain scan because the
+/// backreference makes the regex backtrack out of its limit on long input.
+fn preserve_newlines(text: &str) -> String {
+    const ELEMENTS: [&str; 4] = ["script", "style", "svg", "math"];
+
+    let mut output = String::with_capacity(text.len());
+    let mut unclosed = [false; ELEMENTS.len()];
+    let mut last = 0;
+    let mut position = 0;
+
+    while let Some(offset) = text[position..].find('<') {
+        let start = position + offset;
+        position = start + 1;
+
+        let Some(index) = ELEMENTS
+            .iter()
+            .position(|name| text[position..].starts_with(name))
+        else {
+            continue;
+        };
+        if unclosed[index] {
+            continue;
+        }
+
+        let name = ELEMENTS[index];
+        let closing = format!("</{}>", name);
+        match text[position + name.len()..].find(&closing) {
+            Some(offset) => {
+                let end = position + name.len() + offset + closing.len();
+                output.push_str(&text[last..start]);
+                output.push_str(&text[start..end].replace('\n', "<WPPreserveNewline />"));
+                last = end;
+                position = end;
+            }
+            // Without a closing tag further on, no later element of this kind
+            // can match either.
+            None => unclosed[index] = true,
+        }
+    }
+
+    output.push_str(&text[last..]);
+    output
+}
+
+/// Port of `wp_replace_in_html_tags()` for a single replacement.
+fn replace_in_html_tags(text: &str, from: &str, to: &str) -> String {
+    split_delimiters(&PATTERNS.html_split, text)
+        .into_iter()
+        .map(|(piece, is_tag)| {
+            if is_tag && piece.contains(from) {
+                piece.replace(from, to)
+            } else {
+                piece.to_string()
+            }
+        })
+        .collect()
+}
+
+/// Port of `wp_strip_all_tags()`: removes every tag, along with the
+/// contents of `<script>` and `<style>` elements.
+pub fn wp_strip_all_tags(text: &str) -> String {
+    let patterns = &*PATTERNS;
+
+    let text = replace_all(&patterns.strip_tags[0], text, "");
+    replace_all(&patterns.strip_tags[1], &text, "")
+        .trim()
+        .to_string()
+}
+
+/// How text is counted when it is trimmed, which WordPress translations
+/// set with `_x( 'words', 'Word count type' )`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WordCountType {
+    Words,
+    /// Every character but whitespace counts, for languages written
+    /// without spaces between words.
+    CharactersExcludingSpaces,
+}
+
+impl WordCountType {
+    /// The word count type of a locale such as `ja` or `zh_CN`. Only the
+    /// Chinese and Japanese translations count characters.
+    pub fn from_locale(locale: &str) -> Self {
+        let language = locale.split(['_', '-']).next().unwrap_or_default();
+
+        if language.eq_ignore_ascii_case("ja") || language.eq_ignore_ascii_case("zh") {
+            WordCountType::CharactersExcludingSpaces
+        } else {
+            WordCountType::Words
+        }
+    }
+}
+
+/// Port of `wp_trim_words()`: strips all tags and keeps the first
+/// `num_words` words, or characters for locales that count them, appending
+/// `more` when the text was cut.
+pub fn wp_trim_words(
+    text: &str,
+    num_words: usize,
+    more: &str,
+    count_type: WordCountType,
+) -> String {
+    let patterns = &*PATTERNS;
+
+    let text = wp_strip_all_tags(text);
+
+    if count_type == WordCountType::CharactersExcludingSpaces {
+        let text = replace_all(&patterns.words, &text, " ");
+        let text = text.trim_matches(' ');
+
+        return match text
+            .char_indices()
+            .filter(|(_, character)| !character.is_whitespace())
+            .nth(num_words)
+        {
+            Some((end, _)) => format!("{}{}", text[..end].trim_end(), more),
+            None => text.to_string(),
+        };
+    }
+
+    let mut words: Vec<&str> = patterns
+        .words
+        .split(&text)
+        .flatten()
+        .filter(|word| !word.is_empty())
+        .collect();
+
+    if words.len() > num_words {
+        words.truncate(num_words);
+        format!("{}{}", words.join(" "), more)
+    } else {
+        words.join(" ")
+    }
+}
+
+/// Han ideographs, kana, CJK punctuation and full-width forms, which are
+/// written without spaces between words.
+pub(crate) fn is_cjk(character: char) -> bool {
+    matches!(
+        character,
+        '\u{3000}'..='\u{30ff}'
+            | '\u{31f0}'..='\u{31ff}'
+            | '\u{3400}'..='\u{4dbf}'
+            | '\u{4e00}'..='\u{9fff}'
+            | '\u{f900}'..='\u{faff}'
+            | '\u{ff00}'..='\u{ffef}'
+            | '\u{20000}'..='\u{2fa1f}'
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wpautop_survives_unclosed_scripts() {
+        let text = "<script>\n".repeat(3000);
+        assert!(wpautop(&text).starts_with("<p><script><br />\n"));
+
+        let closed = format!("{}</script>", text);
+        assert!(!wpautop(&closed).contains("<br />"));
+    }
+
+    #[test]
+    fn wpautop_keeps_newlines_in_scripts() {
+        let text = "<script type=\"text/javascript\">\nvar dummy = 1;\n</script>";
+        assert_eq!(wpautop(text).trim(), format!("<p>{}</p>", text));
+    }
+
+    #[test]
+    fn wp_trim_words_counts_words() {
+        let text = "<p>One <strong>two</strong>\n three four</p>";
+        let trim = |num_words| wp_trim_words(text, num_words, "...", WordCountType::Words);
+        assert_eq!(trim(3), "One two three...");
+        assert_eq!(trim(4), "One two three four");
+
+        // Text without spaces is a single word unless the locale counts
+        // characters.
+        let text = "这是一个很长的中文句子 和";
+        assert_eq!(
+            wp_trim_words(text, 1, "...", WordCountType::Words),
+            "这是一个很长的中文句子..."
+        );
+    }
+
+    #[test]
+    fn wp_trim_words_counts_characters_excluding_spaces() {
+        let trim = |text, num_words| {
+            wp_trim_words(
+                text,
+                num_words,
+                "...",
+                WordCountType::CharactersExcludingSpaces,
+            )
+        };
+        let text = "<p>这是一个很长的中文句子</p>";
+        assert_eq!(trim(text, 4), "这是一个...");
+        assert_eq!(trim(text, 11), "这是一个很长的中文句子");
+        assert_eq!(trim("日本語 の\n文章", 5), "日本語 の 文...");
+        assert_eq!(trim("日本語 の\n文章", 6), "日本語 の 文章");
+        assert_eq!(trim("日本語 の 文章", 4), "日本語 の...");
+    }
+
+    #[test]
+    fn word_count_type_follows_the_locale() {
+        assert_eq!(
+            WordCountType::from_locale("ja"),
+            WordCountType::CharactersExcludingSpaces
+        );
+        assert_eq!(
+            WordCountType::from_locale("zh_TW"),
+            WordCountType::CharactersExcludingSpaces
+        );
+        assert_eq!(
+            WordCountType::from_locale("zh-hans"),
+            WordCountType::CharactersExcludingSpaces
+        );
+        assert_eq!(WordCountType::from_locale("ko_KR"), WordCountType::Words);
+        assert_eq!(WordCountType::from_locale(""), WordCountType::Words);
+    }
+
+    fn texturize(text: &str) -> String {
+        Formatter::new(&[]).wptexturize(text)
+    }
+
+    // Cases from WordPress core's `tests/formatting/wpTexturize.php`.
+    #[test]
+    fn wptexturize_dashes() {
+        assert_eq!(texturize("Hey there--you"), "Hey there&#8211;you");
+        assert_eq!(texturize("Hey there---you"), "Hey there&#8212;you");
+        assert_eq!(texturize("Hey there - you"), "Hey there &#8211; you");
+        assert_eq!(texturize("Hey there -- you"), "Hey there &#8212; you");
+        assert_eq!(texturize("xn--example"), "xn--example");
+    }
+
+    #[test]
+    fn wptexturize_quotes() {
+        assert_eq!(
+            texturize(r#""Quoted String""#),
+            "&#8220;Quoted String&#8221;"
+        );
+        assert_eq!(
+            texturize(r#"He said "hi" and left"#),
+            "He said &#8220;hi&#8221; and left"
+        );
+        assert_eq!(
+            texturize(r#"&nbsp;"Testing""#),
+            "&nbsp;&#8220;Testing&#8221;"
+        );
+    }
+
+    #[test]
+    fn wptexturize_apostrophes() {
+        assert_eq!(texturize("test's"), "test&#8217;s");
+        assert_eq!(texturize("'test's"), "&#8216;test&#8217;s");
+        assert_eq!(texturize("'test's'"), "&#8216;test&#8217;s&#8217;");
+        assert_eq!(texturize("Class of '99"), "Class of &#8217;99");
+        assert_eq!(texturize("Class of '99's"), "Class of &#8217;99&#8217;s");
+        assert_eq!(texturize("'Class of 99'"), "&#8216;Class of 99&#8217;");
+        assert_eq!(texturize(r#""Class of 99""#), "&#8220;Class of 99&#8221;");
+        assert_eq!(texturize("'em"), "&#8217;em");
+    }
+
+    #[test]
+    fn wptexturize_primes_and_symbols() {
+        assert_eq!(texturize(r#"9' 38""#), "9&#8242; 38&#8243;");
+        assert_eq!(texturize("14x14"), "14&#215;14");
+        assert_eq!(texturize("0x1234"), "0x1234");
+        assert_eq!(texturize("Wait..."), "Wait&#8230;");
+        assert_eq!(texturize("Name (tm)"), "Name &#8482;");
+        assert_eq!(texturize("Ben & Jerry"), "Ben &#038; Jerry");
+        assert_eq!(texturize("Ben &amp; Jerry"), "Ben &amp; Jerry");
+    }
+
+    #[test]
+    fn wptexturize_skips_code_and_tags() {
+        assert_eq!(texturize("<pre>---&nbsp;</pre>"), "<pre>---&nbsp;</pre>");
+        assert_eq!(
+            texturize("<code>'quoted'</code> 'quoted'"),
+            "<code>'quoted'</code> &#8216;quoted&#8217;"
+        );
+        assert_eq!(
+            texturize(r#"<a title="'quoted'">x</a>"#),
+            r#"<a title="'quoted'">x</a>"#
+        );
+        assert_eq!(texturize("<!-- -- -->"), "<!-- -- -->");
+    }
+
+    #[test]
+    fn wptexturize_skips_registered_shortcodes() {
+        let formatter = Formatter::new(&["code", "caption"]);
+        assert_eq!(
+            formatter.wptexturize("[code]---[/code] ---"),
+            "[code]---[/code] &#8212;"
+        );
+        assert_eq!(
+            formatter.wptexturize(r#"[caption id="a"]'quoted'[/caption]"#),
+            r#"[caption id="a"]&#8216;quoted&#8217;[/caption]"#
+        );
+    }
+
+    // Cases from WordPress core's `tests/formatting/wpAutop.php`.
+    #[test]
+    fn wpautop_paragraphs_and_line_breaks() {
+        assert_eq!(wpautop("foo\nbar"), "<p>foo<br />\nbar</p>\n");
+        assert_eq!(wpautop("foo\n\nbar"), "<p>foo</p>\n<p>bar</p>\n");
+        assert_eq!(
+            wpautop("line 1<br>\nline 2<br/>\nline 3<br />\nline 4\nline 5").trim(),
+            "<p>line 1<br />\nline 2<br />\nline 3<br />\nline 4<br />\nline 5</p>"
+        );
+        assert_eq!(
+            wpautop("line 1<br>\n<br/>\nline 2<br/>\n<br />\nline 3").trim(),
+            "<p>line 1</p>\n<p>line 2</p>\n<p>line 3</p>"
+        );
+        assert_eq!(wpautop("   \n "), "");
+    }
+
+    #[test]
+    fn wpautop_block_elements() {
+        assert_eq!(wpautop("a<div>b</div>").trim(), "<p>a</p>\n<div>b</div>");
+        assert_eq!(
+            wpautop("<blockquote>foo</blockquote>").trim(),
+            "<blockquote><p>foo</p></blockquote>"
+        );
+        assert_eq!(
+            wpautop("paragraph1<hr>paragraph2").trim(),
+            "<p>paragraph1</p>\n<hr>\n<p>paragraph2</p>"
+        );
+        assert_eq!(
+            wpautop("<a>foo</a>\n<em>bar</em>").trim(),
+            "<p><a>foo</a><br />\n<em>bar</em></p>"
+        );
+    }
+
+    #[test]
+    fn wpautop_skips_pre_and_tag_newlines() {
+        let code = "<pre>line 1\n\nline 2</pre>";
+        assert_eq!(wpautop(code).trim(), code);
+        assert_eq!(
+            wpautop("Hello <a\nhref='world'>"),
+            "<p>Hello <a\nhref='world'></p>\n"
+        );
+        assert_eq!(
+            wpautop("Hello <!-- a\nhref='world' -->"),
+            "<p>Hello <!-- a\nhref='world' --></p>\n"
+        );
+        let input = concat!(
+            r#"Username: <input type="text" id="username" name="username" /><br />"#,
+            r#"Password: <input type="password" id="password1" name="password1" />"#
+        );
+        assert_eq!(wpautop(input).trim(), format!("<p>{}</p>", input));
+    }
+}
@@ -0,0 +1,8 @@
+pub mod acf;
+pub mod blocks;
+pub mod diff;
+pub mod formatting;
+pub mod permalinks;
+pub mod php;
+pub mod seo;
+pub mod shortcodes;
@@ -0,0 +1,698 @@
+use chrono::{Datelike, NaiveDateTime, Timelike};
+use fancy_regex::Regex;
+use percent_encoding::percent_decode_str;
+use std::collections::HashMap;
+use url::{form_urlencoded, Url};
+
+// Post types whose links WordPress builds itself; every other type gets
+// links under its rewrite base.
+const BUILTIN_POST_TYPES: [&str; 3] = ["post", "page", "attachment"];
+
+// Statuses that `get_permalink()` always links to with the plain form.
+const UNPUBLISHED_STATUSES: [&str; 4] = ["draft", "pending", "auto-draft", "future"];
+
+/// The site's permalink settings, read from `wp_options`.
+#[derive(Clone, Debug)]
+pub struct Permalinks {
+    home: String,
+    home_path: String,
+    structure: String,
+    structure_pattern: Option<(Regex, Vec<String>)>,
+    category_base: String,
+    tag_base: String,
+    front_page: Option<u64>,
+    default_category: u64,
+    /// `(post_type, base)` pairs, the most specific base first.
+    post_type_bases: Vec<(String, String)>,
+}
+
+/// What a post's link is built from.
+pub struct PostLink<'a> {
+    pub id: u64,
+    pub post_type: &'a str,
+    pub post_status: &'a str,
+    pub date: Option<NaiveDateTime>,
+    /// Slugs from the top-level ancestor down to the post itself.
+    pub path: Vec<&'a str>,
+    /// Slug path of the post's main category, as in `%category%`.
+    pub category: &'a str,
+    /// Nicename of the post's author, as in `%author%`.
+    pub author: &'a str,
+}
+
+/// A candidate for what a path points at. `Permalinks::routes` returns them
+/// in the order WordPress tries its rewrite rules.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Route {
+    FrontPage,
+    /// `?p=` or `?page_id=` links.
+    PostId(u64),
+    /// A term, with the slugs of its hierarchy.
+    Term {
+        taxonomy: &'static str,
+        path: Vec<String>,
+    },
+    Author(String),
+    /// A post of a custom post type, with the slugs of its hierarchy.
+    PostType {
+        post_type: String,
+        path: Vec<String>,
+    },
+    /// A page, with the slugs of its hierarchy.
+    Page(Vec<String>),
+    Post(PostMatch),
+}
+
+/// The parts of a post link matched against `permalink_structure`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PostMatch {
+    pub post_id: Option<u64>,
+    pub slug: Option<String>,
+    pub year: Option<i32>,
+    pub month: Option<u32>,
+    pub day: Option<u32>,
+    pub author: Option<String>,
+}
+
+impl PostMatch {
+    /// Whether the date parts of the link agree with the post date, as the
+    /// `year`, `monthnum` and `day` query vars require.
+    pub fn matches_date(&self, date: Option<NaiveDateTime>) -> bool {
+        let Some(date) = date else {
+            return self.year.is_none() && self.month.is_none() && self.day.is_none();
+        };
+
+        self.year.is_none_or(|year| year == date.year())
+            && self.month.is_none_or(|month| month == date.month())
+            && self.day.is_none_or(|day| day == date.day())
+    }
+}
+
+impl Permalinks {
+    pub fn new(
+        options: &HashMap<String, String>,
+        post_type_bases: &HashMap<String, String>,
+    ) -> Self {
+        let option = |name: &str| options.get(name).map(|value| value.trim()).unwrap_or("");
+        let base = |name: &str, default: &str| {
+            let value = option(name).trim_matches('/');
+            if value.is_empty() {
+                default.to_string()
+            } else {
+                value.to_string()
+            }
+        };
+
+        let home = option("home").trim_end_matches('/').to_string();
+        let home_path = Url::parse(&home)
+            .map(|url| url.path().trim_end_matches('/').to_string())
+            .unwrap_or_default();
+
+        let structure = option("permalink_structure").to_string();
+
+        // Longer bases are tried first so `shop/featured` wins over `shop`;
+        // the rest of the order only keeps resolution deterministic.
+        let mut post_type_bases: Vec<(String, String)> = post_type_bases
+            .iter()
+            .map(|(post_type, base)| (post_type.clone(), base.clone()))
+            .collect();
+        post_type_bases.sort_by(|(type_a, base_a), (type_b, base_b)| {
+            base_b
+                .split('/')
+                .count()
+                .cmp(&base_a.split('/').count())
+                .then_with(|| base_a.cmp(base_b))
+                .then_with(|| type_a.cmp(type_b))
+        });
+
+        let front_page = if option("show_on_front") == "page" {
+            option("page_on_front").parse().ok().filter(|id| *id != 0)
+        } else {
+            None
+        };
+
+        Permalinks {
+            home,
+            home_path,
+            structure_pattern: structure_pattern(&structure),
+            structure,
+            category_base: base("category_base", "category"),
+            tag_base: base("tag_base", "tag"),
+            front_page,
+            default_category: option("default_category").parse().unwrap_or(0),
+            post_type_bases,
+        }
+    }
+
+    /// The option names `new` reads.
+    pub fn option_names() -> Vec<String> {
+        [
+            "home",
+            "permalink_structure",
+            "category_base",
+            "tag_base",
+            "show_on_front",
+            "page_on_front",
+            "default_category",
+        ]
+        .iter()
+        .map(|name| name.to_string())
+        .collect()
+    }
+
+    /// Whether the structure contains a tag such as `%category%`.
+    pub fn uses(&self, tag: &str) -> bool {
+        self.structure.contains(tag)
+    }
+
+    pub fn front_page(&self) -> Option<u64> {
+        self.front_page
+    }
+
+    pub fn default_category(&self) -> u64 {
+        self.default_category
+    }
+
+    /// Builds the link `get_permalink()` would return for a post.
+    pub fn post_link(&self, post: &PostLink) -> String {
+        let slug = post.path.last().copied().unwrap_or("");
+
+        if post.post_type == "page" && self.front_page == Some(post.id) {
+            return format!("{}/", self.home);
+        }
+
+        let pretty = !self.structure.is_empty()
+            && !slug.is_empty()
+            && post.post_type != "attachment"
+            && !UNPUBLISHED_STATUSES.contains(&post.post_status);
+
+        if !pretty {
+            return match post.post_type {
+                "post" => format!("{}/?p={}", self.home, post.id),
+                "page" => format!("{}/?page_id={}", self.home, post.id),
+                "attachment" => format!("{}/?attachment_id={}", self.home, post.id),
+                post_type => format!("{}/?post_type={}&p={}", self.home, post_type, post.id),
+            };
+        }
+
+        match post.post_type {
+            "post" => format!("{}{}", self.home, self.expand_structure(post, slug)),
+            "page" => self.link(&post.path.join("/")),
+            post_type => self.link(&format!(
+                "{}/{}",
+                self.post_type_base(post_type),
+                post.path.join("/")
+            )),
+        }
+    }
+
+    /// Builds the link `get_term_link()` would return for a term. `path` is
+    /// the slug path of categories, top-level first, and the slug of other
+    /// terms.
+    pub fn term_link(&self, taxonomy: &str, term_id: u64, path: &str) -> String {
+        if self.structure.is_empty() {
+            return match taxonomy {
+                "category" => format!("{}/?cat={}", self.home, term_id),
+                "post_tag" => format!("{}/?tag={}", self.home, path),
+                taxonomy => format!("{}/?taxonomy={}&term={}", self.home, taxonomy, path),
+            };
+        }
+
+        match taxonomy {
+            "category" => self.link(&format!("{}/{}", self.category_base, path)),
+            "post_tag" => self.link(&format!("{}/{}", self.tag_base, path)),
+            taxonomy => self.link(&format!("{}/{}", taxonomy, path)),
+        }
+    }
+
+    fn expand_structure(&self, post: &PostLink, slug: &str) -> String {
+        let date = post.date.unwrap_or_default();
+        let mut link = self.structure.clone();
+
+        for (tag, value) in [
+            ("%year%", date.year().to_string()),
+            ("%monthnum%", format!("{:02}", date.month())),
+            ("%day%", format!("{:02}", date.day())),
+            ("%hour%", format!("{:02}", date.hour())),
+            ("%minute%", format!("{:02}", date.minute())),
+            ("%second%", format!("{:02}", date.second())),
+            ("%post_id%", post.id.to_string()),
+            ("%postname%", slug.to_string()),
+            ("%category%", post.category.to_string()),
+            ("%author%", post.author.to_string()),
+        ] {
+            link = link.replace(tag, &value);
+        }
+
+        link
+    }
+
+    // Links other than posts follow the structure's trailing slash, like
+    // `user_trailingslashit()`.
+    fn link(&self, path: &str) -> String {
+        if self.structure.ends_with('/') {
+            format!("{}/{}/", self.home, path)
+        } else {
+            format!("{}/{}", self.home, path)
+        }
+    }
+
+    fn post_type_base(&self, post_type: &str) -> String {
+        self.post_type_bases
+            .iter()
+            .find(|(base_type, _)| base_type == post_type)
+            .map_or_else(|| post_type.to_string(), |(_, base)| base.clone())
+    }
+
+    /// Works out what a path or full URL on the site may point at. The path
+    /// is matched after percent-decoding, so `/%E6%96%B0%E9%97%BB/` and
+    /// `/新闻/` resolve alike.
+    pub fn routes(&self, path: &str) -> Vec<Route> {
+        let (path, query) = match Url::parse(path) {
+            Ok(url) => (url.path().to_string(), url.query().map(str::to_string)),
+            Err(_) => match path.split_once('?') {
+                Some((path, query)) => (path.to_string(), Some(query.to_string())),
+                None => (path.to_string(), None),
+            },
+        };
+
+        if let Some(query) = query {
+            for (name, value) in form_urlencoded::parse(query.as_bytes()) {
+                if matches!(name.as_ref(), "p" | "page_id") {
+                    if let Ok(id) = value.parse() {
+                        return vec![Route::PostId(id)];
+                    }
+                }
+            }
+        }
+
+        let path = path
+            .strip_prefix(&self.home_path)
+            .filter(|rest| rest.is_empty() || rest.starts_with('/'))
+            .unwrap_or(&path);
+
+        let segments: Vec<String> = path
+            .split('/')
+            .filter(|segment| !segment.is_empty())
+            .map(|segment| percent_decode_str(segment).decode_utf8_lossy().into_owned())
+            .collect();
+
+        if segments.is_empty() {
+            return vec![Route::FrontPage];
+        }
+
+        if self.structure.is_empty() {
+            return Vec::new();
+        }
+
+        let mut routes = Vec::new();
+
+        if let [first, slug] = segments.as_slice() {
+            if first == "author" {
+                routes.push(Route::Author(slug.clone()));
+            } else if *first == self.tag_base {
+                routes.push(Route::Term {
+                    taxonomy: "post_tag",
+                    path: vec![slug.clone()],
+                });
+            }
+        }
+
+        let base_length = self.category_base.split('/').count();
+        if segments.len() > base_length && segments[..base_length].join("/") == self.category_base {
+            routes.push(Route::Term {
+                taxonomy: "category",
+                path: segments[base_length..].to_vec(),
+            });
+        }
+
+        for (post_type, base) in &self.post_type_bases {
+            let base_length = base.split('/').count();
+            if segments.len() > base_length && segments[..base_length].join("/") == *base {
+                routes.push(Route::PostType {
+                    post_type: post_type.clone(),
+                    path: segments[base_length..].to_vec(),
+                });
+            }
+        }
+
+        // Types without a configured base use their own name, which is never
+        // numeric and never one of the bases above.
+        let first = &segments[0];
+        if routes.is_empty()
+            && segments.len() > 1
+            && !first.chars().all(|c| c.is_ascii_digit())
+            && !BUILTIN_POST_TYPES.contains(&first.as_str())
+            && !self
+                .post_type_bases
+                .iter()
+                .any(|(post_type, _)| post_type == first)
+        {
+            routes.push(Route::PostType {
+                post_type: first.clone(),
+                path: segments[1..].to_vec(),
+            });
+        }
+
+        // WordPress checks for a page with the path before matching posts,
+        // so a page wins when both would fit.
+        routes.push(Route::Page(segments.clone()));
+
+        if let Some(post) = self.match_structure(&segments) {
+            routes.push(Route::Post(post));
+        }
+
+        routes
+    }
+
+    fn match_structure(&self, segments: &[String]) -> Option<PostMatch> {
+        let (pattern, tags) = self.structure_pattern.as_ref()?;
+        let path = format!("/{}", segments.join("/"));
+        let captures = pattern.captures(&path).ok()??;
+
+        let mut post = PostMatch::default();
+        for (index, tag) in tags.iter().enumerate() {
+            let value = captures.get(index + 1)?.as_str();
+            match tag.as_str() {
+                "year" => post.year = Some(value.parse().ok()?),
+                "monthnum" => post.month = Some(value.parse().ok()?),
+                "day" => post.day = Some(value.parse().ok()?),
+                "post_id" => post.post_id = Some(value.parse().ok()?),
+                "postname" => post.slug = Some(value.to_string()),
+                "author" => post.author = Some(value.to_string()),
+                _ => {}
+            }
+        }
+
+        (post.post_id.is_some() || post.slug.is_some()).then_some(post)
+    }
+}
+
+// Turns `permalink_structure` into a pattern over the decoded path, along
+// with the tag captured by each group.
+fn structure_pattern(structure: &str) -> Option<(Regex, Vec<String>)> {
+    let structure = structure.trim_end_matches('/');
+    if structure.is_empty() {
+        return None;
+    }
+
+    let mut pattern = String::from("^");
+    let mut tags = Vec::new();
+    let mut rest = structure;
+
+    while let Some(start) = rest.find('%') {
+        let Some(length) = rest[start + 1..].find('%') else {
+            break;
+        };
+        let tag = &rest[start + 1..start + 1 + length];
+
+        pattern.push_str(&fancy_regex::escape(&rest[..start]));
+        pattern.push_str(match tag {
+            "year" => r"([0-9]{4})",
+            "monthnum" | "day" | "hour" | "minute" | "second" => r"([0-9]{1,2})",
+            "post_id" => r"([0-9]+)",
+            "category" => r"(.+?)",
+            _ => r"([^/]+)",
+        });
+        tags.push(tag.to_string());
+
+        rest = &rest[start + length + 2..];
+    }
+
+    pattern.push_str(&fancy_regex::escape(rest));
+    pattern.push_str("/?$");
+
+    Regex::new(&pattern).ok().map(|pattern| (pattern, tags))
+}
+
+/// The forms a slug from a URL may be stored in: WordPress keeps non-ASCII
+/// slugs percent-encoded in lowercase, as `sanitize_title()` leaves them.
+pub fn slug_forms(slug: &str) -> Vec<String> {
+    let mut encoded = String::with_capacity(slug.len());
+    for c in slug.chars() {
+        if c.is_ascii() {
+            encoded.push(c.to_ascii_lowercase());
+        } else {
+            let mut buffer = [0; 4];
+            for byte in c.encode_utf8(&mut buffer).bytes() {
+                encoded.push_str(&format!("%{:02x}", byte));
+            }
+        }
+    }
+
+    if encoded == slug {
+        vec![encoded]
+    } else {
+        vec![slug.to_string(), encoded]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn permalinks(home: &str, structure: &str, bases: &[(&str, &str)]) -> Permalinks {
+        let options = [("home", home), ("permalink_structure", structure)]
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect();
+        let bases = bases
+            .iter()
+            .map(|(post_type, base)| (post_type.to_string(), base.to_string()))
+            .collect();
+        Permalinks::new(&options, &bases)
+    }
+
+    fn post<'a>(id: u64, post_type: &'a str, path: Vec<&'a str>) -> PostLink<'a> {
+        PostLink {
+            id,
+            post_type,
+            post_status: "publish",
+            date: NaiveDate::from_ymd_opt(2024, 5, 7).and_then(|date| date.and_hms_opt(8, 30, 0)),
+            path,
+            category: "news/local",
+            author: "jane",
+        }
+    }
+
+    fn segments(path: &[&str]) -> Vec<String> {
+        path.iter().map(|segment| segment.to_string()).collect()
+    }
+
+    #[test]
+    fn builds_post_links_from_the_structure() {
+        let links = permalinks("https://example.com", "/%year%/%monthnum%/%postname%/", &[]);
+        assert_eq!(
+            links.post_link(&post(12, "post", vec!["hello"])),
+            "https://example.com/2024/05/hello/"
+        );
+
+        let links = permalinks("https://example.com", "/%category%/%postname%", &[]);
+        assert_eq!(
+            links.post_link(&post(12, "post", vec!["hello"])),
+            "https://example.com/news/local/hello"
+        );
+        assert_eq!(
+            links.post_link(&post(3, "page", vec!["about", "team"])),
+            "https://example.com/about/team"
+        );
+
+        let links = permalinks("https://example.com", "/archives/%post_id%", &[]);
+        assert_eq!(
+            links.post_link(&post(12, "post", vec!["hello"])),
+            "https://example.com/archives/12"
+        );
+    }
+
+    #[test]
+    fn builds_plain_links_without_a_structure() {
+        let links = permalinks("https://example.com/", "", &[]);
+        assert_eq!(
+            links.post_link(&post(12, "post", vec!["hello"])),
+            "https://example.com/?p=12"
+        );
+        assert_eq!(
+            links.post_link(&post(3, "page", vec!["about"])),
+            "https://example.com/?page_id=3"
+        );
+        assert_eq!(
+            links.term_link("category", 4, "news/local"),
+            "https://example.com/?cat=4"
+        );
+    }
+
+    #[test]
+    fn builds_term_and_custom_post_type_links() {
+        let links = permalinks(
+            "https://example.com",
+            "/%postname%/",
+            &[("book", "library/books")],
+        );
+        assert_eq!(
+            links.term_link("category", 4, "news/local"),
+            "https://example.com/category/news/local/"
+        );
+        assert_eq!(
+            links.term_link("post_tag", 5, "rust"),
+            "https://example.com/tag/rust/"
+        );
+        assert_eq!(
+            links.post_link(&post(20, "book", vec!["dune"])),
+            "https://example.com/library/books/dune/"
+        );
+        assert_eq!(
+            links.post_link(&post(21, "movie", vec!["alien"])),
+            "https://example.com/movie/alien/"
+        );
+    }
+
+    #[test]
+    fn routes_date_and_post_id_structures() {
+        let links = permalinks("https://example.com", "/%year%/%monthnum%/%postname%/", &[]);
+        let routes = links.routes("/2024/05/hello/");
+        assert_eq!(
+            routes.last(),
+            Some(&Route::Post(PostMatch {
+                slug: Some("hello".to_string()),
+                year: Some(2024),
+                month: Some(5),
+                ..PostMatch::default()
+            }))
+        );
+
+        let links = permalinks("https://example.com", "/archives/%post_id%", &[]);
+        assert_eq!(
+            links.routes("/archives/12").last(),
+            Some(&Route::Post(PostMatch {
+                post_id: Some(12),
+                ..PostMatch::default()
+            }))
+        );
+        assert!(!links
+            .routes("/archives/twelve")
+            .iter()
+            .any(|route| matches!(route, Route::Post(_))));
+    }
+
+    #[test]
+    fn routes_nested_category_paths() {
+        let links = permalinks("https://example.com", "/%category%/%postname%/", &[]);
+
+        assert_eq!(
+            links.routes("/category/news/local/"),
+            vec![
+                Route::Term {
+                    taxonomy: "category",
+                    path: segments(&["news", "local"]),
+                },
+                Route::Page(segments(&["category", "news", "local"])),
+                Route::Post(PostMatch {
+                    slug: Some("local".to_string()),
+                    ..PostMatch::default()
+                }),
+            ]
+        );
+        assert_eq!(
+            links.routes("/news/local/hello/").last(),
+            Some(&Route::Post(PostMatch {
+                slug: Some("hello".to_string()),
+                ..PostMatch::default()
+            }))
+        );
+    }
+
+    #[test]
+    fn routes_plain_links_and_the_front_page() {
+        let links = permalinks("https://example.com", "/%postname%/", &[]);
+        assert_eq!(links.routes("/?p=12"), vec![Route::PostId(12)]);
+        assert_eq!(
+            links.routes("https://example.com/?page_id=3"),
+            vec![Route::PostId(3)]
+        );
+        assert_eq!(links.routes("/"), vec![Route::FrontPage]);
+    }
+
+    #[test]
+    fn routes_under_a_home_subdirectory() {
+        let links = permalinks("https://example.com/blog", "/%postname%/", &[]);
+        assert_eq!(links.routes("/blog/"), vec![Route::FrontPage]);
+        assert_eq!(
+            links.routes("https://example.com/blog/hello/"),
+            vec![
+                Route::Page(segments(&["hello"])),
+                Route::Post(PostMatch {
+                    slug: Some("hello".to_string()),
+                    ..PostMatch::default()
+                }),
+            ]
+        );
+        assert_eq!(
+            links.post_link(&post(12, "post", vec!["hello"])),
+            "https://example.com/blog/hello/"
+        );
+    }
+
+    #[test]
+    fn pages_are_tried_before_posts() {
+        let links = permalinks("https://example.com", "/%postname%/", &[]);
+        let routes = links.routes("/about/");
+        assert_eq!(
+            routes,
+            vec![
+                Route::Page(segments(&["about"])),
+                Route::Post(PostMatch {
+                    slug: Some("about".to_string()),
+                    ..PostMatch::default()
+                }),
+            ]
+        );
+    }
+
+    #[test]
+    fn decodes_percent_encoded_cjk_slugs() {
+        let links = permalinks("https://example.com", "/%postname%/", &[]);
+        assert_eq!(
+            links.routes("/%E6%96%B0%E9%97%BB/")[0],
+            Route::Page(segments(&["新闻"]))
+        );
+        assert_eq!(
+            slug_forms("新闻"),
+            vec!["新闻".to_string(), "%e6%96%b0%e9%97%bb".to_string()]
+        );
+        assert_eq!(slug_forms("hello"), vec!["hello".to_string()]);
+        assert_eq!(
+            slug_forms("Hello"),
+            vec!["Hello".to_string(), "hello".to_string()]
+        );
+    }
+
+    #[test]
+    fn routes_custom_post_type_bases_longest_first() {
+        let links = permalinks(
+            "https://example.com",
+            "/%postname%/",
+            &[("product", "shop"), ("featured", "shop/featured")],
+        );
+        assert_eq!(
+            links.routes("/shop/featured/lamp/")[..2],
+            [
+                Route::PostType {
+                    post_type: "featured".to_string(),
+                    path: segments(&["lamp"]),
+                },
+                Route::PostType {
+                    post_type: "product".to_string(),
+                    path: segments(&["featured", "lamp"]),
+                },
+            ]
+        );
+        assert_eq!(
+            links.routes("/movie/alien/")[0],
+            Route::PostType {
+                post_type: "movie".to_string(),
+                path: segments(&["alien"]),
+            }
+        );
+    }
+}
@@ -0,0 +1,310 @@
+use serde_json::{Map, Number, Value};
+
+// PHP nests arrays freely, but anything deeper than this is almost certainly
+// corrupt data and would otherwise risk overflowing the stack.
+const MAX_DEPTH: usize = 64;
+
+/// Decodes a value produced by PHP's `serialize()` into JSON.
+///
+/// Arrays with sequential integer keys starting at zero become JSON arrays,
+/// every other array or object becomes a JSON object that keeps PHP's key
+/// order. String lengths are byte lengths, exactly as PHP writes them.
+/// Returns `None` when the input is not a complete serialized value.
+pub fn unserialize(input: &str) -> Option<Value> {
+    let mut parser = Parser {
+        input: input.as_bytes(),
+        pos: 0,
+    };
+
+    let value = parser.parse_value(0)?;
+
+    if parser.pos == parser.input.len() {
+        Some(value)
+    } else {
+        None
+    }
+}
+
+/// Equivalent of WordPress's `maybe_unserialize()`: serialized input is
+/// decoded, anything else (including malformed data) is returned as a string.
+pub fn maybe_unserialize(input: &str) -> Value {
+    if is_serialized(input) {
+        if let Some(value) = unserialize(input.trim()) {
+            return value;
+        }
+    }
+
+    Value::String(input.to_string())
+}
+
+/// Cheap check mirroring WordPress's `is_serialized()`, used to avoid running
+/// the decoder on plain strings.
+pub fn is_serialized(input: &str) -> bool {
+    let input = input.trim();
+
+    if input == "N;" {
+        return true;
+    }
+
+    let bytes = input.as_bytes();
+    if bytes.len() < 4 || bytes[1] != b':' {
+        return false;
+    }
+
+    match bytes[0] {
+        b'a' | b'O' => input.ends_with('}'),
+        b's' => input.ends_with("\";"),
+        b'b' | b'i' | b'd' | b'E' => input.ends_with(';'),
+        _ => false,
+    }
+}
+
+struct Parser<'a> {
+    input: &'a [u8],
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn parse_value(&mut self, depth: usize) -> Option<Value> {
+        if depth > MAX_DEPTH {
+            return None;
+        }
+
+        let tag = self.next()?;
+
+        match tag {
+            b'N' => {
+                self.expect(b';')?;
+                Some(Value::Null)
+            }
+            b'b' => {
+                self.expect(b':')?;
+                let value = self.read_until(b';')?;
+                match value {
+                    b"0" => Some(Value::Bool(false)),
+                    b"1" => Some(Value::Bool(true)),
+                    _ => None,
+                }
+            }
+            b'i' => {
+                self.expect(b':')?;
+                let value = self.read_until(b';')?;
+                let value = std::str::from_utf8(value).ok()?.parse::<i64>().ok()?;
+                Some(Value::Number(value.into()))
+            }
+            b'd' => {
+                self.expect(b':')?;
+                let value = self.read_until(b';')?;
+                let value = match value {
+                    b"INF" => f64::INFINITY,
+                    b"-INF" => f64::NEG_INFINITY,
+                    b"NAN" => f64::NAN,
+                    _ => std::str::from_utf8(value).ok()?.parse::<f64>().ok()?,
+                };
+                // JSON has no representation for infinities or NaN.
+                Some(Number::from_f64(value).map_or(Value::Null, Value::Number))
+            }
+            b's' => {
+                self.expect(b':')?;
+                let value = self.read_string()?;
+                self.expect(b';')?;
+                Some(Value::String(value))
+            }
+            b'a' => {
+                self.expect(b':')?;
+                let entries = self.parse_entries(depth)?;
+                Some(Self::array_to_json(entries))
+            }
+            b'O' => {
+                self.expect(b':')?;
+                let _class_name = self.read_string()?;
+                self.expect(b':')?;
+                let entries = self.parse_entries(depth)?;
+                let properties = entries
+                    .into_iter()
+                    .map(|(key, value)| (Self::property_name(key), value))
+                    .collect();
+                Some(Value::Object(properties))
+            }
+            b'E' => {
+                // PHP 8.1 enums are serialized as `E:len:"Class:Case";`.
+                self.expect(b':')?;
+                let value = self.read_string()?;
+                self.expect(b';')?;
+                Some(Value::String(value))
+            }
+            b'r' | b'R' => {
+                // References point back into the structure being built; they
+                // cannot be represented in JSON, so they decode to null.
+                self.expect(b':')?;
+                self.read_until(b';')?;
+                Some(Value::Null)
+            }
+            _ => None,
+        }
+    }
+
+    fn parse_entries(&mut self, depth: usize) -> Option<Vec<(String, Value)>> {
+        let count = self.read_length(b':')?;
+        self.expect(b'{')?;
+
+        let mut entries = Vec::with_capacity(count.min(1024));
+        for _ in 0..count {
+            let key = match self.parse_value(depth + 1)? {
+                Value::String(key) => key,
+                Value::Number(key) => key.to_string(),
+                _ => return None,
+            };
+            let value = self.parse_value(depth + 1)?;
+            entries.push((key, value));
+        }
+
+        self.expect(b'}')?;
+        Some(entries)
+    }
+
+    fn array_to_json(entries: Vec<(String, Value)>) -> Value {
+        let is_list = entries
+            .iter()
+            .enumerate()
+            .all(|(index, (key, _))| key.parse::<usize>().ok() == Some(index));
+
+        if is_list {
+            Value::Array(entries.into_iter().map(|(_, value)| value).collect())
+        } else {
+            Value::Object(entries.into_iter().collect::<Map<String, Value>>())
+        }
+    }
+
+    // Protected and private properties are prefixed with `\0*\0` and
+    // `\0ClassName\0` respectively.
+    fn property_name(key: String) -> String {
+        match key.rfind('\0') {
+            Some(index) => key[index + 1..].to_string(),
+            None => key,
+        }
+    }
+
+    fn read_string(&mut self) -> Option<String> {
+        let length = self.read_length(b':')?;
+        self.expect(b'"')?;
+
+        let end = self.pos.checked_add(length)?;
+        let bytes = self.input.get(self.pos..end)?;
+        self.pos = end;
+
+        self.expect(b'"')?;
+        Some(String::from_utf8_lossy(bytes).into_owned())
+    }
+
+    fn read_length(&mut self, terminator: u8) -> Option<usize> {
+        let digits = self.read_until(terminator)?;
+        std::str::from_utf8(digits).ok()?.parse::<usize>().ok()
+    }
+
+    fn read_until(&mut self, terminator: u8) -> Option<&[u8]> {
+        let start = self.pos;
+        let offset = self.input[start..]
+            .iter()
+            .position(|&byte| byte == terminator)?;
+        self.pos = start + offset + 1;
+        Some(&self.input[start..start + offset])
+    }
+
+    fn expect(&mut self, byte: u8) -> Option<()> {
+        if self.next()? == byte {
+            Some(())
+        } else {
+            None
+        }
+    }
+
+    fn next(&mut self) -> Option<u8> {
+        let byte = *self.input.get(self.pos)?;
+        self.pos += 1;
+        Some(byte)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn unserializes_scalars() {
+        assert_eq!(unserialize("N;"), Some(Value::Null));
+        assert_eq!(unserialize("b:1;"), Some(json!(true)));
+        assert_eq!(unserialize("b:0;"), Some(json!(false)));
+        assert_eq!(unserialize("b:2;"), None);
+        assert_eq!(unserialize("i:-42;"), Some(json!(-42)));
+        assert_eq!(unserialize("d:0.5;"), Some(json!(0.5)));
+        assert_eq!(unserialize("d:-1.25E+2;"), Some(json!(-125.0)));
+        assert_eq!(unserialize("d:INF;"), Some(Value::Null));
+        assert_eq!(unserialize(r#"s:5:"hello";"#), Some(json!("hello")));
+    }
+
+    #[test]
+    fn string_lengths_are_bytes() {
+        assert_eq!(unserialize(r#"s:5:"héllo";"#), None);
+        assert_eq!(unserialize(r#"s:6:"héllo";"#), Some(json!("héllo")));
+        assert_eq!(unserialize(r#"s:9:"日本語";"#), Some(json!("日本語")));
+        assert_eq!(unserialize(r#"s:4:"a"b";"#), None);
+        assert_eq!(unserialize(r#"s:3:"a"b";"#), Some(json!("a\"b")));
+    }
+
+    #[test]
+    fn unserializes_lists_and_keeps_array_order() {
+        assert_eq!(
+            unserialize(r#"a:3:{i:0;i:12;i:1;i:7;i:2;s:1:"x";}"#),
+            Some(json!([12, 7, "x"]))
+        );
+
+        // Keys out of sequence make a map, which must keep PHP's order.
+        let value = unserialize("a:3:{i:2;i:30;i:0;i:10;i:1;i:20;}").unwrap();
+        let keys: Vec<&str> = value
+            .as_object()
+            .unwrap()
+            .keys()
+            .map(String::as_str)
+            .collect();
+        assert_eq!(keys, ["2", "0", "1"]);
+
+        let value = unserialize(r#"a:2:{s:5:"zebra";i:1;s:5:"apple";i:2;}"#).unwrap();
+        assert_eq!(
+            serde_json::to_string(&value).unwrap(),
+            r#"{"zebra":1,"apple":2}"#
+        );
+    }
+
+    #[test]
+    fn unserializes_nested_arrays_and_objects() {
+        let input = concat!(
+            r#"a:2:{s:4:"list";a:2:{i:0;b:1;i:1;N;}"#,
+            "s:3:\"obj\";O:8:\"stdClass\":3:{s:1:\"a\";d:1.5;",
+            "s:4:\"\0*\0b\";i:2;s:6:\"\0Foo\0c\";a:0:{}}}"
+        );
+        assert_eq!(
+            unserialize(input),
+            Some(json!({
+                "list": [true, null],
+                "obj": {"a": 1.5, "b": 2, "c": []}
+            }))
+        );
+    }
+
+    #[test]
+    fn rejects_incomplete_input() {
+        assert_eq!(unserialize("a:2:{i:0;i:1;}"), None);
+        assert_eq!(unserialize("i:1;i:2;"), None);
+        assert_eq!(unserialize(r#"s:10:"short";"#), None);
+        assert_eq!(unserialize(&"a:1:{i:0;".repeat(100)), None);
+    }
+
+    #[test]
+    fn maybe_unserialize_keeps_plain_strings() {
+        assert_eq!(maybe_unserialize("hello"), json!("hello"));
+        assert_eq!(maybe_unserialize("a:1:{broken}"), json!("a:1:{broken}"));
+        assert_eq!(maybe_unserialize(" i:5; "), json!(5));
+    }
+}
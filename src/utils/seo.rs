@@ -0,0 +1,784 @@
+use chrono::{Datelike, Utc};
+use fancy_regex::{Captures, Regex};
+use serde::Serialize;
+use serde_json::Value as Json;
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+use crate::utils::php;
+
+/// Post meta keys Yoast SEO stores its per-post settings under.
+pub const YOAST_META_KEYS: [&str; 9] = [
+    "_yoast_wpseo_title",
+    "_yoast_wpseo_metadesc",
+    "_yoast_wpseo_canonical",
+    "_yoast_wpseo_meta-robots-noindex",
+    "_yoast_wpseo_meta-robots-nofollow",
+    "_yoast_wpseo_meta-robots-adv",
+    "_yoast_wpseo_opengraph-title",
+    "_yoast_wpseo_opengraph-description",
+    "_yoast_wpseo_opengraph-image",
+];
+
+/// Post and term meta keys Rank Math stores its settings under.
+pub const RANK_MATH_META_KEYS: [&str; 7] = [
+    "rank_math_title",
+    "rank_math_description",
+    "rank_math_canonical_url",
+    "rank_math_robots",
+    "rank_math_facebook_title",
+    "rank_math_facebook_description",
+    "rank_math_facebook_image",
+];
+
+// `%%title%%` in Yoast templates, `%title%` or `%customfield(name)%` in
+// Rank Math ones.
+static YOAST_VARIABLE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"%%([a-z0-9_]+)%%").expect("invalid variable pattern"));
+static RANK_MATH_VARIABLE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"%([a-z0-9_]+)(?:\([^)%]*\))?%").expect("invalid variable pattern")
+});
+
+// The `separator` option of Yoast SEO and what it outputs for each.
+const YOAST_SEPARATORS: [(&str, &str); 14] = [
+    ("sc-dash", "-"),
+    ("sc-ndash", "&ndash;"),
+    ("sc-mdash", "&mdash;"),
+    ("sc-colon", ":"),
+    ("sc-middot", "&middot;"),
+    ("sc-bull", "&bull;"),
+    ("sc-star", "*"),
+    ("sc-smstar", "&#8902;"),
+    ("sc-pipe", "|"),
+    ("sc-tilde", "~"),
+    ("sc-laquo", "&laquo;"),
+    ("sc-raquo", "&raquo;"),
+    ("sc-lt", "&lt;"),
+    ("sc-gt", "&gt;"),
+];
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Plugin {
+    Yoast,
+    RankMath,
+}
+
+/// The site's SEO plugin and its settings, read from `wp_options`.
+pub struct Settings {
+    plugin: Option<Plugin>,
+    /// `wpseo_titles` or `rank-math-options-titles`: templates, the
+    /// separator and the default robots directives.
+    titles: Json,
+    /// Yoast SEO keeps the settings of terms in the `wpseo_taxonomy_meta`
+    /// option rather than in term meta.
+    taxonomy_meta: Json,
+    site_name: String,
+    site_description: String,
+    public: bool,
+}
+
+/// A post or term the metadata is resolved for.
+pub enum Object<'a> {
+    Post { post_type: &'a str },
+    Term { taxonomy: &'a str, term_id: u64 },
+}
+
+/// What the metadata of a post or term is resolved from.
+pub struct Source<'a> {
+    pub object: Object<'a>,
+    pub title: &'a str,
+    /// Plain-text excerpt of a post, or the description of a term.
+    pub excerpt: &'a str,
+    pub link: &'a str,
+    /// URL of the featured image.
+    pub image: Option<&'a str>,
+    pub meta: &'a HashMap<String, String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Seo {
+    pub title: String,
+    pub description: String,
+    pub canonical: String,
+    pub robots: Robots,
+    pub og_title: String,
+    pub og_description: String,
+    pub og_image: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Robots {
+    pub index: bool,
+    pub follow: bool,
+    /// Other directives, such as `noarchive` or `max-snippet:-1`.
+    pub advanced: Vec<String>,
+}
+
+// What a post or term overrides of the defaults.
+#[derive(Default)]
+struct Overrides {
+    title: Option<String>,
+    description: Option<String>,
+    canonical: Option<String>,
+    og_title: Option<String>,
+    og_description: Option<String>,
+    og_image: Option<String>,
+    index: Option<bool>,
+    follow: Option<bool>,
+    advanced: Option<Vec<String>>,
+}
+
+impl Settings {
+    /// Uses the plugin WordPress has active. Plugins activated for a whole
+    /// network are not listed in `active_plugins`, so the plugin whose
+    /// settings exist is used otherwise.
+    pub fn new(options: &HashMap<String, String>) -> Self {
+        let option = |name: &str| {
+            options
+                .get(name)
+                .map_or(Json::Null, |value| php::maybe_unserialize(value))
+        };
+
+        let active_plugins = strings(&option("active_plugins"));
+        let is_active = |file: &str| active_plugins.iter().any(|plugin| plugin.ends_with(file));
+
+        let plugin = if is_active("/wp-seo.php") {
+            Some(Plugin::Yoast)
+        } else if is_active("/rank-math.php") {
+            Some(Plugin::RankMath)
+        } else if options.contains_key("wpseo_titles") {
+            Some(Plugin::Yoast)
+        } else if options.contains_key("rank-math-options-titles") {
+            Some(Plugin::RankMath)
+        } else {
+            None
+        };
+
+        let titles = match plugin {
+            Some(Plugin::Yoast) => option("wpseo_titles"),
+            Some(Plugin::RankMath) => option("rank-math-options-titles"),
+            None => Json::Null,
+        };
+
+        Settings {
+            plugin,
+            titles,
+            taxonomy_meta: option("wpseo_taxonomy_meta"),
+            site_name: options.get("blogname").cloned().unwrap_or_default(),
+            site_description: options.get("blogdescription").cloned().unwrap_or_default(),
+            public: options
+                .get("blog_public")
+                .is_none_or(|value| value.trim() != "0"),
+        }
+    }
+
+    /// The option names `new` reads.
+    pub fn option_names() -> Vec<String> {
+        [
+            "active_plugins",
+            "wpseo_titles",
+            "wpseo_taxonomy_meta",
+            "rank-math-options-titles",
+            "blogname",
+            "blogdescription",
+            "blog_public",
+        ]
+        .iter()
+        .map(|name| name.to_string())
+        .collect()
+    }
+
+    pub fn plugin(&self) -> Option<Plugin> {
+        self.plugin
+    }
+
+    /// The post meta keys the plugin keeps its settings under, none without
+    /// a plugin.
+    pub fn meta_keys(&self) -> &'static [&'static str] {
+        match self.plugin {
+            Some(Plugin::Yoast) => &YOAST_META_KEYS,
+            Some(Plugin::RankMath) => &RANK_MATH_META_KEYS,
+            None => &[],
+        }
+    }
+
+    /// Resolves the metadata the plugin would output. Without a plugin, or
+    /// where its templates resolve to nothing, the title falls back to the
+    /// post or term title and the description to the excerpt.
+    pub fn resolve(&self, source: &Source) -> Seo {
+        let overrides = match (self.plugin, &source.object) {
+            (Some(Plugin::Yoast), Object::Post { .. }) => Self::yoast_post(source.meta),
+            (Some(Plugin::Yoast), Object::Term { taxonomy, term_id }) => {
+                self.yoast_term(taxonomy, *term_id)
+            }
+            (Some(Plugin::RankMath), _) => Self::rank_math(source.meta),
+            (None, _) => Overrides::default(),
+        };
+
+        let (title_template, description_template) = self.templates(&source.object);
+
+        let title = overrides
+            .title
+            .or(title_template)
+            .map(|template| self.replace(&template, source))
+            .filter(|title| !title.is_empty())
+            .unwrap_or_else(|| source.title.to_string());
+
+        let description = overrides
+            .description
+            .or(description_template)
+            .map(|template| self.replace(&template, source))
+            .filter(|description| !description.is_empty())
+            .unwrap_or_else(|| source.excerpt.to_string());
+
+        let defaults = self.default_robots(&source.object);
+        let robots = Robots {
+            index: self.public && overrides.index.unwrap_or(defaults.index),
+            follow: overrides.follow.unwrap_or(defaults.follow),
+            advanced: overrides.advanced.unwrap_or(defaults.advanced),
+        };
+
+        let og_title = overrides
+            .og_title
+            .map(|template| self.replace(&template, source))
+            .filter(|og_title| !og_title.is_empty())
+            .unwrap_or_else(|| title.clone());
+
+        let og_description = overrides
+            .og_description
+            .map(|template| self.replace(&template, source))
+            .filter(|og_description| !og_description.is_empty())
+            .unwrap_or_else(|| description.clone());
+
+        Seo {
+            title,
+            description,
+            canonical: overrides
+                .canonical
+                .unwrap_or_else(|| source.link.to_string()),
+            robots,
+            og_title,
+            og_description,
+            og_image: overrides
+                .og_image
+                .or_else(|| source.image.map(str::to_string)),
+        }
+    }
+
+    fn yoast_post(meta: &HashMap<String, String>) -> Overrides {
+        let get = |key: &str| value(meta.get(&format!("_yoast_wpseo_{}", key)));
+
+        Overrides {
+            title: get("title"),
+            description: get("metadesc"),
+            canonical: get("canonical"),
+            og_title: get("opengraph-title"),
+            og_description: get("opengraph-description"),
+            og_image: get("opengraph-image"),
+            // "0" or no value follows the post type's setting.
+            index: match get("meta-robots-noindex").as_deref() {
+                Some("1") => Some(false),
+                Some("2") => Some(true),
+                _ => None,
+            },
+            follow: (get("meta-robots-nofollow").as_deref() == Some("1")).then_some(false),
+            advanced: get("meta-robots-adv").map(|directives| {
+                directives
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|directive| !matches!(*directive, "" | "-" | "none"))
+                    .map(str::to_string)
+                    .collect()
+            }),
+        }
+    }
+
+    fn yoast_term(&self, taxonomy: &str, term_id: u64) -> Overrides {
+        let meta = &self.taxonomy_meta[taxonomy][term_id.to_string()];
+        let get = |key: &str| value(meta[format!("wpseo_{}", key)].as_str());
+
+        Overrides {
+            title: get("title"),
+            description: get("desc"),
+            canonical: get("canonical"),
+            og_title: get("opengraph-title"),
+            og_description: get("opengraph-description"),
+            og_image: get("opengraph-image"),
+            index: match get("noindex").as_deref() {
+                Some("noindex") => Some(false),
+                Some("index") => Some(true),
+                _ => None,
+            },
+            follow: None,
+            advanced: None,
+        }
+    }
+
+    // Rank Math uses the same keys for posts and terms.
+    fn rank_math(meta: &HashMap<String, String>) -> Overrides {
+        let get = |key: &str| value(meta.get(&format!("rank_math_{}", key)));
+
+        let robots = meta
+            .get("rank_math_robots")
+            .map(|robots| strings(&php::maybe_unserialize(robots)))
+            .filter(|directives| !directives.is_empty())
+            .map(|directives| Robots::from_directives(&directives));
+
+        Overrides {
+            title: get("title"),
+            description: get("description"),
+            canonical: get("canonical_url"),
+            og_title: get("facebook_title"),
+            og_description: get("facebook_description"),
+            og_image: get("facebook_image"),
+            index: robots.as_ref().map(|robots| robots.index),
+            follow: robots.as_ref().map(|robots| robots.follow),
+            advanced: robots.map(|robots| robots.advanced),
+        }
+    }
+
+    // The title and description templates of a post type or taxonomy, with
+    // the plugin's defaults for those never saved.
+    fn templates(&self, object: &Object) -> (Option<String>, Option<String>) {
+        let template = |key: String, default: &str| {
+            Some(self.titles[key].as_str().unwrap_or(default).to_string())
+        };
+
+        match (self.plugin, object) {
+            (Some(Plugin::Yoast), Object::Post { post_type }) => (
+                template(
+                    format!("title-{}", post_type),
+                    "%%title%% %%page%% %%sep%% %%sitename%%",
+                ),
+                template(format!("metadesc-{}", post_type), ""),
+            ),
+            (Some(Plugin::Yoast), Object::Term { taxonomy, .. }) => (
+                template(
+                    format!("title-tax-{}", taxonomy),
+                    "%%term_title%% Archives %%page%% %%sep%% %%sitename%%",
+                ),
+                template(format!("metadesc-tax-{}", taxonomy), ""),
+            ),
+            (Some(Plugin::RankMath), Object::Post { post_type }) => (
+                template(
+                    format!("pt_{}_title", post_type),
+                    "%title% %sep% %sitename%",
+                ),
+                template(format!("pt_{}_description", post_type), "%excerpt%"),
+            ),
+            (Some(Plugin::RankMath), Object::Term { taxonomy, .. }) => (
+                template(
+                    format!("tax_{}_title", taxonomy),
+                    "%term% Archives %page% %sep% %sitename%",
+                ),
+                template(
+                    format!("tax_{}_description", taxonomy),
+                    "%term_description%",
+                ),
+            ),
+            (None, _) => (None, None),
+        }
+    }
+
+    fn default_robots(&self, object: &Object) -> Robots {
+        match self.plugin {
+            Some(Plugin::Yoast) => {
+                let key = match object {
+                    Object::Post { post_type } => format!("noindex-{}", post_type),
+                    Object::Term { taxonomy, .. } => format!("noindex-tax-{}", taxonomy),
+                };
+
+                Robots {
+                    index: !is_enabled(&self.titles[key]),
+                    follow: true,
+                    advanced: Vec::new(),
+                }
+            }
+            Some(Plugin::RankMath) => {
+                // Post types and taxonomies use the global directives
+                // unless they are set to use their own.
+                let prefix = match object {
+                    Object::Post { post_type } => format!("pt_{}", post_type),
+                    Object::Term { taxonomy, .. } => format!("tax_{}", taxonomy),
+                };
+                let robots = if is_enabled(&self.titles[format!("{}_custom_robots", prefix)]) {
+                    &self.titles[format!("{}_robots", prefix)]
+                } else {
+                    &self.titles["robots_global"]
+                };
+
+                Robots::from_directives(&strings(robots))
+            }
+            None => Robots {
+                index: true,
+                follow: true,
+                advanced: Vec::new(),
+            },
+        }
+    }
+
+    // Replaces the template variables the plugin supports for posts and
+    // terms. Variables without a value here, such as `%%page%%`, are
+    // removed, and the whitespace they leave is collapsed.
+    fn replace(&self, template: &str, source: &Source) -> String {
+        let pattern = match self.plugin {
+            Some(Plugin::RankMath) => &*RANK_MATH_VARIABLE,
+            _ => &*YOAST_VARIABLE,
+        };
+
+        let replaced = pattern.replace_all(template, |captures: &Captures| {
+            self.variable(&captures[1], source)
+        });
+
+        replaced.split_whitespace().collect::<Vec<_>>().join(" ")
+    }
+
+    fn variable(&self, name: &str, source: &Source) -> String {
+        let is_term = matches!(source.object, Object::Term { .. });
+
+        match name {
+            "title" | "term_title" | "term" => source.title.to_string(),
+            "sitename" => self.site_name.clone(),
+            "sitedesc" => self.site_description.clone(),
+            "sep" => self.separator(),
+            "excerpt" | "excerpt_only" => source.excerpt.to_string(),
+            "term_description" | "category_description" | "tag_description" if is_term => {
+                source.excerpt.to_string()
+            }
+            "currentyear" => Utc::now().year().to_string(),
+            _ => String::new(),
+        }
+    }
+
+    fn separator(&self) -> String {
+        match self.plugin {
+            Some(Plugin::RankMath) => self.titles["title_separator"]
+                .as_str()
+                .unwrap_or("-")
+                .to_string(),
+            _ => {
+                let name = self.titles["separator"].as_str().unwrap_or("sc-dash");
+                YOAST_SEPARATORS
+                    .iter()
+                    .find(|(separator, _)| *separator == name)
+                    .map_or("-", |(_, output)| *output)
+                    .to_string()
+            }
+        }
+    }
+}
+
+impl Robots {
+    fn from_directives(directives: &[String]) -> Self {
+        let has = |directive: &str| directives.iter().any(|value| value == directive);
+
+        Robots {
+            index: !has("noindex"),
+            follow: !has("nofollow"),
+            advanced: directives
+                .iter()
+                .filter(|value| {
+                    !matches!(value.as_str(), "index" | "noindex" | "follow" | "nofollow")
+                })
+                .cloned()
+                .collect(),
+        }
+    }
+}
+
+// A trimmed setting, or `None` when it is empty.
+fn value<S: AsRef<str>>(value: Option<S>) -> Option<String> {
+    value
+        .map(|value| value.as_ref().trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+// The strings in a PHP array, such as a list of robots directives.
+fn strings(value: &Json) -> Vec<String> {
+    let values: Vec<&Json> = match value {
+        Json::Array(values) => values.iter().collect(),
+        Json::Object(values) => values.values().collect(),
+        _ => Vec::new(),
+    };
+
+    values
+        .into_iter()
+        .filter_map(|value| value.as_str())
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+        .collect()
+}
+
+// Checkbox settings are saved as booleans by Yoast SEO and as "on" or "off"
+// by Rank Math.
+fn is_enabled(value: &Json) -> bool {
+    match value {
+        Json::Bool(value) => *value,
+        Json::Number(value) => value.as_i64() == Some(1),
+        Json::String(value) => matches!(value.as_str(), "1" | "on" | "true"),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(pairs: &[(&str, String)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.clone()))
+            .collect()
+    }
+
+    fn meta(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+
+    // `serialize()` of a string.
+    fn string(value: &str) -> String {
+        format!("s:{}:\"{}\";", value.len(), value)
+    }
+
+    // `serialize()` of an array, from already serialized keys and values.
+    fn array(entries: &[(String, String)]) -> String {
+        let body: String = entries
+            .iter()
+            .map(|(key, value)| format!("{}{}", key, value))
+            .collect();
+        format!("a:{}:{{{}}}", entries.len(), body)
+    }
+
+    fn list(values: &[&str]) -> String {
+        let entries: Vec<(String, String)> = values
+            .iter()
+            .enumerate()
+            .map(|(index, value)| (format!("i:{};", index), string(value)))
+            .collect();
+        array(&entries)
+    }
+
+    fn source<'a>(object: Object<'a>, meta: &'a HashMap<String, String>) -> Source<'a> {
+        Source {
+            object,
+            title: "Hello",
+            excerpt: "An excerpt",
+            link: "https://example.com/hello/",
+            image: Some("https://example.com/hello.jpg"),
+            meta,
+        }
+    }
+
+    fn post(post_type: &str) -> Object<'_> {
+        Object::Post { post_type }
+    }
+
+    fn yoast(titles: &[(&str, String)]) -> Settings {
+        let titles: Vec<(String, String)> = titles
+            .iter()
+            .map(|(key, value)| (string(key), value.clone()))
+            .collect();
+
+        Settings::new(&options(&[
+            ("active_plugins", list(&["wordpress-seo/wp-seo.php"])),
+            ("wpseo_titles", array(&titles)),
+            ("blogname", "Site".to_string()),
+        ]))
+    }
+
+    #[test]
+    fn detects_the_active_plugin() {
+        let none = Settings::new(&options(&[("blogname", "Site".to_string())]));
+        assert_eq!(none.plugin(), None);
+
+        let network = Settings::new(&options(&[("rank-math-options-titles", array(&[]))]));
+        assert_eq!(network.plugin(), Some(Plugin::RankMath));
+
+        let both = Settings::new(&options(&[
+            ("active_plugins", list(&["seo-by-rank-math/rank-math.php"])),
+            ("wpseo_titles", array(&[])),
+        ]));
+        assert_eq!(both.plugin(), Some(Plugin::RankMath));
+    }
+
+    #[test]
+    fn substitutes_template_variables() {
+        let settings = yoast(&[
+            ("separator", string("sc-pipe")),
+            (
+                "title-post",
+                string("%%title%% %%page%% %%sep%% %%sitename%%"),
+            ),
+            ("metadesc-post", string("%%excerpt%% %%unknown%%")),
+        ]);
+        let meta = HashMap::new();
+        let seo = settings.resolve(&source(post("post"), &meta));
+
+        assert_eq!(seo.title, "Hello | Site");
+        assert_eq!(seo.description, "An excerpt");
+        assert_eq!(seo.og_title, "Hello | Site");
+
+        // Unsaved templates use the plugin's defaults.
+        let settings = yoast(&[("separator", string("sc-ndash"))]);
+        let seo = settings.resolve(&source(post("page"), &meta));
+        assert_eq!(seo.title, "Hello &ndash; Site");
+
+        let settings = Settings::new(&options(&[
+            ("active_plugins", list(&["seo-by-rank-math/rank-math.php"])),
+            (
+                "rank-math-options-titles",
+                array(&[(string("title_separator"), string("»"))]),
+            ),
+            ("blogname", "Site".to_string()),
+        ]));
+        let seo = settings.resolve(&source(post("post"), &meta));
+        assert_eq!(seo.title, "Hello » Site");
+        assert_eq!(seo.description, "An excerpt");
+    }
+
+    #[test]
+    fn yoast_noindex_overrides_the_post_type() {
+        let settings = yoast(&[("noindex-post", "b:1;".to_string())]);
+        let index = |value: &str| {
+            let meta = meta(&[("_yoast_wpseo_meta-robots-noindex", value)]);
+            settings.resolve(&source(post("post"), &meta)).robots.index
+        };
+
+        assert!(!index("0"));
+        assert!(!index("1"));
+        assert!(index("2"));
+
+        let settings = yoast(&[]);
+        let index = |value: &str| {
+            let meta = meta(&[("_yoast_wpseo_meta-robots-noindex", value)]);
+            settings.resolve(&source(post("post"), &meta)).robots.index
+        };
+
+        assert!(index("0"));
+        assert!(!index("1"));
+        assert!(index("2"));
+
+        let meta = meta(&[
+            ("_yoast_wpseo_meta-robots-nofollow", "1"),
+            ("_yoast_wpseo_meta-robots-adv", "noarchive, none,nosnippet"),
+        ]);
+        let robots = settings.resolve(&source(post("post"), &meta)).robots;
+        assert!(!robots.follow);
+        assert_eq!(robots.advanced, vec!["noarchive", "nosnippet"]);
+    }
+
+    #[test]
+    fn discouraged_search_engines_noindex_everything() {
+        let settings = Settings::new(&options(&[
+            ("wpseo_titles", array(&[])),
+            ("blog_public", "0".to_string()),
+        ]));
+        let meta = meta(&[("_yoast_wpseo_meta-robots-noindex", "2")]);
+
+        assert!(!settings.resolve(&source(post("post"), &meta)).robots.index);
+    }
+
+    #[test]
+    fn reads_yoast_term_settings_from_the_taxonomy_meta() {
+        let term = array(&[
+            (string("wpseo_title"), string("Custom %%term_title%%")),
+            (string("wpseo_desc"), string("About %%term_title%%")),
+            (string("wpseo_noindex"), string("noindex")),
+        ]);
+        let taxonomy_meta = array(&[(string("category"), array(&[("i:5;".to_string(), term)]))]);
+        let settings = Settings::new(&options(&[
+            ("active_plugins", list(&["wordpress-seo/wp-seo.php"])),
+            ("wpseo_taxonomy_meta", taxonomy_meta),
+            ("blogname", "Site".to_string()),
+        ]));
+        let meta = HashMap::new();
+        let term = |taxonomy, term_id| Object::Term { taxonomy, term_id };
+
+        let seo = settings.resolve(&source(term("category", 5), &meta));
+        assert_eq!(seo.title, "Custom Hello");
+        assert_eq!(seo.description, "About Hello");
+        assert!(!seo.robots.index);
+
+        let seo = settings.resolve(&source(term("category", 6), &meta));
+        assert_eq!(seo.title, "Hello Archives - Site");
+        assert_eq!(seo.description, "An excerpt");
+        assert!(seo.robots.index);
+
+        let seo = settings.resolve(&source(term("post_tag", 5), &meta));
+        assert_eq!(seo.title, "Hello Archives - Site");
+    }
+
+    #[test]
+    fn rank_math_uses_custom_robots_only_when_enabled() {
+        let settings = Settings::new(&options(&[
+            ("active_plugins", list(&["seo-by-rank-math/rank-math.php"])),
+            (
+                "rank-math-options-titles",
+                array(&[
+                    (string("robots_global"), list(&["index", "follow"])),
+                    (string("pt_post_custom_robots"), string("on")),
+                    (string("pt_post_robots"), list(&["noindex", "noarchive"])),
+                    (string("pt_page_custom_robots"), string("off")),
+                    (string("pt_page_robots"), list(&["noindex"])),
+                ]),
+            ),
+        ]));
+        let empty = HashMap::new();
+
+        let robots = settings.resolve(&source(post("post"), &empty)).robots;
+        assert!(!robots.index);
+        assert!(robots.follow);
+        assert_eq!(robots.advanced, vec!["noarchive"]);
+
+        let robots = settings.resolve(&source(post("page"), &empty)).robots;
+        assert!(robots.index);
+        assert!(robots.advanced.is_empty());
+
+        // Robots saved for the post replace the post type's.
+        let meta = meta(&[("rank_math_robots", &list(&["nofollow"]))]);
+        let robots = settings.resolve(&source(post("post"), &meta)).robots;
+        assert!(robots.index);
+        assert!(!robots.follow);
+        assert!(robots.advanced.is_empty());
+    }
+
+    #[test]
+    fn falls_back_to_the_title_and_excerpt() {
+        let settings = Settings::new(&HashMap::new());
+        let empty = HashMap::new();
+        let seo = settings.resolve(&source(post("post"), &empty));
+
+        assert_eq!(seo.title, "Hello");
+        assert_eq!(seo.description, "An excerpt");
+        assert_eq!(seo.canonical, "https://example.com/hello/");
+        assert_eq!(
+            seo.og_image.as_deref(),
+            Some("https://example.com/hello.jpg")
+        );
+        assert_eq!(
+            seo.robots,
+            Robots {
+                index: true,
+                follow: true,
+                advanced: Vec::new(),
+            }
+        );
+
+        // A template or override resolving to nothing falls back as well.
+        let settings = yoast(&[("title-post", string("%%page%%"))]);
+        let meta = meta(&[
+            ("_yoast_wpseo_metadesc", "  "),
+            ("_yoast_wpseo_opengraph-title", "%%page%%"),
+            ("_yoast_wpseo_canonical", "https://example.com/canonical/"),
+        ]);
+        let seo = settings.resolve(&source(post("post"), &meta));
+
+        assert_eq!(seo.title, "Hello");
+        assert_eq!(seo.description, "An excerpt");
+        assert_eq!(seo.og_title, "Hello");
+        assert_eq!(seo.canonical, "https://example.com/canonical/");
+    }
+}
@@ -0,0 +1,837 @@
+use std::collections::HashMap;
+
+/// How shortcodes in post content are handled for a request.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ShortcodeMode {
+    /// Leave the content untouched.
+    Raw,
+    /// Remove shortcode tags, keeping the content they enclose.
+    Strip,
+    /// Render registered shortcodes to HTML and strip the rest.
+    Render,
+}
+
+impl ShortcodeMode {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "raw" => Some(ShortcodeMode::Raw),
+            "strip" => Some(ShortcodeMode::Strip),
+            "render" => Some(ShortcodeMode::Render),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Node {
+    Text(String),
+    Shortcode(Shortcode),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Shortcode {
+    pub name: String,
+    /// Named attributes keyed by lowercased name; positional values are keyed
+    /// by their index, as in `shortcode_parse_atts()`.
+    pub attrs: HashMap<String, String>,
+    /// `None` for self-closing shortcodes, the parsed inner nodes otherwise.
+    pub content: Option<Vec<Node>>,
+}
+
+impl Shortcode {
+    pub fn attr(&self, name: &str) -> Option<&str> {
+        self.attrs
+            .get(name)
+            .map(String::as_str)
+            .filter(|value| !value.is_empty())
+    }
+}
+
+/// An image available to the `[gallery]` renderer.
+#[derive(Clone, Debug, Default)]
+pub struct GalleryImage {
+    pub url: String,
+    pub alt: String,
+    pub caption: String,
+    pub sizes: HashMap<String, String>,
+}
+
+/// Data the renderers need beyond the shortcode itself; it is loaded up front
+/// so rendering never touches the database.
+#[derive(Default)]
+pub struct RenderContext {
+    pub post_id: u64,
+    pub images: HashMap<u64, GalleryImage>,
+    pub children: HashMap<u64, Vec<u64>>,
+}
+
+pub type Renderer = fn(&Shortcode, &str, &RenderContext) -> String;
+
+pub struct Registry {
+    renderers: HashMap<&'static str, Renderer>,
+}
+
+impl Registry {
+    pub fn new() -> Self {
+        Registry {
+            renderers: HashMap::new(),
+        }
+    }
+
+    /// Registry with renderers for the shortcodes WordPress core ships.
+    pub fn core() -> Self {
+        let mut registry = Registry::new();
+        registry.register("caption", render_caption);
+        registry.register("wp_caption", render_caption);
+        registry.register("gallery", render_gallery);
+        registry.register("embed", render_embed);
+        registry.register("audio", render_audio);
+        registry.register("video", render_video);
+        registry
+    }
+
+    pub fn register(&mut self, name: &'static str, renderer: Renderer) {
+        self.renderers.insert(name, renderer);
+    }
+
+    pub fn names(&self) -> Vec<&'static str> {
+        self.renderers.keys().copied().collect()
+    }
+
+    fn get(&self, name: &str) -> Option<&Renderer> {
+        self.renderers.get(name)
+    }
+}
+
+/// Parses content into text and shortcode nodes, following the rules of
+/// WordPress's `get_shortcode_regex()`: an enclosing shortcode runs up to the
+/// first matching `[/name]`, and `[[name]]` escapes a shortcode.
+pub fn parse(content: &str) -> Vec<Node> {
+    Parser::new(content).parse(0, content.len(), 0)
+}
+
+/// Serializes nodes back to content according to `mode`. Unknown shortcodes
+/// are always stripped when rendering.
+pub fn render(
+    nodes: &[Node],
+    mode: ShortcodeMode,
+    registry: &Registry,
+    context: &RenderContext,
+) -> String {
+    let mut output = String::new();
+
+    for node in nodes {
+        match node {
+            Node::Text(text) => output.push_str(text),
+            Node::Shortcode(shortcode) => {
+                let inner = shortcode
+                    .content
+                    .as_ref()
+                    .map(|content| render(content, mode, registry, context))
+                    .unwrap_or_default();
+
+                match (mode, registry.get(&shortcode.name)) {
+                    (ShortcodeMode::Render, Some(renderer)) => {
+                        output.push_str(&renderer(shortcode, &inner, context))
+                    }
+                    _ => output.push_str(&inner),
+                }
+            }
+        }
+    }
+
+    output
+}
+
+/// Attachment IDs referenced explicitly by `[gallery ids="..."]`.
+pub fn gallery_attachment_ids(nodes: &[Node]) -> Vec<u64> {
+    let mut ids = Vec::new();
+    visit_galleries(nodes, &mut |gallery| {
+        ids.extend(explicit_gallery_ids(gallery))
+    });
+    ids
+}
+
+/// Posts whose attached images are shown by galleries without explicit IDs.
+pub fn gallery_parent_ids(nodes: &[Node], post_id: u64) -> Vec<u64> {
+    let mut ids = Vec::new();
+    visit_galleries(nodes, &mut |gallery| {
+        if explicit_gallery_ids(gallery).is_empty() {
+            ids.push(gallery_parent(gallery, post_id));
+        }
+    });
+    ids
+}
+
+fn visit_galleries(nodes: &[Node], visit: &mut dyn FnMut(&Shortcode)) {
+    for node in nodes {
+        if let Node::Shortcode(shortcode) = node {
+            if shortcode.name == "gallery" {
+                visit(shortcode);
+            }
+            if let Some(content) = &shortcode.content {
+                visit_galleries(content, visit);
+            }
+        }
+    }
+}
+
+fn explicit_gallery_ids(gallery: &Shortcode) -> Vec<u64> {
+    gallery
+        .attr("ids")
+        .or_else(|| gallery.attr("include"))
+        .unwrap_or_default()
+        .split(',')
+        .filter_map(|id| id.trim().parse().ok())
+        .collect()
+}
+
+fn gallery_parent(gallery: &Shortcode, post_id: u64) -> u64 {
+    gallery
+        .attr("id")
+        .and_then(|id| id.trim().parse().ok())
+        .unwrap_or(post_id)
+}
+
+// Deeper shortcodes keep their content as text. Nesting only goes as deep as
+// the number of distinct shortcode names, since a shortcode cannot contain
+// another one with its own name.
+const MAX_DEPTH: usize = 32;
+
+/// Parses a range of the content at a time. The positions of every closing
+/// tag are collected up front so finding where an enclosing shortcode ends
+/// does not rescan the rest of the content.
+struct Parser<'a> {
+    content: &'a str,
+    closing_tags: HashMap<&'a str, Vec<usize>>,
+}
+
+impl<'a> Parser<'a> {
+    fn new(content: &'a str) -> Self {
+        let mut closing_tags: HashMap<&str, Vec<usize>> = HashMap::new();
+
+        for (start, _) in content.match_indices("[/") {
+            let name_start = start + 2;
+            let name_length = shortcode_name_length(&content.as_bytes()[name_start..]);
+            if name_length > 0 && content[name_start + name_length..].starts_with(']') {
+                closing_tags
+                    .entry(&content[name_start..name_start + name_length])
+                    .or_default()
+                    .push(start);
+            }
+        }
+
+        Parser {
+            content,
+            closing_tags,
+        }
+    }
+
+    fn parse(&self, start: usize, end: usize, depth: usize) -> Vec<Node> {
+        let content = self.content;
+        let mut nodes = Vec::new();
+        let mut text = String::new();
+        let mut pos = start;
+
+        while let Some(index) = content[pos..end].find('[') {
+            let start = pos + index;
+            text.push_str(&content[pos..start]);
+
+            let escaped = content[start + 1..end].starts_with('[');
+            let tag_start = if escaped { start + 1 } else { start };
+
+            match self.parse_shortcode(tag_start, end, depth) {
+                Some((_, next)) if escaped && content[next..end].starts_with(']') => {
+                    text.push_str(&content[tag_start..next]);
+                    pos = next + 1;
+                }
+                Some((shortcode, next)) => {
+                    if escaped {
+                        text.push('[');
+                    }
+                    if !text.is_empty() {
+                        nodes.push(Node::Text(std::mem::take(&mut text)));
+                    }
+                    nodes.push(Node::Shortcode(shortcode));
+                    pos = next;
+                }
+                None => {
+                    text.push('[');
+                    pos = start + 1;
+                }
+            }
+        }
+
+        text.push_str(&content[pos..end]);
+        if !text.is_empty() {
+            nodes.push(Node::Text(text));
+        }
+
+        nodes
+    }
+
+    fn parse_shortcode(
+        &self,
+        start: usize,
+        end: usize,
+        depth: usize,
+    ) -> Option<(Shortcode, usize)> {
+        let content = self.content;
+        let bytes = &content.as_bytes()[..end];
+        let name_start = start + 1;
+
+        let name_length = shortcode_name_length(&bytes[name_start..]);
+        if name_length == 0 {
+            return None;
+        }
+
+        let name = &content[name_start..name_start + name_length];
+        let mut pos = name_start + name_length;
+
+        match bytes.get(pos) {
+            Some(byte) if byte.is_ascii_whitespace() || *byte == b']' || *byte == b'/' => {}
+            _ => return None,
+        }
+
+        // Attributes cannot contain `[` or `]`, and a `/` directly before the
+        // `]` closes the tag.
+        let close = pos + content[pos..end].find(['[', ']'])?;
+        if bytes[close] == b'[' {
+            return None;
+        }
+        let self_closing = close > pos && bytes[close - 1] == b'/';
+        let attr_end = if self_closing { close - 1 } else { close };
+
+        let mut shortcode = Shortcode {
+            name: name.to_string(),
+            attrs: parse_attributes(&content[pos..attr_end]),
+            content: None,
+        };
+        pos = close + 1;
+
+        if !self_closing {
+            let closing_tag = self.closing_tags.get(name).and_then(|positions| {
+                positions
+                    .get(positions.partition_point(|position| *position < pos))
+                    .filter(|position| **position < end)
+            });
+
+            if let Some(&closing_tag) = closing_tag {
+                shortcode.content = Some(if depth < MAX_DEPTH {
+                    self.parse(pos, closing_tag, depth + 1)
+                } else {
+                    vec![Node::Text(content[pos..closing_tag].to_string())]
+                });
+                pos = closing_tag + name.len() + 3;
+            }
+        }
+
+        Some((shortcode, pos))
+    }
+}
+
+/// Length of the shortcode name at the start of `bytes`, or 0 when there is
+/// none. Names start with a letter or underscore.
+fn shortcode_name_length(bytes: &[u8]) -> usize {
+    match bytes.first() {
+        Some(byte) if byte.is_ascii_alphabetic() || *byte == b'_' => bytes
+            .iter()
+            .take_while(|byte| byte.is_ascii_alphanumeric() || **byte == b'_' || **byte == b'-')
+            .count(),
+        _ => 0,
+    }
+}
+
+/// Port of `shortcode_parse_atts()`: `name="value"`, `name='value'` and
+/// `name=value` pairs plus positional values. Names are lowercased.
+fn parse_attributes(text: &str) -> HashMap<String, String> {
+    let text = text.replace(['\u{00a0}', '\u{200b}'], " ");
+    let chars: Vec<char> = text.chars().collect();
+
+    let mut attrs = HashMap::new();
+    let mut positional = 0;
+    let mut pos = 0;
+
+    let is_name_char = |c: char| c.is_alphanumeric() || c == '_' || c == '-';
+
+    while pos < chars.len() {
+        if chars[pos].is_whitespace() {
+            pos += 1;
+            continue;
+        }
+
+        let name_end = pos
+            + chars[pos..]
+                .iter()
+                .take_while(|c| is_name_char(**c))
+                .count();
+        let mut value_start = name_end;
+        while value_start < chars.len() && chars[value_start].is_whitespace() {
+            value_start += 1;
+        }
+
+        if name_end > pos && chars.get(value_start) == Some(&'=') {
+            let name: String = chars[pos..name_end].iter().collect();
+            value_start += 1;
+            while value_start < chars.len() && chars[value_start].is_whitespace() {
+                value_start += 1;
+            }
+
+            let (value, next) = read_value(&chars, value_start);
+            attrs.insert(name.to_lowercase(), value);
+            pos = next;
+        } else {
+            let (value, next) = read_value(&chars, pos);
+            attrs.insert(positional.to_string(), value);
+            positional += 1;
+            pos = next;
+        }
+    }
+
+    attrs
+}
+
+fn read_value(chars: &[char], start: usize) -> (String, usize) {
+    match chars.get(start) {
+        Some(&quote @ ('"' | '\'')) => {
+            let end = chars[start + 1..]
+                .iter()
+                .position(|c| *c == quote)
+                .map_or(chars.len(), |index| start + 1 + index);
+            let value = chars[start + 1..end].iter().collect();
+            (value, (end + 1).min(chars.len()))
+        }
+        _ => {
+            let end = start
+                + chars[start..]
+                    .iter()
+                    .take_while(|c| !c.is_whitespace())
+                    .count();
+            (chars[start..end].iter().collect(), end)
+        }
+    }
+}
+
+fn escape_attr(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#039;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn render_caption(shortcode: &Shortcode, content: &str, _: &RenderContext) -> String {
+    // Older captions keep the text in the content after the image (and its
+    // optional link) instead of the `caption` attribute.
+    let (media, caption) = match shortcode.attr("caption") {
+        Some(caption) => (content.trim().to_string(), caption.to_string()),
+        None => split_caption(content),
+    };
+
+    let id = shortcode.attr("id").map(escape_attr);
+    let align = escape_attr(shortcode.attr("align").unwrap_or("alignnone"));
+    let width = shortcode
+        .attr("width")
+        .and_then(|width| width.trim().parse::<u32>().ok())
+        .filter(|width| *width > 0);
+
+    let mut html = String::from("<figure");
+    if let Some(id) = &id {
+        html.push_str(&format!(
+            " id=\"{}\" aria-describedby=\"caption-{}\"",
+            id, id
+        ));
+    }
+    if let Some(width) = width {
+        html.push_str(&format!(" style=\"width: {}px\"", width));
+    }
+    html.push_str(&format!(" class=\"wp-caption {}\">", align));
+    html.push_str(&media);
+
+    if !caption.is_empty() {
+        match &id {
+            Some(id) => html.push_str(&format!(
+                "<figcaption id=\"caption-{}\" class=\"wp-caption-text\">{}</figcaption>",
+                id, caption
+            )),
+            None => html.push_str(&format!(
+                "<figcaption class=\"wp-caption-text\">{}</figcaption>",
+                caption
+            )),
+        }
+    }
+
+    html.push_str("</figure>");
+    html
+}
+
+fn split_caption(content: &str) -> (String, String) {
+    let content = content.trim();
+    let lower = content.to_ascii_lowercase();
+
+    let media_end = if lower.starts_with("<a ") {
+        lower.find("</a>").map(|index| index + 4)
+    } else if lower.starts_with("<img ") {
+        lower.find('>').map(|index| index + 1)
+    } else {
+        None
+    };
+
+    match media_end {
+        Some(end) => (
+            content[..end].to_string(),
+            content[end..].trim().to_string(),
+        ),
+        None => (content.to_string(), String::new()),
+    }
+}
+
+fn render_gallery(shortcode: &Shortcode, _: &str, context: &RenderContext) -> String {
+    let mut ids = explicit_gallery_ids(shortcode);
+    if ids.is_empty() {
+        ids = context
+            .children
+            .get(&gallery_parent(shortcode, context.post_id))
+            .cloned()
+            .unwrap_or_default();
+    }
+
+    let images: Vec<&GalleryImage> = ids.iter().filter_map(|id| context.images.get(id)).collect();
+    if images.is_empty() {
+        return String::new();
+    }
+
+    let columns = shortcode
+        .attr("columns")
+        .and_then(|columns| columns.trim().parse::<u32>().ok())
+        .unwrap_or(3);
+    let size = shortcode.attr("size").unwrap_or("thumbnail");
+    let link = shortcode.attr("link").unwrap_or("file");
+
+    let mut html = format!(
+        "<div class=\"gallery galleryid-{} gallery-columns-{} gallery-size-{}\">",
+        context.post_id,
+        columns,
+        escape_attr(size)
+    );
+
+    for image in images {
+        let src = image.sizes.get(size).unwrap_or(&image.url);
+        let img = format!(
+            "<img src=\"{}\" alt=\"{}\" loading=\"lazy\" />",
+            escape_attr(src),
+            escape_attr(&image.alt)
+        );
+
+        html.push_str("<figure class=\"gallery-item\"><div class=\"gallery-icon\">");
+        if link == "none" {
+            html.push_str(&img);
+        } else {
+            html.push_str(&format!(
+                "<a href=\"{}\">{}</a>",
+                escape_attr(&image.url),
+                img
+            ));
+        }
+        html.push_str("</div>");
+
+        if !image.caption.trim().is_empty() {
+            html.push_str(&format!(
+                "<figcaption class=\"wp-caption-text gallery-caption\">{}</figcaption>",
+                image.caption.trim()
+            ));
+        }
+        html.push_str("</figure>");
+    }
+
+    html.push_str("</div>");
+    html
+}
+
+fn render_embed(shortcode: &Shortcode, content: &str, _: &RenderContext) -> String {
+    let url = shortcode
+        .attr("src")
+        .map(str::to_string)
+        .unwrap_or_else(|| content.trim().to_string());
+
+    if url.is_empty() {
+        return String::new();
+    }
+
+    let width = shortcode.attr("width").unwrap_or("640");
+    let height = shortcode.attr("height").unwrap_or("360");
+
+    // Without network access oEmbed discovery is not possible, so only the
+    // most common providers are turned into players; anything else becomes
+    // a plain link.
+    let player = youtube_id(&url)
+        .map(|id| format!("https://www.youtube.com/embed/{}", id))
+        .or_else(|| vimeo_id(&url).map(|id| format!("https://player.vimeo.com/video/{}", id)));
+
+    match player {
+        Some(player) => format!(
+            "<figure class=\"wp-block-embed\"><div class=\"wp-block-embed__wrapper\"><iframe src=\"{}\" width=\"{}\" height=\"{}\" frameborder=\"0\" allowfullscreen></iframe></div></figure>",
+            escape_attr(&player),
+            escape_attr(width),
+            escape_attr(height)
+        ),
+        None => format!("<a href=\"{}\">{}</a>", escape_attr(&url), escape_attr(&url)),
+    }
+}
+
+fn youtube_id(url: &str) -> Option<String> {
+    let id = if let Some(rest) = url.split("youtu.be/").nth(1) {
+        rest
+    } else if url.contains("youtube.com/") {
+        url.split(['?', '&'])
+            .find_map(|part| part.strip_prefix("v="))
+            .or_else(|| url.split("/embed/").nth(1))?
+    } else {
+        return None;
+    };
+
+    let id: String = id
+        .chars()
+        .take_while(|c| c.is_ascii_alphanumeric() || *c == '_' || *c == '-')
+        .collect();
+
+    if id.is_empty() {
+        None
+    } else {
+        Some(id)
+    }
+}
+
+fn vimeo_id(url: &str) -> Option<String> {
+    let rest = url.split("vimeo.com/").nth(1)?;
+    let id: String = rest
+        .rsplit('/')
+        .find(|segment| !segment.is_empty() && segment.chars().all(|c| c.is_ascii_digit()))?
+        .to_string();
+    Some(id)
+}
+
+fn media_sources(shortcode: &Shortcode, extensions: &[(&str, &str)]) -> Vec<(String, String)> {
+    let mut sources: Vec<(String, String)> = extensions
+        .iter()
+        .filter_map(|(extension, mime_type)| {
+            shortcode
+                .attr(extension)
+                .map(|src| (src.to_string(), mime_type.to_string()))
+        })
+        .collect();
+
+    if let Some(src) = shortcode.attr("src") {
+        let extension = src
+            .split(['?', '#'])
+            .next()
+            .and_then(|path| path.rsplit('.').next())
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        let mime_type = extensions
+            .iter()
+            .find(|(candidate, _)| *candidate == extension)
+            .map(|(_, mime_type)| mime_type.to_string())
+            .unwrap_or_default();
+        sources.insert(0, (src.to_string(), mime_type));
+    }
+
+    sources
+}
+
+fn boolean_attributes(shortcode: &Shortcode, names: &[&str]) -> String {
+    names
+        .iter()
+        .filter(|name| matches!(shortcode.attr(name), Some("1" | "on" | "true")))
+        .map(|name| format!(" {}", name))
+        .collect()
+}
+
+fn source_tags(sources: &[(String, String)]) -> String {
+    sources
+        .iter()
+        .map(|(src, mime_type)| {
+            if mime_type.is_empty() {
+                format!("<source src=\"{}\" />", escape_attr(src))
+            } else {
+                format!(
+                    "<source type=\"{}\" src=\"{}\" />",
+                    escape_attr(mime_type),
+                    escape_attr(src)
+                )
+            }
+        })
+        .collect()
+}
+
+fn render_audio(shortcode: &Shortcode, _: &str, _: &RenderContext) -> String {
+    let sources = media_sources(
+        shortcode,
+        &[
+            ("mp3", "audio/mpeg"),
+            ("m4a", "audio/mpeg"),
+            ("ogg", "audio/ogg"),
+            ("wav", "audio/wav"),
+            ("wma", "audio/x-ms-wma"),
+            ("flac", "audio/flac"),
+        ],
+    );
+
+    let Some((first, _)) = sources.first() else {
+        return String::new();
+    };
+
+    format!(
+        "<audio class=\"wp-audio-shortcode\" preload=\"{}\" style=\"width: 100%;\" controls=\"controls\"{}>{}<a href=\"{}\">{}</a></audio>",
+        escape_attr(shortcode.attr("preload").unwrap_or("none")),
+        boolean_attributes(shortcode, &["loop", "autoplay"]),
+        source_tags(&sources),
+        escape_attr(first),
+        escape_attr(first)
+    )
+}
+
+fn render_video(shortcode: &Shortcode, _: &str, _: &RenderContext) -> String {
+    let sources = media_sources(
+        shortcode,
+        &[
+            ("mp4", "video/mp4"),
+            ("m4v", "video/mp4"),
+            ("webm", "video/webm"),
+            ("ogv", "video/ogg"),
+            ("wmv", "video/x-ms-wmv"),
+            ("flv", "video/x-flv"),
+            ("mov", "video/quicktime"),
+        ],
+    );
+
+    let Some((first, _)) = sources.first() else {
+        return String::new();
+    };
+
+    let width = shortcode
+        .attr("width")
+        .and_then(|width| width.trim().parse::<u32>().ok())
+        .unwrap_or(640);
+    let height = shortcode
+        .attr("height")
+        .and_then(|height| height.trim().parse::<u32>().ok())
+        .unwrap_or(360);
+
+    let poster = shortcode
+        .attr("poster")
+        .map(|poster| format!(" poster=\"{}\"", escape_attr(poster)))
+        .unwrap_or_default();
+
+    format!(
+        "<div style=\"width: {}px;\" class=\"wp-video\"><video class=\"wp-video-shortcode\" width=\"{}\" height=\"{}\"{} preload=\"{}\" controls=\"controls\"{}>{}<a href=\"{}\">{}</a></video></div>",
+        width,
+        width,
+        height,
+        poster,
+        escape_attr(shortcode.attr("preload").unwrap_or("metadata")),
+        boolean_attributes(shortcode, &["loop", "autoplay", "muted"]),
+        source_tags(&sources),
+        escape_attr(first),
+        escape_attr(first)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(value: &str) -> Node {
+        Node::Text(value.to_string())
+    }
+
+    fn shortcode(name: &str, attrs: &[(&str, &str)], content: Option<Vec<Node>>) -> Node {
+        Node::Shortcode(Shortcode {
+            name: name.to_string(),
+            attrs: attrs
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect(),
+            content,
+        })
+    }
+
+    #[test]
+    fn parses_self_closing_shortcodes() {
+        assert_eq!(
+            parse(r#"a [gallery ids="1,2" size=large /] b"#),
+            vec![
+                text("a "),
+                shortcode("gallery", &[("ids", "1,2"), ("size", "large")], None),
+                text(" b"),
+            ]
+        );
+        assert_eq!(parse("[embed]"), vec![shortcode("embed", &[], None)]);
+    }
+
+    #[test]
+    fn parses_enclosing_shortcodes() {
+        assert_eq!(
+            parse(r#"[caption id="x" 'left']<img /> Text[/caption]"#),
+            vec![shortcode(
+                "caption",
+                &[("id", "x"), ("0", "left")],
+                Some(vec![text("<img /> Text")])
+            )]
+        );
+        assert_eq!(
+            parse("[video]a[/audio][/video]"),
+            vec![shortcode("video", &[], Some(vec![text("a[/audio]")]))]
+        );
+    }
+
+    #[test]
+    fn parses_nested_shortcodes() {
+        assert_eq!(
+            parse("[a][b x=1]in[/b][c/][/a]"),
+            vec![shortcode(
+                "a",
+                &[],
+                Some(vec![
+                    shortcode("b", &[("x", "1")], Some(vec![text("in")])),
+                    shortcode("c", &[], None),
+                ])
+            )]
+        );
+        // An enclosing shortcode runs to the first closing tag with its name.
+        assert_eq!(
+            parse("[a][a]x[/a][/a]"),
+            vec![
+                shortcode("a", &[], Some(vec![shortcode("a", &[], None), text("x")])),
+                text("[/a]"),
+            ]
+        );
+    }
+
+    #[test]
+    fn keeps_escaped_and_invalid_shortcodes_as_text() {
+        assert_eq!(parse("[[x]]"), vec![text("[x]")]);
+        assert_eq!(parse("[[x]y[/x]]"), vec![text("[x]y[/x]")]);
+        assert_eq!(parse("[[x]"), vec![text("["), shortcode("x", &[], None)]);
+        assert_eq!(
+            parse("[1] [a [b]"),
+            vec![text("[1] [a "), shortcode("b", &[], None)]
+        );
+    }
+
+    #[test]
+    fn parses_repeated_unclosed_tags_in_linear_time() {
+        let content = format!("{}[/a]", "[a]".repeat(50_000));
+        let started = std::time::Instant::now();
+        let nodes = parse(&content);
+        assert!(started.elapsed().as_secs() < 2);
+        assert_eq!(nodes.len(), 1);
+    }
+}